@@ -0,0 +1,408 @@
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use walkdir::WalkDir;
+
+use crate::report::{LanguageReport, LanguageStatus, ProjectReport};
+use crate::rust::project::Options;
+
+// 一种语言的分析器。新增语言时实现该 trait 并在 Registry::builtin 中注册
+pub trait Analyzer {
+    // 命令行中使用的语言名称
+    fn name(&self) -> &str;
+
+    // 语言名称的别名，例如 javascript 的 js
+    fn aliases(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    // 该语言源文件的扩展名，不含点
+    fn extensions(&self) -> Vec<&str>;
+
+    // 没有已知扩展名的文件按 shebang 中的解释器识别，例如 python3、node
+    fn interpreters(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    // 检查运行分析器所需的工具链是否可用
+    fn check_available(&self) -> Result<()>;
+
+    fn analyze(&self, directory: &Path, options: &Options) -> Result<ProjectReport>;
+}
+
+// 已注册的分析器，按注册顺序排列
+pub struct Registry {
+    analyzers: Vec<Box<dyn Analyzer>>,
+}
+
+impl Registry {
+    // 内置的各语言分析器
+    pub fn builtin() -> Self {
+        let analyzers: Vec<Box<dyn Analyzer>> = vec![
+            Box::new(RustAnalyzer),
+            Box::new(ScriptAnalyzer {
+                name: "python",
+                aliases: &[],
+                extensions: &["py"],
+                interpreters: &["python", "python3"],
+                interpreter: "python3",
+                script: "pythonAPI.py",
+            }),
+            Box::new(ScriptAnalyzer {
+                name: "javascript",
+                aliases: &["js", "typescript", "ts"],
+                extensions: &["js", "jsx", "ts", "tsx"],
+                interpreters: &["node"],
+                interpreter: "node",
+                script: "javascriptAPI.js",
+            }),
+            Box::new(JavaAnalyzer),
+            Box::new(CompiledAnalyzer {
+                name: "c",
+                aliases: &[],
+                extensions: &["c", "h"],
+                compiler: "gcc",
+                source: "cAPI.c",
+            }),
+            Box::new(CompiledAnalyzer {
+                name: "cpp",
+                aliases: &["c++"],
+                extensions: &["cpp", "cc", "cxx", "hpp", "hh", "hxx"],
+                compiler: "g++",
+                source: "cppAPI.cpp",
+            }),
+        ];
+        Registry { analyzers }
+    }
+
+    // 名称或别名与已注册的分析器冲突时返回错误
+    pub fn register(&mut self, analyzer: Box<dyn Analyzer>) -> Result<()> {
+        let names: Vec<String> = std::iter::once(analyzer.name())
+            .chain(analyzer.aliases())
+            .map(str::to_string)
+            .collect();
+        if let Some(name) = names.iter().find(|name| self.get(name).is_some()) {
+            bail!("语言 {} 已经注册", name);
+        }
+        self.analyzers.push(analyzer);
+        Ok(())
+    }
+
+    // 按名称或别名查找分析器
+    pub fn get(&self, name: &str) -> Option<&dyn Analyzer> {
+        self.iter().find(|analyzer| analyzer.name() == name || analyzer.aliases().contains(&name))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Analyzer> {
+        self.analyzers.iter().map(|analyzer| analyzer.as_ref())
+    }
+
+    // 命令行中可以使用的所有名称和别名
+    pub fn language_names(&self) -> Vec<&str> {
+        self.iter()
+            .flat_map(|analyzer| std::iter::once(analyzer.name()).chain(analyzer.aliases()))
+            .collect()
+    }
+
+    // 按扩展名检测目录中各文件的语言，没有已知扩展名的文件再看 shebang。
+    // 多个分析器声明同一扩展名时（例如 c 和 cpp 的 .h）归先注册的一个。返回语言名称到相对路径的映射
+    pub fn detect(&self, directory: &Path, options: &Options) -> BTreeMap<String, Vec<String>> {
+        let mut detected: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let files = WalkDir::new(directory)
+            .max_depth(options.max_depth.unwrap_or(usize::MAX))
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| !(e.file_type().is_dir() && options.ignore_dirs.iter().any(|d| e.file_name() == d.as_str())))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file());
+        for entry in files {
+            let path = entry.path();
+            let by_extension = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(|ext| self.iter().find(|analyzer| analyzer.extensions().contains(&ext)));
+            let analyzer = by_extension.or_else(|| {
+                let interpreter = shebang_interpreter(path)?;
+                self.iter().find(|analyzer| {
+                    analyzer.interpreters().iter().any(|name| {
+                        *name == interpreter || *name == interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.')
+                    })
+                })
+            });
+            if let (Some(analyzer), Ok(relative)) = (analyzer, path.strip_prefix(directory)) {
+                detected.entry(analyzer.name().to_string()).or_default().push(relative.display().to_string());
+            }
+        }
+        detected
+    }
+
+    // 分析混合语言的目录：对检测到的每种语言运行其分析器，只保留检测为该语言的文件，合并为一份报告。
+    // languages 不为空时只分析其中的语言。工具链不可用的语言记为 skipped，分析器执行失败的记为 failed
+    pub fn analyze_all(&self, directory: &Path, options: &Options, languages: &[&str]) -> Result<ProjectReport> {
+        self.analyze_all_with(directory, options, languages, |analyzer| analyzer.analyze(directory, options))
+    }
+
+    // 与 analyze_all 相同，但由 analyze 运行每种语言的分析器，调用方可以借此保留某种语言的完整分析结果
+    pub fn analyze_all_with(
+        &self,
+        directory: &Path,
+        options: &Options,
+        languages: &[&str],
+        mut analyze: impl FnMut(&dyn Analyzer) -> Result<ProjectReport>,
+    ) -> Result<ProjectReport> {
+        if !directory.is_dir() {
+            bail!("目录不存在: {}", directory.display());
+        }
+        let mut selected = Vec::new();
+        for name in languages {
+            match self.get(name) {
+                Some(analyzer) => selected.push(analyzer.name()),
+                None => bail!("不支持的语言: {}", name),
+            }
+        }
+        let detected = self.detect(directory, options);
+        let mut report = ProjectReport::new(Vec::new());
+        for analyzer in self.iter() {
+            let Some(paths) = detected.get(analyzer.name()) else {
+                continue;
+            };
+            if !selected.is_empty() && !selected.contains(&analyzer.name()) {
+                continue;
+            }
+            let mut language = LanguageReport {
+                language: analyzer.name().to_string(),
+                status: LanguageStatus::Analyzed,
+                detected_files: paths.len(),
+                files: 0,
+                functions: 0,
+                classes: 0,
+                reason: None,
+            };
+            let result = match analyzer.check_available() {
+                Ok(()) => analyze(analyzer).map_err(|e| (LanguageStatus::Failed, e)),
+                Err(e) => Err((LanguageStatus::Skipped, e)),
+            };
+            match result {
+                Ok(mut result) => {
+                    let paths: HashSet<&str> = paths.iter().map(String::as_str).collect();
+                    result.files.retain(|file| paths.contains(file.path.as_str()));
+                    for file in &mut result.files {
+                        file.language = Some(analyzer.name().to_string());
+                        language.functions += file.functions.len();
+                        language.classes += file.classes.len();
+                    }
+                    language.files = result.files.len();
+                    report.files.append(&mut result.files);
+                    report.diagnostics.append(&mut result.diagnostics);
+                    report.details.merge(result.details);
+                }
+                Err((status, e)) => {
+                    language.status = status;
+                    language.reason = Some(format!("{:#}", e));
+                }
+            }
+            report.languages.push(language);
+        }
+        Ok(report)
+    }
+}
+
+// Rust 分析器在本进程中运行
+struct RustAnalyzer;
+
+impl Analyzer for RustAnalyzer {
+    fn name(&self) -> &str {
+        "rust"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["rs"]
+    }
+
+    fn check_available(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn analyze(&self, directory: &Path, options: &Options) -> Result<ProjectReport> {
+        crate::analyze(directory, options)
+    }
+}
+
+// 由解释器运行的分析脚本，例如 python3 pythonAPI.py <目录>
+struct ScriptAnalyzer {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    extensions: &'static [&'static str],
+    // shebang 中可能出现的解释器名称
+    interpreters: &'static [&'static str],
+    interpreter: &'static str,
+    script: &'static str,
+}
+
+impl Analyzer for ScriptAnalyzer {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        self.aliases.to_vec()
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        self.extensions.to_vec()
+    }
+
+    fn interpreters(&self) -> Vec<&str> {
+        self.interpreters.to_vec()
+    }
+
+    fn check_available(&self) -> Result<()> {
+        check_tool(self.interpreter, "--version")?;
+        find_script(self.script).map(|_| ())
+    }
+
+    fn analyze(&self, directory: &Path, _options: &Options) -> Result<ProjectReport> {
+        // 脚本只接受目录一个参数
+        let mut command = Command::new(self.interpreter);
+        command.arg(find_script(self.script)?).arg(directory);
+        run_command(command, directory, self.name)
+    }
+}
+
+// 先编译再运行的分析器，例如 gcc cAPI.c
+struct CompiledAnalyzer {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    extensions: &'static [&'static str],
+    compiler: &'static str,
+    source: &'static str,
+}
+
+impl Analyzer for CompiledAnalyzer {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        self.aliases.to_vec()
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        self.extensions.to_vec()
+    }
+
+    fn check_available(&self) -> Result<()> {
+        check_tool(self.compiler, "--version")?;
+        find_script(self.source).map(|_| ())
+    }
+
+    fn analyze(&self, directory: &Path, _options: &Options) -> Result<ProjectReport> {
+        let source = find_script(self.source)?;
+        let stem = Path::new(self.source).file_stem().unwrap_or_default().to_string_lossy();
+        let output_path = env::temp_dir().join(format!("code_parser_{}{}", stem, env::consts::EXE_SUFFIX));
+
+        let status = Command::new(self.compiler).arg(&source).arg("-o").arg(&output_path).status()?;
+        if !status.success() {
+            bail!("{} 分析器编译失败，退出代码: {:?}", self.name, status.code());
+        }
+
+        // 分析器只接受目录一个参数
+        let mut command = Command::new(&output_path);
+        command.arg(directory);
+        run_command(command, directory, self.name)
+    }
+}
+
+// Java 分析器先用 javac 编译到临时目录，再用 java 运行
+struct JavaAnalyzer;
+
+impl Analyzer for JavaAnalyzer {
+    fn name(&self) -> &str {
+        "java"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["java"]
+    }
+
+    fn check_available(&self) -> Result<()> {
+        check_tool("javac", "-version")?;
+        check_tool("java", "-version")?;
+        find_script("JavaAPI.java").map(|_| ())
+    }
+
+    fn analyze(&self, directory: &Path, _options: &Options) -> Result<ProjectReport> {
+        let source = find_script("JavaAPI.java")?;
+        let output_dir = env::temp_dir().join("code_parser_java");
+        fs::create_dir_all(&output_dir)?;
+
+        let status = Command::new("javac").arg("-d").arg(&output_dir).arg(&source).status()?;
+        if !status.success() {
+            bail!("java 分析器编译失败，退出代码: {:?}", status.code());
+        }
+
+        // Java 分析器只接受目录一个参数
+        let mut command = Command::new("java");
+        command.arg("-cp").arg(&output_dir).arg("JavaAPI").arg(directory);
+        run_command(command, directory, "java")
+    }
+}
+
+// 文件第一行 shebang 中的解释器名称，例如 "#!/usr/bin/env python3" 为 python3
+fn shebang_interpreter(path: &Path) -> Option<String> {
+    let mut head = [0; 128];
+    let length = fs::File::open(path).and_then(|mut file| file.read(&mut head)).ok()?;
+    let first_line = String::from_utf8_lossy(&head[..length]).lines().next()?.to_string();
+    let mut words = first_line.strip_prefix("#!")?.split_whitespace();
+    let mut program = Path::new(words.next()?).file_name()?.to_str()?;
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-'))?;
+    }
+    Some(program.to_string())
+}
+
+// 工具能够运行即视为可用
+fn check_tool(program: &str, version_flag: &str) -> Result<()> {
+    let status = Command::new(program)
+        .arg(version_flag)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("未找到 {}", program))?;
+    if !status.success() {
+        bail!("{} {} 执行失败", program, version_flag);
+    }
+    Ok(())
+}
+
+// 依次在当前目录、src 目录和本项目的 src 目录中查找分析器脚本
+fn find_script(name: &str) -> Result<PathBuf> {
+    let candidates = [
+        PathBuf::from(name),
+        Path::new("src").join(name),
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join(name),
+    ];
+    match candidates.into_iter().find(|path| path.exists()) {
+        Some(path) => Ok(path),
+        None => bail!("未找到分析器脚本: {}", name),
+    }
+}
+
+// 运行分析器，把它在标准输出上打印的 JSON 校验并转换为统一的报告
+fn run_command(mut command: Command, directory: &Path, name: &str) -> Result<ProjectReport> {
+    let output = command
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("无法运行 {} 分析器", name))?;
+    if !output.status.success() {
+        bail!("{} 分析器执行失败，退出代码: {:?}", name, output.status.code());
+    }
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("{} 分析器的输出不是有效的 JSON", name))?;
+    ProjectReport::normalize(&value, directory).with_context(|| format!("{} 分析器的输出不符合报告格式", name))
+}
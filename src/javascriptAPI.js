@@ -19,6 +19,19 @@ const results = {
 	files: []
 };
 
+// 没有扩展名、shebang 指向 node 的脚本
+function isNodeScript(filePath) {
+	if (path.extname(filePath)) {
+		return false;
+	}
+	const fd = fs.openSync(filePath, 'r');
+	const head = Buffer.alloc(128);
+	const length = fs.readSync(fd, head, 0, head.length, 0);
+	fs.closeSync(fd);
+	const firstLine = head.toString('utf-8', 0, length).split('\n')[0];
+	return firstLine.startsWith('#!') && firstLine.includes('node');
+}
+
 // 递归遍历目录
 function walkDir(dir) {
 	const files = fs.readdirSync(dir);
@@ -29,7 +42,7 @@ function walkDir(dir) {
 
 		if (stat.isDirectory()) {
 			walkDir(filePath);
-		} else if (file.match(/\.(js|jsx|ts|tsx)$/)) {
+		} else if (file.match(/\.(js|jsx|ts|tsx)$/) || isNodeScript(filePath)) {
 			analyzeFile(filePath);
 		}
 	});
@@ -1 +1,47 @@
-pub mod test_utils; 
\ No newline at end of file
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+pub mod analyzer;
+pub mod plugin;
+pub mod report;
+pub mod rust;
+pub mod test_utils;
+
+pub use analyzer::{Analyzer, Registry};
+pub use plugin::{PluginAnalyzer, PluginConfig};
+pub use report::*;
+pub use rust::project::Options;
+
+use rust::project::ProjectAnalysis;
+use rust::report::RustReport;
+
+// 分析目录中的 Rust 代码，返回各语言通用格式的报告，details 中带有 Rust 的详细报告
+pub fn analyze(path: impl AsRef<Path>, options: &Options) -> Result<ProjectReport> {
+    let dir = path.as_ref();
+    let mut analysis = analyze_project(dir, options)?;
+    if options.public_only {
+        analysis.retain_exported();
+    }
+    Ok(rust::report::project_report(dir, &analysis))
+}
+
+// 分析目录中的 Rust 代码，返回带有可见性、签名、度量等信息的详细报告
+pub fn analyze_rust(path: impl AsRef<Path>, options: &Options) -> Result<RustReport> {
+    let dir = path.as_ref();
+    let mut analysis = analyze_project(dir, options)?;
+    if options.public_only {
+        analysis.retain_exported();
+    }
+    Ok(RustReport::from_analysis(dir, &analysis))
+}
+
+// 分析目录中的 Rust 代码，返回没有按 public_only 筛选的分析结果。
+// 文档覆盖率、调用图等附加输出需要完整的条目，由调用方从同一份结果得到报告和附加输出
+pub fn analyze_project(path: impl AsRef<Path>, options: &Options) -> Result<ProjectAnalysis> {
+    let dir = path.as_ref();
+    if !dir.is_dir() {
+        bail!("目录不存在: {}", dir.display());
+    }
+    Ok(ProjectAnalysis::analyze(dir, options))
+}
@@ -1,12 +1,18 @@
+use std::env;
 use std::error::Error;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus};
 use std::fs;
 use serde::{Deserialize, Serialize};
-use clap::{App, Arg, SubCommand};
-
-// 支持的语言列表
-const SUPPORTED_LANGUAGES: &[&str] = &["rust", "python", "javascript", "java", "c", "cpp"];
+use clap::{App, Arg, ArgMatches, SubCommand};
+use code_parser::rust::coverage::DocCoverage;
+use code_parser::rust::log;
+use code_parser::rust::metrics::{self, MetricKey};
+use code_parser::rust::project::{feature_matrix, ProjectAnalysis};
+use code_parser::rust::report::{project_report, RustReport};
+use code_parser::{
+    Analyzer, LanguageStatus, Options, PluginAnalyzer, PluginConfig, ProjectReport, RankingReport, Registry,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Config {
@@ -15,16 +21,20 @@ struct Config {
     max_depth: i32,
     log_level: String,
     output_dir: String,
+    // 外部分析器插件，通过标准输入输出上的 JSON 消息通信
+    #[serde(default)]
+    plugins: Vec<PluginConfig>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             ignore_dirs: vec!["tests".to_string(), "node_modules".to_string(), "target".to_string(), "venv".to_string(), "__pycache__".to_string()],
-            output_format: "text".to_string(),
+            output_format: "json".to_string(),
             max_depth: 5,
             log_level: "info".to_string(),
-            output_dir: "~/.parser/output".to_string(),
+            output_dir: log::DEFAULT_OUTPUT_DIR.to_string(),
+            plugins: Vec::new(),
         }
     }
 }
@@ -44,17 +54,28 @@ fn load_config() -> Config {
 
 fn main() -> Result<(), Box<dyn Error>> {
     let mut config = load_config();
-    
+    // 命令行可选的语言、支持的语言列表和分派都来自已注册的分析器
+    let mut registry = Registry::builtin();
+    for plugin in &config.plugins {
+        if let Err(e) = registry.register(Box::new(PluginAnalyzer::new(plugin.clone()))) {
+            eprintln!("忽略插件 {}: {}", plugin.name, e);
+        }
+    }
+    let languages = registry.language_names();
+
     let matches = App::new("代码解析工具集")
         .version("1.1.0")
         .author("Your Name")
         .about("分析多种编程语言项目的代码结构")
         .subcommand(SubCommand::with_name("analyze")
             .about("分析指定目录的代码")
-            .arg(Arg::with_name("language")
-                .help("要分析的语言")
-                .required(true)
-                .possible_values(SUPPORTED_LANGUAGES))
+            .arg(Arg::with_name("lang")
+                .help("要分析的语言，可多次指定。不指定时按扩展名和 shebang 检测目录中的各种语言")
+                .long("lang")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .possible_values(&languages))
             .arg(Arg::with_name("directory")
                 .help("要分析的目录路径")
                 .required(true))
@@ -70,11 +91,88 @@ fn main() -> Result<(), Box<dyn Error>> {
             .arg(Arg::with_name("format")
                 .help("输出格式 (text/json/html)")
                 .long("format")
+                .takes_value(true)
                 .possible_values(&["text", "json", "html"]))
             .arg(Arg::with_name("max-depth")
                 .help("最大递归深度")
                 .long("max-depth")
-                .takes_value(true)))
+                .takes_value(true))
+            .arg(Arg::with_name("public-only")
+                .help("只输出下游可以访问的公开 API（仅 Rust）")
+                .long("public-only"))
+            .arg(Arg::with_name("strict")
+                .help("有文件无法解析或有分析器执行失败时以非零状态退出")
+                .long("strict"))
+            .arg(Arg::with_name("doc-coverage")
+                .help("统计公开条目的文档覆盖率（仅 Rust）")
+                .long("doc-coverage"))
+            .arg(Arg::with_name("features")
+                .help("启用的 Cargo 特性，用逗号分隔（仅 Rust）")
+                .long("features")
+                .takes_value(true))
+            .arg(Arg::with_name("no-default-features")
+                .help("不启用 default 特性（仅 Rust）")
+                .long("no-default-features"))
+            .arg(Arg::with_name("cfg")
+                .help("视为成立的 cfg 选项，例如 unix 或 target_os=\"linux\"，可多次指定，未给出的条件按未知处理，test 和 doc 按不成立处理（仅 Rust）")
+                .long("cfg")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1))
+            .arg(Arg::with_name("feature-matrix")
+                .help("列出每个 Cargo 特性新增的条目（仅 Rust）")
+                .long("feature-matrix"))
+            .arg(Arg::with_name("trait-matrix")
+                .help("输出 trait 实现矩阵 (json/markdown/html)（仅 Rust）")
+                .long("trait-matrix")
+                .takes_value(true)
+                .possible_values(&["json", "markdown", "html"]))
+            .arg(Arg::with_name("metrics")
+                .help("输出函数的复杂度和规模度量（仅 Rust）")
+                .long("metrics"))
+            .arg(Arg::with_name("sort-by")
+                .help("按某项度量列出最复杂的函数 (complexity/cognitive/nesting/params/loc/statements)（仅 Rust）")
+                .long("sort-by")
+                .takes_value(true)
+                .possible_values(&["complexity", "cognitive", "nesting", "params", "loc", "statements"]))
+            .arg(Arg::with_name("top")
+                .help("--sort-by 列出的函数个数，默认为 10（仅 Rust）")
+                .long("top")
+                .takes_value(true))
+            .arg(Arg::with_name("call-graph")
+                .help("输出调用图 (json/dot/mermaid)（仅 Rust）")
+                .long("call-graph")
+                .takes_value(true)
+                .possible_values(&["json", "dot", "mermaid"])))
+        .subcommand(SubCommand::with_name("audit")
+            .about("审计 Rust 项目中的代码")
+            .arg(Arg::with_name("check")
+                .help("审计的内容：unsafe 列出所有 unsafe 代码，panic 列出非测试代码中可能 panic 的位置")
+                .required(true)
+                .possible_values(&["unsafe", "panic"]))
+            .arg(Arg::with_name("directory")
+                .help("要审计的目录路径")
+                .required(true))
+            .arg(Arg::with_name("ignore-dirs")
+                .help("要忽略的目录，用逗号分隔")
+                .long("ignore-dirs")
+                .takes_value(true))
+            .arg(Arg::with_name("module")
+                .help("只报告该模块（含子模块）中的函数，例如 crate::net（仅 panic）")
+                .long("module")
+                .takes_value(true))
+            .arg(Arg::with_name("baseline")
+                .help("基线文件，与之相比有新增的 unsafe 代码时以非零状态退出（仅 unsafe）")
+                .long("baseline")
+                .takes_value(true))
+            .arg(Arg::with_name("update-baseline")
+                .help("把本次审计结果写入基线文件")
+                .long("update-baseline")
+                .requires("baseline")))
+        .subcommand(SubCommand::with_name("languages")
+            .about("列出支持的语言及其工具链是否可用"))
+        .subcommand(SubCommand::with_name("schema")
+            .about("输出分析报告的 JSON Schema"))
         .subcommand(SubCommand::with_name("config")
             .about("配置工具")
             .arg(Arg::with_name("show")
@@ -88,7 +186,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     match matches.subcommand() {
         ("analyze", Some(analyze_matches)) => {
-            let language = analyze_matches.value_of("language").unwrap();
+            let selected: Vec<&str> = analyze_matches.values_of("lang").into_iter().flatten().collect();
             let directory = analyze_matches.value_of("directory").unwrap();
             
             // 处理命令行参数
@@ -106,20 +204,90 @@ fn main() -> Result<(), Box<dyn Error>> {
                 config.output_format = format.to_string();
             }
             
-            // 根据语言选择并运行对应的解析器
-            match language {
-                "python" => run_python_parser(directory, &config)?,
-                "rust" => run_rust_parser(directory, &config)?,
-                "javascript" | "js" => run_javascript_parser(directory, &config)?,
-                "java" => run_java_parser(directory, &config)?,
-                "c" => run_c_parser(directory, &config)?,
-                "cpp" | "c++" => run_cpp_parser(directory, &config)?,
+            if let Some(language) = selected.iter().find(|language| registry.get(language).is_none()) {
+                eprintln!("不支持的语言: {}", language);
+                print_supported_languages(&registry);
+                return Err(format!("不支持的语言: {}", language).into());
+            }
+            let options = analyze_options(&config, analyze_matches);
+            let dir = Path::new(directory);
+            // Rust 代码只分析一次，报告、排名和附加输出都来自同一份结果。
+            // 保存完整的结果和按 --public-only 筛选后的结果
+            let mut rust_analysis: Option<(ProjectAnalysis, Option<ProjectAnalysis>)> = None;
+            let mut analyze = |analyzer: &dyn Analyzer| -> anyhow::Result<ProjectReport> {
+                if analyzer.name() != "rust" {
+                    return analyzer.analyze(dir, &options);
+                }
+                let analysis = code_parser::analyze_project(dir, &options)?;
+                let exported = options.public_only.then(|| {
+                    let mut exported = analysis.clone();
+                    exported.retain_exported();
+                    exported
+                });
+                let report = project_report(dir, exported.as_ref().unwrap_or(&analysis));
+                rust_analysis = Some((analysis, exported));
+                Ok(report)
+            };
+            let mut report = match selected.as_slice() {
+                // 只指定一种语言时直接运行对应的分析器，工具链不可用时报错
+                [language] => {
+                    let analyzer = registry.get(language).unwrap();
+                    analyzer
+                        .check_available()
+                        .map_err(|e| format!("无法运行 {} 分析器: {}", analyzer.name(), e))?;
+                    eprintln!("运行 {} 代码分析器...", analyzer.name());
+                    let report = analyze(analyzer)?;
+                    eprintln!("{} 分析器成功完成！", analyzer.name());
+                    report
+                }
+                // 否则分析检测到的各种语言并合并，工具链不可用的语言跳过
                 _ => {
-                    eprintln!("不支持的语言: {}", language);
-                    print_supported_languages();
-                    return Err(format!("不支持的语言: {}", language).into());
+                    eprintln!("检测目录中的语言...");
+                    let report = registry.analyze_all_with(dir, &options, &selected, &mut analyze)?;
+                    for language in &report.languages {
+                        match &language.reason {
+                            Some(reason) => eprintln!("{} {}: {}", language.language, language.status.keyword(), reason),
+                            None => eprintln!("{} {}: {} 个文件", language.language, language.status.keyword(), language.files),
+                        }
+                    }
+                    report
+                }
+            };
+            if analyze_matches.is_present("strict") {
+                if !report.diagnostics.is_empty() {
+                    return Err(format!("{} 个文件无法解析（--strict）", report.diagnostics.len()).into());
+                }
+                if let Some(failed) = report.languages.iter().find(|l| l.status == LanguageStatus::Failed) {
+                    return Err(format!("{} 分析器执行失败（--strict）", failed.language).into());
                 }
             }
+            if let Some(details) = &report.details.rust {
+                report.ranking = ranking(details, analyze_matches)?;
+            }
+            if let Some((analysis, exported)) = &rust_analysis {
+                write_rust_extras(analysis, exported.as_ref().unwrap_or(analysis), &options, &config, analyze_matches)?;
+            }
+            // 报告写到标准输出，进度信息写到标准错误
+            match config.output_format.as_str() {
+                "text" => print!("{}", report.to_text()),
+                "html" => print!("{}", report.to_html()),
+                _ => println!("{}", report.to_json()?),
+            }
+        }
+        ("languages", Some(_)) => {
+            print_supported_languages(&registry);
+        }
+        ("schema", Some(_)) => {
+            println!("{}", serde_json::to_string_pretty(&code_parser::json_schema())?);
+        }
+        ("audit", Some(audit_matches)) => {
+            let check = audit_matches.value_of("check").unwrap();
+            let directory = audit_matches.value_of("directory").unwrap();
+            let mut config = config;
+            if let Some(ignore_dirs) = audit_matches.value_of("ignore-dirs") {
+                config.ignore_dirs = ignore_dirs.split(',').map(String::from).collect();
+            }
+            run_rust_audit(check, directory, &config, audit_matches)?;
         }
         ("config", Some(config_matches)) => {
             if config_matches.is_present("show") {
@@ -166,208 +334,163 @@ fn save_config(config: &Config) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn print_supported_languages() {
-    eprintln!("支持的语言:");
-    for lang in SUPPORTED_LANGUAGES {
-        eprintln!("  - {}", lang);
+fn print_supported_languages(registry: &Registry) {
+    println!("支持的语言:");
+    for analyzer in registry.iter() {
+        let mut names = vec![analyzer.name()];
+        names.extend(analyzer.aliases());
+        let extensions: Vec<String> = analyzer.extensions().iter().map(|ext| format!(".{}", ext)).collect();
+        let status = match analyzer.check_available() {
+            Ok(()) => "可用".to_string(),
+            Err(e) => format!("不可用: {}", e),
+        };
+        println!("  - {} ({}) [{}]", names.join(" / "), extensions.join(" "), status);
     }
 }
 
-fn run_python_parser(directory: &str, config: &Config) -> Result<(), Box<dyn Error>> {
-    println!("运行Python代码解析器...");
-
-    let parser_path = Path::new("pythonAPI.py");
-    if !parser_path.exists() {
-        return Err(format!("未找到Python解析器脚本: {}", parser_path.display()).into());
-    }
-
-    let mut command = Command::new("python3");
-    command.arg(parser_path)
-           .arg(directory)
-           .arg("--format")
-           .arg(&config.output_format)
-           .arg("--max-depth")
-           .arg(config.max_depth.to_string());
-
-    for dir in &config.ignore_dirs {
-        command.arg("--ignore-dir").arg(dir);
+// 由配置和命令行参数得到分析选项
+fn analyze_options(config: &Config, matches: &ArgMatches) -> Options {
+    Options {
+        ignore_dirs: config.ignore_dirs.clone(),
+        max_depth: usize::try_from(config.max_depth).ok(),
+        features: matches
+            .value_of("features")
+            .into_iter()
+            .flat_map(|list| list.split([',', ' ']))
+            .filter(|feature| !feature.is_empty())
+            .map(str::to_string)
+            .collect(),
+        no_default_features: matches.is_present("no-default-features"),
+        cfgs: matches.values_of("cfg").into_iter().flatten().map(str::to_string).collect(),
+        public_only: matches.is_present("public-only"),
     }
+}
 
-    let status = command.status()?;
-    check_status(status, "Python解析器")
+// --sort-by / --top 的排名放进报告，只给出 --top 时按圈复杂度排序
+fn ranking(details: &RustReport, matches: &ArgMatches) -> Result<Option<RankingReport>, Box<dyn Error>> {
+    let key = match (matches.value_of("sort-by"), matches.is_present("top")) {
+        (Some(key), _) => MetricKey::from_keyword(key).ok_or_else(|| format!("未知的度量: {}", key))?,
+        (None, true) => MetricKey::Complexity,
+        (None, false) => return Ok(None),
+    };
+    let top = match matches.value_of("top") {
+        Some(top) => top.parse().map_err(|_| format!("无效的函数个数: {}", top))?,
+        None => 10,
+    };
+    Ok(Some(details.ranking(key, top)))
 }
 
-fn run_rust_parser(directory: &str, config: &Config) -> Result<(), Box<dyn Error>> {
-    println!("运行Rust代码解析器...");
+// 输出目录，开头的 ~ 换成主目录
+fn output_dir(config: &Config) -> PathBuf {
+    log::expand_home(&config.output_dir)
+}
 
-    let parser_path = Path::new("rustAPI.rs");
-    if !parser_path.exists() {
-        return Err(format!("未找到Rust解析器脚本: {}", parser_path.display()).into());
+// 文档覆盖率、度量、调用图等写入输出目录，不写入分析的目录。
+// 度量按 --public-only 筛选后的函数 selected 计算，其余在筛选之前计算
+fn write_rust_extras(
+    analysis: &ProjectAnalysis,
+    selected: &ProjectAnalysis,
+    options: &Options,
+    config: &Config,
+    matches: &ArgMatches,
+) -> Result<(), Box<dyn Error>> {
+    let extras = ["doc-coverage", "feature-matrix", "metrics", "call-graph", "trait-matrix"];
+    if !extras.iter().any(|extra| matches.is_present(extra)) {
+        return Ok(());
     }
-
-    println!("编译Rust解析器...");
-    let output_path = "rustAPI";
-
-    let compile_status = Command::new("rustc")
-        .arg(parser_path)
-        .arg("-o")
-        .arg(output_path)
-        .status()?;
-
-    if !compile_status.success() {
-        return Err(format!("Rust解析器编译失败，退出代码: {:?}", compile_status.code()).into());
+    let output = output_dir(config);
+    fs::create_dir_all(&output)?;
+    if let Some(format) = matches.value_of("call-graph") {
+        eprintln!("{}", log::write_call_graph(&output, &analysis.crates, format)?);
     }
-
-    println!("执行Rust解析器...");
-    let mut command = Command::new(output_path);
-    command.arg(directory)
-           .arg("--format")
-           .arg(&config.output_format)
-           .arg("--max-depth")
-           .arg(config.max_depth.to_string());
-
-    for dir in &config.ignore_dirs {
-        command.arg("--ignore-dir").arg(dir);
+    if let Some(format) = matches.value_of("trait-matrix") {
+        eprintln!("{}", log::write_trait_matrix(&output, &analysis.crates, format)?);
     }
-
-    let status = command.status()?;
-    check_status(status, "Rust解析器")
-}
-
-fn run_javascript_parser(directory: &str, config: &Config) -> Result<(), Box<dyn Error>> {
-    println!("运行JavaScript代码解析器...");
-
-    let parser_path = Path::new("javascriptAPI.js");
-    if !parser_path.exists() {
-        return Err(format!("未找到JavaScript解析器脚本: {}", parser_path.display()).into());
+    if !["doc-coverage", "feature-matrix", "metrics"].iter().any(|extra| matches.is_present(extra)) {
+        return Ok(());
     }
 
-    let mut command = Command::new("node");
-    command.arg(parser_path)
-           .arg(directory)
-           .arg("--format")
-           .arg(&config.output_format)
-           .arg("--max-depth")
-           .arg(config.max_depth.to_string());
-
-    for dir in &config.ignore_dirs {
-        command.arg("--ignore-dir").arg(dir);
-    }
-
-    let status = command.status()?;
-    check_status(status, "JavaScript解析器")
-}
-
-fn run_java_parser(directory: &str, config: &Config) -> Result<(), Box<dyn Error>> {
-    println!("运行Java代码解析器...");
-
-    let parser_path = Path::new("javaAPI.java");
-    if !parser_path.exists() {
-        return Err(format!("未找到Java解析器脚本: {}", parser_path.display()).into());
+    let log_path = output.join("fns_log");
+    let mut log_file = fs::File::create(&log_path)?;
+    if matches.is_present("metrics") {
+        for c in &selected.crates {
+            if c.files.iter().any(|items| metrics::measured_fns(items).next().is_some()) {
+                log::write_metrics(&mut log_file, &c.root, &c.files)?;
+            }
+        }
     }
-
-    println!("编译Java解析器...");
-    let compile_status = Command::new("javac").arg(parser_path).status()?;
-
-    if !compile_status.success() {
-        return Err(format!("Java解析器编译失败，退出代码: {:?}", compile_status.code()).into());
+    if matches.is_present("feature-matrix") {
+        for c in &analysis.crates {
+            if let Some(table) = &c.manifest {
+                let matrix = feature_matrix(&c.root, table, &options.cfgs);
+                log::write_feature_matrix(&mut log_file, &c.root, &matrix)?;
+                eprintln!("特性矩阵 {}: {} 个特性", c.root.display(), matrix.len());
+            }
+        }
     }
-
-    println!("执行Java解析器...");
-    let mut command = Command::new("java");
-    command.current_dir("parsers")
-           .arg("JavaAPI")
-           .arg(directory)
-           .arg("--format")
-           .arg(&config.output_format)
-           .arg("--max-depth")
-           .arg(config.max_depth.to_string());
-
-    for dir in &config.ignore_dirs {
-        command.arg("--ignore-dir").arg(dir);
+    if matches.is_present("doc-coverage") {
+        for c in &analysis.crates {
+            let coverage = DocCoverage::compute(&c.files);
+            if coverage.total() == 0 {
+                continue;
+            }
+            log::write_coverage(&mut log_file, &c.root, &coverage)?;
+            eprintln!(
+                "文档覆盖率 {}: {}/{} ({:.1}%)",
+                c.root.display(),
+                coverage.documented(),
+                coverage.total(),
+                coverage.percent()
+            );
+        }
     }
-
-    let status = command.status()?;
-    check_status(status, "Java解析器")
+    eprintln!("Rust 附加结果已写入到 {}", log_path.display());
+    Ok(())
 }
 
-fn run_c_parser(directory: &str, config: &Config) -> Result<(), Box<dyn Error>> {
-    println!("运行C代码解析器...");
+fn run_rust_audit(check: &str, directory: &str, config: &Config, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    println!("运行Rust {} 审计...", check);
 
-    let parser_path = Path::new("cAPI.c");
-    if !parser_path.exists() {
-        return Err(format!("未找到C解析器脚本: {}", parser_path.display()).into());
-    }
-
-    println!("编译C解析器...");
-    let output_path = "cAPI";
-
-    let compile_status = Command::new("gcc")
-        .arg(parser_path)
-        .arg("-o")
-        .arg(output_path)
-        .status()?;
-
-    if !compile_status.success() {
-        return Err(format!("C解析器编译失败，退出代码: {:?}", compile_status.code()).into());
+    let output = output_dir(config);
+    fs::create_dir_all(&output)?;
+    let mut command = rust_parser_command(directory, config)?;
+    command.arg(output.join("fns_log")).arg("--audit").arg(check);
+    for option in ["module", "baseline"] {
+        if let Some(value) = matches.value_of(option) {
+            command.arg(format!("--{}", option)).arg(value);
+        }
     }
-
-    println!("执行C解析器...");
-    let mut command = Command::new(output_path);
-    command.arg(directory)
-           .arg("--format")
-           .arg(&config.output_format)
-           .arg("--max-depth")
-           .arg(config.max_depth.to_string());
-
-    for dir in &config.ignore_dirs {
-        command.arg("--ignore-dir").arg(dir);
+    if matches.is_present("update-baseline") {
+        command.arg("--update-baseline");
     }
 
     let status = command.status()?;
-    check_status(status, "C解析器")
+    check_status(status, "Rust审计")
 }
 
-fn run_cpp_parser(directory: &str, config: &Config) -> Result<(), Box<dyn Error>> {
-    println!("运行C++代码解析器...");
-
-    let parser_path = Path::new("cppAPI.cpp");
+// Rust 解析器与本程序一起构建，返回带有通用参数的命令
+fn rust_parser_command(directory: &str, config: &Config) -> Result<Command, Box<dyn Error>> {
+    let exe = env::current_exe()?;
+    let parser_path = exe.with_file_name(format!("rustAPI{}", env::consts::EXE_SUFFIX));
     if !parser_path.exists() {
-        return Err(format!("未找到C++解析器脚本: {}", parser_path.display()).into());
+        return Err(format!("未找到Rust解析器: {}，请先运行 cargo build", parser_path.display()).into());
     }
 
-    println!("编译C++解析器...");
-    let output_path = "cppAPI";
-
-    let compile_status = Command::new("g++")
-        .arg(parser_path)
-        .arg("-o")
-        .arg(output_path)
-        .status()?;
-
-    if !compile_status.success() {
-        return Err(format!("C++解析器编译失败，退出代码: {:?}", compile_status.code()).into());
-    }
-
-    println!("执行C++解析器...");
-    let mut command = Command::new(output_path);
+    eprintln!("执行Rust解析器...");
+    let mut command = Command::new(parser_path);
     command.arg(directory)
-           .arg("--format")
-           .arg(&config.output_format)
            .arg("--max-depth")
            .arg(config.max_depth.to_string());
 
     for dir in &config.ignore_dirs {
         command.arg("--ignore-dir").arg(dir);
     }
-
-    let status = command.status()?;
-    check_status(status, "C++解析器")
+    Ok(command)
 }
 
 fn check_status(status: ExitStatus, parser_name: &str) -> Result<(), Box<dyn Error>> {
     if status.success() {
-        println!("{}成功完成！", parser_name);
+        eprintln!("{}成功完成！", parser_name);
         Ok(())
     } else {
         Err(format!("{}执行失败，退出代码: {:?}", parser_name, status.code()).into())
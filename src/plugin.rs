@@ -0,0 +1,225 @@
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::analyzer::Analyzer;
+use crate::report::{ProjectReport, SCHEMA_VERSION};
+use crate::rust::project::Options;
+
+// 插件协议的版本，不兼容的修改时加一
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// 插件声明的能力：按请求中的 ignore_dirs / max_depth 跳过目录。没有声明时由本工具过滤插件返回的文件
+pub const CAPABILITY_IGNORE_DIRS: &str = "ignore_dirs";
+pub const CAPABILITY_MAX_DEPTH: &str = "max_depth";
+
+// 配置文件 plugins 中的一项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginConfig {
+    // 命令行中使用的语言名称
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    // 该语言源文件的扩展名，不含点
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    // shebang 中可能出现的解释器名称
+    #[serde(default)]
+    pub interpreters: Vec<String>,
+    // 启动插件的程序及其参数
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+// 本工具写到插件标准输入的消息，每行一个 JSON 对象
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Request<'a> {
+    Handshake { protocol_version: u32, schema_version: &'a str },
+    Analyze { directory: &'a Path, options: &'a Options },
+}
+
+// 插件写到标准输出的消息，每行一个 JSON 对象
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Response {
+    Handshake {
+        protocol_version: u32,
+        #[serde(default)]
+        capabilities: Vec<String>,
+    },
+    // 一个文件的分析结果，格式与报告中 files 的一项相同
+    File { file: Value },
+    // 格式与报告中 diagnostics 的一项相同
+    Diagnostic { diagnostic: Value },
+    Error { message: String },
+    Done,
+}
+
+// 通过标准输入输出上的 JSON 消息与外部插件通信的分析器
+pub struct PluginAnalyzer {
+    config: PluginConfig,
+}
+
+impl PluginAnalyzer {
+    pub fn new(config: PluginConfig) -> Self {
+        PluginAnalyzer { config }
+    }
+}
+
+impl Analyzer for PluginAnalyzer {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        self.config.aliases.iter().map(String::as_str).collect()
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        self.config.extensions.iter().map(String::as_str).collect()
+    }
+
+    fn interpreters(&self) -> Vec<&str> {
+        self.config.interpreters.iter().map(String::as_str).collect()
+    }
+
+    // 能够完成握手即视为可用
+    fn check_available(&self) -> Result<()> {
+        let mut session = Session::start(&self.config)?;
+        let result = session.handshake().map(|_| ());
+        session.kill();
+        result
+    }
+
+    fn analyze(&self, directory: &Path, options: &Options) -> Result<ProjectReport> {
+        let mut session = Session::start(&self.config)?;
+        let result = session.analyze(directory, options);
+        if result.is_err() {
+            session.kill();
+            return result;
+        }
+        let status = session.child.wait()?;
+        if !status.success() {
+            bail!("插件 {} 执行失败，退出代码: {:?}", self.config.name, status.code());
+        }
+        result
+    }
+}
+
+// 一次运行中的插件进程
+struct Session<'a> {
+    config: &'a PluginConfig,
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl<'a> Session<'a> {
+    fn start(config: &'a PluginConfig) -> Result<Self> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("无法启动插件 {}: {}", config.name, config.command))?;
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(child.stdout.take().ok_or_else(|| anyhow!("无法读取插件的输出"))?);
+        Ok(Session { config, child, stdin, stdout })
+    }
+
+    fn send(&mut self, request: &Request) -> Result<()> {
+        let stdin = self.stdin.as_mut().ok_or_else(|| anyhow!("插件的输入已关闭"))?;
+        let line = serde_json::to_string(request)?;
+        writeln!(stdin, "{}", line)
+            .and_then(|_| stdin.flush())
+            .with_context(|| format!("无法向插件 {} 发送请求", self.config.name))
+    }
+
+    // 读取下一条消息，插件提前退出时返回错误
+    fn receive(&mut self) -> Result<Response> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.stdout.read_line(&mut line)? == 0 {
+                bail!("插件 {} 没有完成响应就退出了", self.config.name);
+            }
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+        let response = serde_json::from_str(&line)
+            .with_context(|| format!("插件 {} 的消息无效: {}", self.config.name, line.trim()))?;
+        match response {
+            Response::Error { message } => bail!("插件 {} 报告错误: {}", self.config.name, message),
+            response => Ok(response),
+        }
+    }
+
+    // 交换协议版本，返回插件声明的能力
+    fn handshake(&mut self) -> Result<Vec<String>> {
+        self.send(&Request::Handshake {
+            protocol_version: PROTOCOL_VERSION,
+            schema_version: SCHEMA_VERSION,
+        })?;
+        match self.receive()? {
+            Response::Handshake { protocol_version, capabilities } if protocol_version == PROTOCOL_VERSION => {
+                Ok(capabilities)
+            }
+            Response::Handshake { protocol_version, .. } => bail!(
+                "插件 {} 使用协议版本 {}，本工具支持版本 {}",
+                self.config.name,
+                protocol_version,
+                PROTOCOL_VERSION
+            ),
+            other => bail!("插件 {} 没有回应握手，而是发送了 {:?}", self.config.name, other),
+        }
+    }
+
+    // 发送分析请求，逐条读取插件流式返回的文件和诊断，直到 done
+    fn analyze(&mut self, directory: &Path, options: &Options) -> Result<ProjectReport> {
+        let capabilities = self.handshake()?;
+        self.send(&Request::Analyze { directory, options })?;
+        // 关闭输入，插件读到文件结束即可知道不会有更多请求
+        self.stdin = None;
+
+        let mut files = Vec::new();
+        let mut diagnostics = Vec::new();
+        loop {
+            match self.receive()? {
+                Response::File { file } => files.push(file),
+                Response::Diagnostic { diagnostic } => diagnostics.push(diagnostic),
+                Response::Done => break,
+                other => bail!("插件 {} 发送了意外的消息 {:?}", self.config.name, other),
+            }
+        }
+
+        let mut report = ProjectReport::normalize(&json!({"files": files, "diagnostics": diagnostics}), directory)
+            .with_context(|| format!("插件 {} 的输出不符合报告格式", self.config.name))?;
+        let supports = |capability: &str| capabilities.iter().any(|c| c == capability);
+        if !supports(CAPABILITY_IGNORE_DIRS) {
+            report.files.retain(|file| {
+                !Path::new(&file.path)
+                    .parent()
+                    .into_iter()
+                    .flat_map(Path::iter)
+                    .any(|dir| options.ignore_dirs.iter().any(|ignored| dir == ignored.as_str()))
+            });
+        }
+        if let (false, Some(max_depth)) = (supports(CAPABILITY_MAX_DEPTH), options.max_depth) {
+            report.files.retain(|file| Path::new(&file.path).components().count() <= max_depth);
+        }
+        Ok(report)
+    }
+
+    fn kill(mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -4,91 +4,131 @@
 import os
 import ast
 import sys
+import json
 from pathlib import Path
-from typing import List, Optional
-
-
-class FunctionVisitor(ast.NodeVisitor):
-    def __init__(self):
-        self.functions = []
-        self.current_class = None
-
-    def visit_FunctionDef(self, node):
-        if self.current_class:
-            # 如果在类内部，记录为方法，格式：类名.方法名
-            self.functions.append(f"{self.current_class}.{node.name}")
-        else:
-            # 否则记录为函数
-            self.functions.append(node.name)
-        # 递归访问函数体
-        self.generic_visit(node)
-
-    def visit_AsyncFunctionDef(self, node):
-        # 处理异步函数定义，与普通函数处理方式相同
-        self.visit_FunctionDef(node)
-
-    def visit_ClassDef(self, node):
-        old_class = self.current_class
-        self.current_class = node.name
-        # 递归访问类体
-        self.generic_visit(node)
-        self.current_class = old_class
-
-    def visit_Lambda(self, node):
-        # 匿名函数无法命名，所以不记录
-        self.generic_visit(node)
-
-
-def process_file(file_path: Path) -> Optional[List[str]]:
-    """处理单个Python文件，提取所有函数和方法名"""
+from typing import Optional
+
+
+def decorator_names(node) -> list:
+    """装饰器的名称，例如 @staticmethod、@functools.wraps(f) 分别记为 staticmethod、wraps"""
+    names = []
+    for decorator in node.decorator_list:
+        if isinstance(decorator, ast.Call):
+            decorator = decorator.func
+        if isinstance(decorator, ast.Attribute):
+            names.append(decorator.attr)
+        elif isinstance(decorator, ast.Name):
+            names.append(decorator.id)
+    return names
+
+
+def function_info(node) -> dict:
+    """模块级函数"""
+    return {
+        "name": node.name,
+        "type": "function",
+        "async": isinstance(node, ast.AsyncFunctionDef),
+    }
+
+
+def method_info(node) -> dict:
+    """类中的方法"""
+    decorators = decorator_names(node)
+    if node.name == "__init__":
+        kind = "constructor"
+    elif "property" in decorators:
+        kind = "property"
+    else:
+        kind = "method"
+    # classmethod 包装的是其他装饰器返回的对象时，无法确定调用时绑定的参数，按静态方法处理
+    is_static = "staticmethod" in decorators or ("classmethod" in decorators and len(decorators) > 1)
+    return {
+        "name": node.name,
+        "type": kind,
+        "static": is_static,
+        "async": isinstance(node, ast.AsyncFunctionDef),
+    }
+
+
+def class_info(node: ast.ClassDef) -> dict:
+    methods = [
+        method_info(item)
+        for item in node.body
+        if isinstance(item, (ast.FunctionDef, ast.AsyncFunctionDef))
+    ]
+    return {"name": node.name, "methods": methods}
+
+
+def process_file(file_path: Path, directory: Path) -> Optional[dict]:
+    """处理单个Python文件，提取模块级的函数、lambda 和类。嵌套函数不记录"""
     try:
         with open(file_path, 'r', encoding='utf-8') as file:
             content = file.read()
-        tree = ast.parse(content, filename=file_path)
-        visitor = FunctionVisitor()
-        visitor.visit(tree)
-        return visitor.functions
+        tree = ast.parse(content, filename=str(file_path))
     except Exception as e:
-        print(f"处理文件 {file_path} 时出错: {e}")
+        print(f"处理文件 {file_path} 时出错: {e}", file=sys.stderr)
         return None
 
+    functions = []
+    classes = []
+    for node in tree.body:
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+            functions.append(function_info(node))
+        elif isinstance(node, ast.ClassDef):
+            classes.append(class_info(node))
+        elif isinstance(node, ast.Assign) and isinstance(node.value, ast.Lambda):
+            for target in node.targets:
+                if isinstance(target, ast.Name):
+                    functions.append({"name": target.id, "type": "arrow", "async": False})
+    return {
+        "path": file_path.relative_to(directory).as_posix(),
+        "functions": functions,
+        "classes": classes,
+    }
 
-def walk_directory(directory: Path) -> None:
+
+def is_python_script(file_path: Path) -> bool:
+    """没有扩展名、shebang 指向 python 的脚本"""
+    if file_path.suffix:
+        return False
+    try:
+        with open(file_path, 'rb') as file:
+            first_line = file.readline(128)
+    except OSError:
+        return False
+    return first_line.startswith(b'#!') and b'python' in first_line
+
+
+def walk_directory(directory: Path) -> dict:
     """遍历目录，处理所有Python文件"""
-    log_path = directory / "python_fns_log"
-    
-    with open(log_path, 'w', encoding='utf-8') as log_file:
-        for root, _, files in os.walk(directory):
-            for file in files:
-                if file.endswith('.py'):
-                    file_path = Path(root) / file
-                    functions = process_file(file_path)
-                    
-                    if functions and len(functions) > 0:
-                        # 写入文件路径
-                        log_file.write(f"文件: {file_path}\n")
-                        # 写入函数名称
-                        for func in functions:
-                            log_file.write(f"  - {func}\n")
-    
-    print(f"Python函数列表已写入到 {log_path}")
+    files = []
+    for root, dirs, names in os.walk(directory):
+        dirs.sort()
+        for name in sorted(names):
+            path = Path(root) / name
+            if name.endswith('.py') or is_python_script(path):
+                info = process_file(path, directory)
+                if info is not None:
+                    files.append(info)
+    return {"files": files}
 
 
 def main():
-    """主函数"""
-    # 如果存在toRead.txt文件，则从文件读取目录路径
+    """主函数，结果以 JSON 写到标准输出"""
+    if len(sys.argv) < 2:
+        print("请提供目录路径", file=sys.stderr)
+        sys.exit(1)
     directory = Path(sys.argv[1])
-    print(f"使用命令行参数目录: {directory}")
     # 检查路径是否存在
     if not directory.exists():
-        print(f"错误：目录 '{directory}' 不存在")
-        return
+        print(f"错误：目录 '{directory}' 不存在", file=sys.stderr)
+        sys.exit(1)
     if not directory.is_dir():
-        print(f"错误：路径 '{directory}' 不是一个目录")
-        return
-    
-    walk_directory(directory)
+        print(f"错误：路径 '{directory}' 不是一个目录", file=sys.stderr)
+        sys.exit(1)
+
+    print(json.dumps(walk_directory(directory), indent=2, ensure_ascii=False))
 
 
 if __name__ == "__main__":
-    main()
\ No newline at end of file
+    main()
@@ -0,0 +1,621 @@
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::rust::report::{self as rust_report, RustReport};
+
+// 报告格式的版本。增加可选字段时增加次版本号，删除或改变字段含义时增加主版本号
+pub const SCHEMA_VERSION: &str = "1.3";
+
+// 各语言分析器共用的报告格式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectReport {
+    pub schema_version: String,
+    pub files: Vec<FileReport>,
+    // 无法读取或解析的文件
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<DiagnosticReport>,
+    // 混合语言分析时各语言的统计，单一语言的报告中没有
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<LanguageReport>,
+    // 按某项度量排列的最复杂的函数，只有 Rust 的 --sort-by / --top 才有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ranking: Option<RankingReport>,
+    // 各语言特有的详细报告，通用格式中没有的信息放在这里
+    #[serde(default, skip_serializing_if = "ReportDetails::is_empty")]
+    pub details: ReportDetails,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReportDetails {
+    // Rust 的可见性、签名、位置、文档、属性等，见 rust::report::RustReport
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rust: Option<RustReport>,
+}
+
+impl ReportDetails {
+    pub fn is_empty(&self) -> bool {
+        self.rust.is_none()
+    }
+
+    // 合并另一个分析器的详细报告
+    pub fn merge(&mut self, other: ReportDetails) {
+        if other.rust.is_some() {
+            self.rust = other.rust;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileReport {
+    // 相对于分析的目录
+    pub path: String,
+    // 混合语言分析时文件所属的语言
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub functions: Vec<Symbol>,
+    // 类，以及 Rust 的结构体、枚举等带有方法的类型
+    #[serde(default)]
+    pub classes: Vec<ClassReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassReport {
+    pub name: String,
+    #[serde(default)]
+    pub methods: Vec<Symbol>,
+}
+
+// 函数或方法
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Symbol {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: SymbolKind,
+    // 不需要实例就能调用，只有方法有这个字段
+    #[serde(rename = "static", default, skip_serializing_if = "Option::is_none")]
+    pub is_static: Option<bool>,
+    #[serde(rename = "async", default)]
+    pub is_async: bool,
+    // 在源文件中的范围，分析器没有提供时省略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<LocationReport>,
+}
+
+// 行号和列号从 1 开始，结束位置不包含在内；字节偏移从文件开头算起
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocationReport {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub byte_start: usize,
+    pub byte_end: usize,
+}
+
+impl LocationReport {
+    // 渲染为 "12:5-14:2 (120..180)"
+    pub fn render(&self) -> String {
+        format!(
+            "{}:{}-{}:{} ({}..{})",
+            self.start_line, self.start_column, self.end_line, self.end_column, self.byte_start, self.byte_end
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Function,
+    // 赋值给变量的匿名函数：JavaScript 的箭头函数、Python 的 lambda、Rust 的闭包常量
+    Arrow,
+    Method,
+    Constructor,
+    Property,
+    Get,
+    Set,
+}
+
+impl SymbolKind {
+    pub const ALL: &'static [SymbolKind] = &[
+        SymbolKind::Function,
+        SymbolKind::Arrow,
+        SymbolKind::Method,
+        SymbolKind::Constructor,
+        SymbolKind::Property,
+        SymbolKind::Get,
+        SymbolKind::Set,
+    ];
+
+    pub fn keyword(self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Arrow => "arrow",
+            SymbolKind::Method => "method",
+            SymbolKind::Constructor => "constructor",
+            SymbolKind::Property => "property",
+            SymbolKind::Get => "get",
+            SymbolKind::Set => "set",
+        }
+    }
+
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        SymbolKind::ALL.iter().copied().find(|kind| kind.keyword() == keyword)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticReport {
+    pub file: String,
+    // 从 1 开始的行号和列号，无法定位时为 None
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageReport {
+    pub language: String,
+    pub status: LanguageStatus,
+    // 按扩展名和 shebang 检测到的该语言文件数
+    pub detected_files: usize,
+    // 报告中该语言的文件、函数和类的个数
+    pub files: usize,
+    pub functions: usize,
+    pub classes: usize,
+    // 跳过或失败的原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankingReport {
+    // complexity / cognitive / nesting / params / loc / statements
+    pub metric: String,
+    pub functions: Vec<RankedFunction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankedFunction {
+    // 函数的完整路径，例如 "crate::net::connect"
+    pub path: String,
+    // 相对于分析的目录
+    pub file: String,
+    pub line: usize,
+    pub value: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LanguageStatus {
+    Analyzed,
+    // 工具链不可用
+    Skipped,
+    // 分析器执行失败
+    Failed,
+}
+
+impl LanguageStatus {
+    pub const ALL: [LanguageStatus; 3] = [LanguageStatus::Analyzed, LanguageStatus::Skipped, LanguageStatus::Failed];
+
+    pub fn keyword(self) -> &'static str {
+        match self {
+            LanguageStatus::Analyzed => "analyzed",
+            LanguageStatus::Skipped => "skipped",
+            LanguageStatus::Failed => "failed",
+        }
+    }
+}
+
+impl ProjectReport {
+    pub fn new(files: Vec<FileReport>) -> Self {
+        ProjectReport {
+            schema_version: SCHEMA_VERSION.to_string(),
+            files,
+            diagnostics: Vec::new(),
+            languages: Vec::new(),
+            ranking: None,
+            details: ReportDetails::default(),
+        }
+    }
+
+    // 校验分析器的输出并转换为统一的报告，文件路径改为相对于 root 的形式。接受以下几种写法：
+    //   带 schema_version 的报告，主版本号必须相同；
+    //   {"files": [{"path", "functions": [...], "classes": [{"name", "methods": [...]}]}]}，
+    //     条目中的其他字段（例如 C/C++ 分析器的 return_type）忽略；
+    //   {"classes": [{"name", "path", "methods": [...]}]}，即 Java 分析器的输出，按 path 归入文件
+    pub fn normalize(value: &Value, root: &Path) -> Result<Self> {
+        let object = value.as_object().ok_or_else(|| anyhow!("分析器的输出应为 JSON 对象"))?;
+        if let Some(version) = object.get("schema_version") {
+            let version = version.as_str().ok_or_else(|| anyhow!("schema_version 应为字符串"))?;
+            if major(version) != major(SCHEMA_VERSION) {
+                bail!("不支持的报告版本 {}，当前版本为 {}", version, SCHEMA_VERSION);
+            }
+        }
+
+        let mut report = ProjectReport::new(Vec::new());
+        if let Some(files) = object.get("files") {
+            for (index, file) in array(files, "files")?.iter().enumerate() {
+                let at = format!("files[{}]", index);
+                let file = as_object(file, &at)?;
+                report.files.push(FileReport {
+                    path: relative(string(file, "path", &at)?, root),
+                    language: None,
+                    functions: symbols(file, "functions", &at, SymbolKind::Function)?,
+                    classes: classes(file, &at)?,
+                });
+            }
+        } else if let Some(classes) = object.get("classes") {
+            for (index, class) in array(classes, "classes")?.iter().enumerate() {
+                let at = format!("classes[{}]", index);
+                let class = as_object(class, &at)?;
+                let path = relative(string(class, "path", &at)?, root);
+                let class = ClassReport {
+                    name: string(class, "name", &at)?.to_string(),
+                    methods: symbols(class, "methods", &at, SymbolKind::Method)?,
+                };
+                match report.files.iter_mut().find(|file| file.path == path) {
+                    Some(file) => file.classes.push(class),
+                    None => report.files.push(FileReport {
+                        path,
+                        language: None,
+                        functions: Vec::new(),
+                        classes: vec![class],
+                    }),
+                }
+            }
+        } else {
+            bail!("分析器的输出中既没有 files 也没有 classes");
+        }
+
+        if let Some(diagnostics) = object.get("diagnostics") {
+            for (index, diagnostic) in array(diagnostics, "diagnostics")?.iter().enumerate() {
+                let at = format!("diagnostics[{}]", index);
+                let diagnostic = as_object(diagnostic, &at)?;
+                report.diagnostics.push(DiagnosticReport {
+                    file: relative(string(diagnostic, "file", &at)?, root),
+                    line: optional_number(diagnostic, "line", &at)?,
+                    column: optional_number(diagnostic, "column", &at)?,
+                    message: string(diagnostic, "message", &at)?.to_string(),
+                });
+            }
+        }
+        Ok(report)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            out.push_str(&format!("文件: {}\n", file.path));
+            for function in &file.functions {
+                out.push_str(&format!("  - {}\n", symbol_text(function)));
+            }
+            for class in &file.classes {
+                out.push_str(&format!("  类 {}\n", class.name));
+                for method in &class.methods {
+                    out.push_str(&format!("    - {}\n", symbol_text(method)));
+                }
+            }
+        }
+        for diagnostic in &self.diagnostics {
+            out.push_str(&format!("诊断: {}: {}\n", diagnostic.file, diagnostic.message));
+        }
+        for language in &self.languages {
+            out.push_str(&format!("语言: {}\n", language_text(language)));
+        }
+        if let Some(ranking) = &self.ranking {
+            out.push_str(&format!("排名 (按 {}):\n", ranking.metric));
+            for (index, function) in ranking.functions.iter().enumerate() {
+                out.push_str(&format!("  {}. {}\n", index + 1, ranked_text(function)));
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut out = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>代码结构</title>\n</head>\n<body>\n",
+        );
+        for file in &self.files {
+            out.push_str(&format!("<h2>{}</h2>\n<ul>\n", html_escape(&file.path)));
+            for function in &file.functions {
+                out.push_str(&format!("<li><code>{}</code></li>\n", html_escape(&symbol_text(function))));
+            }
+            for class in &file.classes {
+                out.push_str(&format!("<li><b>{}</b>\n<ul>\n", html_escape(&class.name)));
+                for method in &class.methods {
+                    out.push_str(&format!("<li><code>{}</code></li>\n", html_escape(&symbol_text(method))));
+                }
+                out.push_str("</ul>\n</li>\n");
+            }
+            out.push_str("</ul>\n");
+        }
+        if !self.languages.is_empty() {
+            out.push_str("<h2>语言</h2>\n<ul>\n");
+            for language in &self.languages {
+                out.push_str(&format!("<li>{}</li>\n", html_escape(&language_text(language))));
+            }
+            out.push_str("</ul>\n");
+        }
+        if let Some(ranking) = &self.ranking {
+            out.push_str(&format!("<h2>排名 (按 {})</h2>\n<ol>\n", html_escape(&ranking.metric)));
+            for function in &ranking.functions {
+                out.push_str(&format!("<li><code>{}</code></li>\n", html_escape(&ranked_text(function))));
+            }
+            out.push_str("</ol>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+// 报告格式的 JSON Schema，与上面的结构体一一对应
+pub fn json_schema() -> Value {
+    let mut schema = core_schema();
+    if let (Some(defs), Value::Object(rust)) = (schema["$defs"].as_object_mut(), rust_report::json_schema_defs()) {
+        defs.extend(rust);
+    }
+    schema
+}
+
+fn core_schema() -> Value {
+    let kinds: Vec<&str> = SymbolKind::ALL.iter().map(|kind| kind.keyword()).collect();
+    let statuses: Vec<&str> = LanguageStatus::ALL.iter().map(|status| status.keyword()).collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("https://github.com/code-parser/code-parser/schema/report-{}.json", SCHEMA_VERSION),
+        "title": "code-parser 分析报告",
+        "type": "object",
+        "required": ["schema_version", "files"],
+        "properties": {
+            "schema_version": {
+                "type": "string",
+                "pattern": format!("^{}\\.[0-9]+$", major(SCHEMA_VERSION)),
+            },
+            "files": {"type": "array", "items": {"$ref": "#/$defs/file"}},
+            "diagnostics": {"type": "array", "items": {"$ref": "#/$defs/diagnostic"}},
+            "languages": {"type": "array", "items": {"$ref": "#/$defs/language"}},
+            "ranking": {"$ref": "#/$defs/ranking"},
+            "details": {"$ref": "#/$defs/details"},
+        },
+        "$defs": {
+            "file": {
+                "type": "object",
+                "required": ["path", "functions", "classes"],
+                "properties": {
+                    "path": {"type": "string"},
+                    "language": {"type": "string"},
+                    "functions": {"type": "array", "items": {"$ref": "#/$defs/symbol"}},
+                    "classes": {"type": "array", "items": {"$ref": "#/$defs/class"}},
+                },
+            },
+            "class": {
+                "type": "object",
+                "required": ["name", "methods"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "methods": {"type": "array", "items": {"$ref": "#/$defs/symbol"}},
+                },
+            },
+            "symbol": {
+                "type": "object",
+                "required": ["name", "type", "async"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "type": {"enum": kinds},
+                    "static": {"type": "boolean"},
+                    "async": {"type": "boolean"},
+                    "location": {"$ref": "#/$defs/location"},
+                },
+            },
+            "location": {
+                "type": "object",
+                "required": ["start_line", "start_column", "end_line", "end_column", "byte_start", "byte_end"],
+                "properties": {
+                    "start_line": {"type": "integer", "minimum": 1},
+                    "start_column": {"type": "integer", "minimum": 1},
+                    "end_line": {"type": "integer", "minimum": 1},
+                    "end_column": {"type": "integer", "minimum": 1},
+                    "byte_start": {"type": "integer", "minimum": 0},
+                    "byte_end": {"type": "integer", "minimum": 0},
+                },
+            },
+            "diagnostic": {
+                "type": "object",
+                "required": ["file", "line", "column", "message"],
+                "properties": {
+                    "file": {"type": "string"},
+                    "line": {"type": ["integer", "null"], "minimum": 1},
+                    "column": {"type": ["integer", "null"], "minimum": 1},
+                    "message": {"type": "string"},
+                },
+            },
+            "language": {
+                "type": "object",
+                "required": ["language", "status", "detected_files", "files", "functions", "classes"],
+                "properties": {
+                    "language": {"type": "string"},
+                    "status": {"enum": statuses},
+                    "detected_files": {"type": "integer", "minimum": 0},
+                    "files": {"type": "integer", "minimum": 0},
+                    "functions": {"type": "integer", "minimum": 0},
+                    "classes": {"type": "integer", "minimum": 0},
+                    "reason": {"type": "string"},
+                },
+            },
+            "ranking": {
+                "type": "object",
+                "required": ["metric", "functions"],
+                "properties": {
+                    "metric": {"type": "string"},
+                    "functions": {"type": "array", "items": {"$ref": "#/$defs/ranked_function"}},
+                },
+            },
+            "details": {
+                "type": "object",
+                "properties": {
+                    "rust": {"$ref": "#/$defs/rust_report"},
+                },
+            },
+            "ranked_function": {
+                "type": "object",
+                "required": ["path", "file", "line", "value"],
+                "properties": {
+                    "path": {"type": "string"},
+                    "file": {"type": "string"},
+                    "line": {"type": "integer", "minimum": 1},
+                    "value": {"type": "integer", "minimum": 0},
+                },
+            },
+        },
+    })
+}
+
+fn major(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
+
+fn symbol_text(symbol: &Symbol) -> String {
+    let mut text = String::new();
+    if symbol.is_static == Some(true) {
+        text.push_str("static ");
+    }
+    if symbol.is_async {
+        text.push_str("async ");
+    }
+    text.push_str(&format!("{} ({})", symbol.name, symbol.kind.keyword()));
+    if let Some(location) = &symbol.location {
+        text.push_str(&format!(" @{}", location.render()));
+    }
+    text
+}
+
+fn language_text(language: &LanguageReport) -> String {
+    let mut text = format!(
+        "{} ({}) 检测到 {} 个文件，报告 {} 个文件、{} 个函数、{} 个类",
+        language.language,
+        language.status.keyword(),
+        language.detected_files,
+        language.files,
+        language.functions,
+        language.classes
+    );
+    if let Some(reason) = &language.reason {
+        text.push_str(&format!("：{}", reason));
+    }
+    text
+}
+
+fn ranked_text(function: &RankedFunction) -> String {
+    format!("{} = {} ({}:{})", function.path, function.value, function.file, function.line)
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// 分析的目录中的绝对路径改为相对路径，其他路径原样保留
+fn relative(path: &str, root: &Path) -> String {
+    Path::new(path).strip_prefix(root).map_or_else(|_| path.to_string(), |path| path.display().to_string())
+}
+
+fn as_object<'a>(value: &'a Value, at: &str) -> Result<&'a Map<String, Value>> {
+    value.as_object().ok_or_else(|| anyhow!("{} 应为对象", at))
+}
+
+fn array<'a>(value: &'a Value, at: &str) -> Result<&'a Vec<Value>> {
+    value.as_array().ok_or_else(|| anyhow!("{} 应为数组", at))
+}
+
+fn string<'a>(object: &'a Map<String, Value>, key: &str, at: &str) -> Result<&'a str> {
+    match object.get(key) {
+        Some(Value::String(value)) => Ok(value),
+        Some(_) => bail!("{}.{} 应为字符串", at, key),
+        None => bail!("{} 缺少 {}", at, key),
+    }
+}
+
+fn optional_bool(object: &Map<String, Value>, key: &str, at: &str) -> Result<Option<bool>> {
+    match object.get(key) {
+        Some(Value::Bool(value)) => Ok(Some(*value)),
+        Some(Value::Null) | None => Ok(None),
+        Some(_) => bail!("{}.{} 应为布尔值", at, key),
+    }
+}
+
+fn optional_location(object: &Map<String, Value>, at: &str) -> Result<Option<LocationReport>> {
+    match object.get("location") {
+        Some(Value::Null) | None => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| anyhow!("{}.location 格式错误: {}", at, e)),
+    }
+}
+
+fn optional_number(object: &Map<String, Value>, key: &str, at: &str) -> Result<Option<usize>> {
+    match object.get(key) {
+        Some(Value::Null) | None => Ok(None),
+        Some(value) => match value.as_u64() {
+            Some(number) => Ok(Some(number as usize)),
+            None => bail!("{}.{} 应为非负整数", at, key),
+        },
+    }
+}
+
+// 函数或方法列表，没有 type 字段时按 default 处理。方法总是带有 static 字段
+fn symbols(object: &Map<String, Value>, key: &str, at: &str, default: SymbolKind) -> Result<Vec<Symbol>> {
+    let Some(list) = object.get(key) else {
+        return Ok(Vec::new());
+    };
+    let at = format!("{}.{}", at, key);
+    let mut symbols = Vec::new();
+    for (index, symbol) in array(list, &at)?.iter().enumerate() {
+        let at = format!("{}[{}]", at, index);
+        let symbol = as_object(symbol, &at)?;
+        let kind = match object_kind(symbol, &at)? {
+            Some(keyword) => SymbolKind::from_keyword(keyword).ok_or_else(|| anyhow!("{}.type 不支持 {}", at, keyword))?,
+            None => default,
+        };
+        let is_static = optional_bool(symbol, "static", &at)?;
+        symbols.push(Symbol {
+            name: string(symbol, "name", &at)?.to_string(),
+            kind,
+            is_static: if default == SymbolKind::Method { Some(is_static.unwrap_or(false)) } else { is_static },
+            is_async: optional_bool(symbol, "async", &at)?.unwrap_or(false),
+            location: optional_location(symbol, &at)?,
+        });
+    }
+    Ok(symbols)
+}
+
+fn object_kind<'a>(object: &'a Map<String, Value>, at: &str) -> Result<Option<&'a str>> {
+    match object.get("type") {
+        Some(Value::String(keyword)) => Ok(Some(keyword)),
+        Some(Value::Null) | None => Ok(None),
+        Some(_) => bail!("{}.type 应为字符串", at),
+    }
+}
+
+fn classes(file: &Map<String, Value>, at: &str) -> Result<Vec<ClassReport>> {
+    let Some(list) = file.get("classes") else {
+        return Ok(Vec::new());
+    };
+    let at = format!("{}.classes", at);
+    let mut classes = Vec::new();
+    for (index, class) in array(list, &at)?.iter().enumerate() {
+        let at = format!("{}[{}]", at, index);
+        let class = as_object(class, &at)?;
+        classes.push(ClassReport {
+            name: string(class, "name", &at)?.to_string(),
+            methods: symbols(class, "methods", &at, SymbolKind::Method)?,
+        });
+    }
+    Ok(classes)
+}
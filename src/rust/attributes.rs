@@ -0,0 +1,109 @@
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, Lit, Meta, Token};
+
+use crate::rust::model::{Deprecation, ItemAttrs};
+use crate::rust::signature::render_tokens;
+
+// 合并 `///`、`//!` 和 `#[doc = "..."]` 得到的文档文本。
+// 每行去掉注释标记后的一个前导空格，没有文档时返回 None
+pub fn doc_text(attrs: &[Attribute]) -> Option<String> {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        let Meta::NameValue(meta) = &attr.meta else {
+            continue;
+        };
+        if !meta.path.is_ident("doc") {
+            continue;
+        }
+        if let Expr::Lit(expr) = &meta.value {
+            if let Lit::Str(value) = &expr.lit {
+                let value = value.value();
+                lines.extend(value.split('\n').map(|line| line.strip_prefix(' ').unwrap_or(line).to_string()));
+            }
+        }
+    }
+    let text = lines.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+// 文档中是否有指定标题的小节，例如 title 为 "Errors" 时查找 "# Errors"
+pub fn has_section(docs: &str, title: &str) -> bool {
+    docs.lines().any(|line| {
+        let line = line.trim();
+        line.starts_with('#') && line.trim_start_matches('#').trim().eq_ignore_ascii_case(title)
+    })
+}
+
+// 提取对 API 使用者有意义的属性，无法识别的写法按缺省处理
+pub fn item_attrs(attrs: &[Attribute]) -> ItemAttrs {
+    let mut result = ItemAttrs::default();
+    for attr in attrs {
+        let path = attr.path();
+        if path.is_ident("deprecated") {
+            result.deprecated = Some(deprecation(attr));
+        } else if path.is_ident("must_use") {
+            result.must_use = Some(string_value(&attr.meta).unwrap_or_default());
+        } else if path.is_ident("non_exhaustive") {
+            result.non_exhaustive = true;
+        } else if path.is_ident("inline") {
+            let mode = match &attr.meta {
+                Meta::List(list) => render_tokens(&list.tokens),
+                _ => "inline".to_string(),
+            };
+            result.inline = Some(mode);
+        } else if path.is_ident("derive") {
+            result.derives.extend(nested_metas(attr).iter().map(|meta| render_tokens(meta.path())));
+        } else if path.is_ident("repr") {
+            result.repr.extend(nested_metas(attr).iter().map(render_tokens));
+        } else if path.is_ident("doc") {
+            if let Meta::List(_) = &attr.meta {
+                result.doc_hidden |= nested_metas(attr).iter().any(|meta| meta.path().is_ident("hidden"));
+            }
+        } else if path.is_ident("cfg") {
+            if let Meta::List(list) = &attr.meta {
+                result.cfg.push(render_tokens(&list.tokens));
+            }
+        }
+    }
+    result
+}
+
+// `#[deprecated]`、`#[deprecated = "note"]` 或 `#[deprecated(since = "..", note = "..")]`
+fn deprecation(attr: &Attribute) -> Deprecation {
+    let mut result = Deprecation::default();
+    match &attr.meta {
+        Meta::NameValue(_) => result.note = string_value(&attr.meta),
+        Meta::List(_) => {
+            for arg in nested_metas(attr) {
+                if arg.path().is_ident("since") {
+                    result.since = string_value(&arg);
+                } else if arg.path().is_ident("note") {
+                    result.note = string_value(&arg);
+                }
+            }
+        }
+        Meta::Path(_) => {}
+    }
+    result
+}
+
+// `name = "value"` 形式中的字符串
+fn string_value(meta: &Meta) -> Option<String> {
+    let Meta::NameValue(meta) = meta else {
+        return None;
+    };
+    match &meta.value {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Str(value) => Some(value.value()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// `#[name(a, b(c))]` 中逗号分隔的各项
+fn nested_metas(attr: &Attribute) -> Vec<Meta> {
+    attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        .map(|metas| metas.into_iter().collect())
+        .unwrap_or_default()
+}
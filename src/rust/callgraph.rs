@@ -0,0 +1,222 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::rust::model::{CallKind, FileItems, FnItem};
+use crate::rust::reexports::Resolver;
+
+// 调用图中的一条边，对应函数体中的一次调用
+#[derive(Debug, Clone, Serialize)]
+pub struct CallEdge {
+    pub caller: String,
+    // 解析成功时为被调用函数的完整路径，否则为源码中的写法；宏调用以 "!" 结尾
+    pub callee: String,
+    pub kind: &'static str,
+    pub resolved: bool,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+// 一个 crate 内部的调用图
+#[derive(Debug, Clone, Serialize)]
+pub struct CallGraph {
+    // crate 根文件
+    #[serde(rename = "crate")]
+    pub root: String,
+    // crate 中所有函数和方法的完整路径
+    pub nodes: Vec<String>,
+    pub edges: Vec<CallEdge>,
+    // 没有被 crate 内其他函数调用的函数，通常是 main、公开 API 或死代码
+    pub entry_points: Vec<String>,
+}
+
+// 调用方所在的上下文：所在模块，以及方法所属的类型或 trait
+struct Caller<'a> {
+    item: &'a FnItem,
+    file: &'a Path,
+    module: String,
+    owner: Option<String>,
+}
+
+impl CallGraph {
+    // 路径调用通过 use 导入和模块树解析；方法调用在接收者为 self 时先找所属类型的方法，
+    // 否则只有 crate 中恰好一个同名方法时才视为解析成功；宏调用在宏由 crate 自己定义时视为解析成功
+    pub fn build(root: &Path, files: &[FileItems]) -> Self {
+        let mut callers = Vec::new();
+        let mut macros = HashSet::new();
+        for items in files {
+            macros.extend(items.macro_defs.iter().map(String::as_str));
+            for func in &items.functions {
+                callers.push(Caller {
+                    item: func,
+                    file: &items.file,
+                    module: parent(&func.path).to_string(),
+                    owner: None,
+                });
+            }
+            let methods = items
+                .types
+                .iter()
+                .flat_map(|item| &item.methods)
+                .chain(items.traits.iter().flat_map(|def| &def.methods))
+                .chain(items.trait_impls.iter().flat_map(|imp| &imp.methods));
+            for method in methods {
+                let owner = parent(&method.path);
+                callers.push(Caller {
+                    item: method,
+                    file: &items.file,
+                    module: parent(owner).to_string(),
+                    owner: Some(owner.to_string()),
+                });
+            }
+        }
+
+        let nodes: BTreeSet<&str> = callers.iter().map(|caller| caller.item.path.as_str()).collect();
+        let mut methods_by_name: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for caller in callers.iter().filter(|caller| caller.owner.is_some()) {
+            let name = caller.item.path.rsplit("::").next().unwrap_or_default();
+            methods_by_name.entry(name).or_default().insert(&caller.item.path);
+        }
+        let resolver = Resolver::new(files);
+
+        let mut edges = Vec::new();
+        for caller in &callers {
+            for call in &caller.item.calls {
+                let resolved = match call.kind {
+                    CallKind::Path => {
+                        let segments: Vec<String> = call.callee.split("::").map(str::to_string).collect();
+                        let candidate = match (segments.first().map(String::as_str), &caller.owner) {
+                            (Some("Self"), Some(owner)) => Some(format!("{}::{}", owner, segments[1..].join("::"))),
+                            _ => resolver.resolve_item(&caller.module, &segments),
+                        };
+                        // 函数体中定义的嵌套函数不在模块的命名空间中，按所在模块补全路径再找一次
+                        candidate
+                            .filter(|path| nodes.contains(path.as_str()))
+                            .or_else(|| Some(format!("{}::{}", caller.module, call.callee)))
+                            .filter(|path| nodes.contains(path.as_str()))
+                    }
+                    CallKind::Method => {
+                        let own = caller
+                            .owner
+                            .as_ref()
+                            .filter(|_| call.on_self)
+                            .map(|owner| format!("{}::{}", owner, call.callee))
+                            .filter(|path| nodes.contains(path.as_str()));
+                        own.or_else(|| match methods_by_name.get(call.callee.as_str()) {
+                            Some(paths) if paths.len() == 1 => paths.iter().next().map(|path| path.to_string()),
+                            _ => None,
+                        })
+                    }
+                    CallKind::Macro => macros.contains(call.callee.as_str()).then(|| format!("{}!", call.callee)),
+                };
+                let callee = match (&resolved, call.kind) {
+                    (Some(path), _) => path.clone(),
+                    (None, CallKind::Macro) => format!("{}!", call.callee),
+                    (None, _) => call.callee.clone(),
+                };
+                edges.push(CallEdge {
+                    caller: caller.item.path.clone(),
+                    callee,
+                    kind: call.kind.keyword(),
+                    resolved: resolved.is_some(),
+                    file: caller.file.display().to_string(),
+                    line: call.location.start_line,
+                    column: call.location.start_column,
+                });
+            }
+        }
+
+        let called: HashSet<&str> = edges
+            .iter()
+            .filter(|edge| edge.resolved && edge.caller != edge.callee)
+            .map(|edge| edge.callee.as_str())
+            .collect();
+        let entry_points = nodes.iter().filter(|node| !called.contains(*node)).map(|node| node.to_string()).collect();
+        CallGraph {
+            root: root.display().to_string(),
+            nodes: nodes.iter().map(|node| node.to_string()).collect(),
+            edges,
+            entry_points,
+        }
+    }
+
+    // 不重复的边，同一对函数之间的多次调用只保留一条
+    fn distinct_edges(&self) -> Vec<&CallEdge> {
+        let mut seen = HashSet::new();
+        self.edges
+            .iter()
+            .filter(|edge| seen.insert((edge.caller.as_str(), edge.callee.as_str())))
+            .collect()
+    }
+
+    // 边指向的、不属于 crate 中函数的目标：未解析的调用和宏
+    fn external_targets(&self) -> BTreeSet<&str> {
+        let nodes: HashSet<&str> = self.nodes.iter().map(String::as_str).collect();
+        self.edges
+            .iter()
+            .map(|edge| edge.callee.as_str())
+            .filter(|callee| !nodes.contains(callee))
+            .collect()
+    }
+}
+
+pub fn to_json(graphs: &[CallGraph]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(graphs)
+}
+
+// Graphviz 格式，每个 crate 一个子图，未解析的调用用虚线表示
+pub fn to_dot(graphs: &[CallGraph]) -> String {
+    let mut out = String::from("digraph calls {\n    rankdir=LR;\n    node [shape=box];\n");
+    for (index, graph) in graphs.iter().enumerate() {
+        let id = |name: &str| format!("{:?}", format!("{}:{}", index, name));
+        out.push_str(&format!("    subgraph cluster_{} {{\n        label={:?};\n", index, graph.root));
+        for node in &graph.nodes {
+            out.push_str(&format!("        {} [label={:?}];\n", id(node), node));
+        }
+        for target in graph.external_targets() {
+            out.push_str(&format!("        {} [label={:?}, style=dashed];\n", id(target), target));
+        }
+        for edge in graph.distinct_edges() {
+            let style = if edge.resolved { "" } else { " [style=dashed]" };
+            out.push_str(&format!("        {} -> {}{};\n", id(&edge.caller), id(&edge.callee), style));
+        }
+        out.push_str("    }\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+// Mermaid 流程图格式，每个 crate 一个子图，未解析的调用用虚线箭头表示
+pub fn to_mermaid(graphs: &[CallGraph]) -> String {
+    let mut out = String::from("graph LR\n");
+    for (index, graph) in graphs.iter().enumerate() {
+        let mut ids: HashMap<&str, String> = HashMap::new();
+        out.push_str(&format!("    subgraph c{}[\"{}\"]\n", index, mermaid_label(&graph.root)));
+        for node in &graph.nodes {
+            let id = format!("c{}n{}", index, ids.len());
+            out.push_str(&format!("        {}[\"{}\"]\n", id, mermaid_label(node)));
+            ids.insert(node, id);
+        }
+        for target in graph.external_targets() {
+            let id = format!("c{}n{}", index, ids.len());
+            out.push_str(&format!("        {}([\"{}\"])\n", id, mermaid_label(target)));
+            ids.insert(target, id);
+        }
+        out.push_str("    end\n");
+        for edge in graph.distinct_edges() {
+            let arrow = if edge.resolved { "-->" } else { "-.->" };
+            out.push_str(&format!("    {} {} {}\n", ids[edge.caller.as_str()], arrow, ids[edge.callee.as_str()]));
+        }
+    }
+    out
+}
+
+fn mermaid_label(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+fn parent(path: &str) -> &str {
+    path.rsplit_once("::").map_or("", |(parent, _)| parent)
+}
@@ -0,0 +1,329 @@
+use std::collections::BTreeSet;
+use std::mem;
+
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    Attribute, Block, Expr, FieldsNamed, FieldsUnnamed, ImplItem, Item, ItemEnum, ItemImpl, ItemMod, ItemTrait, Lit,
+    Meta, Stmt, Token, TraitItem,
+};
+
+use crate::rust::signature::render_tokens;
+
+// `#[cfg(...)]` 中的条件表达式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgExpr {
+    // `unix`、`test`
+    Name(String),
+    // `feature = "serde"`、`target_os = "linux"`
+    KeyValue(String, String),
+    All(Vec<CfgExpr>),
+    Any(Vec<CfgExpr>),
+    Not(Box<CfgExpr>),
+}
+
+impl CfgExpr {
+    pub fn from_meta(meta: &Meta) -> Result<Self, String> {
+        match meta {
+            Meta::Path(path) => Ok(CfgExpr::Name(render_tokens(path))),
+            Meta::NameValue(pair) => match &pair.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(value) => Ok(CfgExpr::KeyValue(render_tokens(&pair.path), value.value())),
+                    _ => Err(format!("cfg 条件的值必须是字符串: {}", render_tokens(meta))),
+                },
+                _ => Err(format!("cfg 条件的值必须是字符串: {}", render_tokens(meta))),
+            },
+            Meta::List(list) => {
+                let args = list
+                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                    .map_err(|e| format!("无法解析 cfg 条件 {}: {}", render_tokens(meta), e))?;
+                let args = args.iter().map(CfgExpr::from_meta).collect::<Result<Vec<_>, _>>()?;
+                if list.path.is_ident("all") {
+                    Ok(CfgExpr::All(args))
+                } else if list.path.is_ident("any") {
+                    Ok(CfgExpr::Any(args))
+                } else if list.path.is_ident("not") {
+                    match <[CfgExpr; 1]>::try_from(args) {
+                        Ok([arg]) => Ok(CfgExpr::Not(Box::new(arg))),
+                        Err(_) => Err(format!("not() 只能有一个参数: {}", render_tokens(meta))),
+                    }
+                } else {
+                    Err(format!("未知的 cfg 谓词: {}", render_tokens(meta)))
+                }
+            }
+        }
+    }
+
+    // 条件成立时 name 是否一定成立，例如 `all(test, unix)` 要求 test
+    pub fn requires(&self, name: &str) -> bool {
+        match self {
+            CfgExpr::Name(n) => n == name,
+            CfgExpr::KeyValue(..) | CfgExpr::Not(_) => false,
+            CfgExpr::All(args) => args.iter().any(|arg| arg.requires(name)),
+            CfgExpr::Any(args) => !args.is_empty() && args.iter().all(|arg| arg.requires(name)),
+        }
+    }
+
+    // 条目上 `#[cfg(...)]` 属性的条件，不是 cfg 属性时返回 None
+    pub fn from_attr(attr: &Attribute) -> Option<Result<Self, String>> {
+        if !attr.path().is_ident("cfg") {
+            return None;
+        }
+        Some(
+            attr.parse_args::<Meta>()
+                .map_err(|e| format!("无法解析 cfg 属性: {}", e))
+                .and_then(|meta| CfgExpr::from_meta(&meta)),
+        )
+    }
+}
+
+// 只在运行测试或生成文档时才设置的 cfg 名称，普通构建中一定不成立，除非通过 `--cfg` 给出。
+// debug_assertions 取决于构建配置，仍按未知处理
+const OFF_BY_DEFAULT: &[&str] = &["test", "doc", "doctest"];
+
+// 分析时已知的 cfg 选项：启用的特性，以及 `--cfg` 给出的名称和键值对。
+// 条件按三值逻辑计算，没有通过 `--cfg` 给出的名称和键（例如 unix、target_os）既不成立也不否定，
+// 带有这类条件的条目保留下来并标出其 cfg 条件；OFF_BY_DEFAULT 中的名称没有给出时按不成立处理
+#[derive(Debug, Clone, Default)]
+pub struct CfgSet {
+    // 启用的特性，None 表示不知道启用了哪些特性（没有 Cargo.toml 也没有给出特性选项）
+    features: Option<BTreeSet<String>>,
+    names: BTreeSet<String>,
+    values: BTreeSet<(String, String)>,
+}
+
+impl CfgSet {
+    // cfgs 的每一项形如 `unix` 或 `target_os="linux"`
+    pub fn new<'a>(features: impl IntoIterator<Item = &'a String>, cfgs: &[&str]) -> Self {
+        let mut set = CfgSet::without_features(cfgs);
+        set.features = Some(features.into_iter().cloned().collect());
+        set
+    }
+
+    // 不知道启用了哪些特性时的 cfg 选项，feature 条件都按未知处理
+    pub fn without_features(cfgs: &[&str]) -> Self {
+        let mut set = CfgSet::default();
+        for cfg in cfgs {
+            match cfg.split_once('=') {
+                Some((key, value)) => {
+                    let value = value.trim().trim_matches('"');
+                    set.values.insert((key.trim().to_string(), value.to_string()));
+                }
+                None => {
+                    set.names.insert(cfg.trim().to_string());
+                }
+            }
+        }
+        set
+    }
+
+    // 条件成立时返回 Some(true)，不成立时返回 Some(false)，取决于未知的选项时返回 None
+    pub fn eval(&self, expr: &CfgExpr) -> Option<bool> {
+        match expr {
+            CfgExpr::Name(name) if self.names.contains(name) => Some(true),
+            CfgExpr::Name(name) => OFF_BY_DEFAULT.contains(&name.as_str()).then_some(false),
+            CfgExpr::KeyValue(key, value) if key == "feature" => {
+                self.features.as_ref().map(|features| features.contains(value))
+            }
+            CfgExpr::KeyValue(key, value) => self.values.contains(&(key.clone(), value.clone())).then_some(true),
+            CfgExpr::All(exprs) => {
+                let results: Vec<Option<bool>> = exprs.iter().map(|expr| self.eval(expr)).collect();
+                if results.contains(&Some(false)) {
+                    Some(false)
+                } else {
+                    results.iter().all(|result| *result == Some(true)).then_some(true)
+                }
+            }
+            CfgExpr::Any(exprs) => {
+                let results: Vec<Option<bool>> = exprs.iter().map(|expr| self.eval(expr)).collect();
+                if results.contains(&Some(true)) {
+                    Some(true)
+                } else {
+                    results.iter().all(|result| *result == Some(false)).then_some(false)
+                }
+            }
+            CfgExpr::Not(expr) => self.eval(expr).map(|result| !result),
+        }
+    }
+
+    // 条目是否保留：只去掉某个 cfg 条件一定不成立的条目。无法解析或取决于未知选项的条件按成立处理，
+    // 宁可多报也不漏掉条目
+    pub fn enabled(&self, attrs: &[Attribute]) -> bool {
+        attrs
+            .iter()
+            .filter_map(CfgExpr::from_attr)
+            .all(|expr| match expr {
+                Ok(expr) => self.eval(&expr) != Some(false),
+                Err(_) => true,
+            })
+    }
+
+    // 展开 `#[cfg_attr(条件, 属性...)]`：条件成立时替换为其中的属性，否则去掉
+    pub fn expand_cfg_attr(&self, attrs: &mut Vec<Attribute>) {
+        let mut expanded = Vec::with_capacity(attrs.len());
+        let mut pending: Vec<Attribute> = mem::take(attrs);
+        pending.reverse();
+        while let Some(attr) = pending.pop() {
+            if !attr.path().is_ident("cfg_attr") {
+                expanded.push(attr);
+                continue;
+            }
+            let parsed = attr.parse_args_with(|input: ParseStream| {
+                let predicate: Meta = input.parse()?;
+                input.parse::<Token![,]>()?;
+                let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+                Ok((predicate, metas))
+            });
+            let Ok((predicate, metas)) = parsed else {
+                expanded.push(attr);
+                continue;
+            };
+            match CfgExpr::from_meta(&predicate).map(|expr| self.eval(&expr)) {
+                // 展开出的属性可能还是 cfg_attr，放回待处理列表
+                Ok(Some(true)) => {
+                    for meta in metas.into_iter().rev() {
+                        pending.push(Attribute {
+                            pound_token: attr.pound_token,
+                            style: attr.style,
+                            bracket_token: attr.bracket_token,
+                            meta,
+                        });
+                    }
+                }
+                Ok(Some(false)) => {}
+                // 条件取决于未知的选项时保留原样
+                _ => expanded.push(attr),
+            }
+        }
+        *attrs = expanded;
+    }
+
+    // 去掉语法树中条件不成立的条目、字段、变体和语句，并展开 cfg_attr
+    pub fn strip_file(&self, file: &mut syn::File) {
+        CfgStripper(self).visit_file_mut(file);
+    }
+
+    pub fn strip_items(&self, items: &mut Vec<Item>) {
+        let mut stripper = CfgStripper(self);
+        items.retain_mut(|item| stripper.keep_item(item));
+        for item in items {
+            stripper.visit_item_mut(item);
+        }
+    }
+}
+
+struct CfgStripper<'a>(&'a CfgSet);
+
+impl CfgStripper<'_> {
+    fn keep(&self, attrs: &mut Vec<Attribute>) -> bool {
+        self.0.expand_cfg_attr(attrs);
+        self.0.enabled(attrs)
+    }
+
+    fn keep_item(&self, item: &mut Item) -> bool {
+        item_attrs_mut(item).is_none_or(|attrs| self.keep(attrs))
+    }
+}
+
+impl VisitMut for CfgStripper<'_> {
+    fn visit_file_mut(&mut self, node: &mut syn::File) {
+        // `#![cfg(...)]` 不成立时整个文件都不参与编译
+        if !self.keep(&mut node.attrs) {
+            node.items.clear();
+        }
+        node.items.retain_mut(|item| self.keep_item(item));
+        visit_mut::visit_file_mut(self, node);
+    }
+
+    fn visit_item_mod_mut(&mut self, node: &mut ItemMod) {
+        if let Some((_, items)) = &mut node.content {
+            items.retain_mut(|item| self.keep_item(item));
+        }
+        visit_mut::visit_item_mod_mut(self, node);
+    }
+
+    fn visit_item_impl_mut(&mut self, node: &mut ItemImpl) {
+        node.items.retain_mut(|item| impl_item_attrs_mut(item).is_none_or(|attrs| self.keep(attrs)));
+        visit_mut::visit_item_impl_mut(self, node);
+    }
+
+    fn visit_item_trait_mut(&mut self, node: &mut ItemTrait) {
+        node.items.retain_mut(|item| trait_item_attrs_mut(item).is_none_or(|attrs| self.keep(attrs)));
+        visit_mut::visit_item_trait_mut(self, node);
+    }
+
+    fn visit_item_enum_mut(&mut self, node: &mut ItemEnum) {
+        node.variants = mem::take(&mut node.variants)
+            .into_iter()
+            .filter_map(|mut variant| self.keep(&mut variant.attrs).then_some(variant))
+            .collect();
+        visit_mut::visit_item_enum_mut(self, node);
+    }
+
+    fn visit_fields_named_mut(&mut self, node: &mut FieldsNamed) {
+        node.named = mem::take(&mut node.named)
+            .into_iter()
+            .filter_map(|mut field| self.keep(&mut field.attrs).then_some(field))
+            .collect();
+        visit_mut::visit_fields_named_mut(self, node);
+    }
+
+    fn visit_fields_unnamed_mut(&mut self, node: &mut FieldsUnnamed) {
+        node.unnamed = mem::take(&mut node.unnamed)
+            .into_iter()
+            .filter_map(|mut field| self.keep(&mut field.attrs).then_some(field))
+            .collect();
+        visit_mut::visit_fields_unnamed_mut(self, node);
+    }
+
+    fn visit_block_mut(&mut self, node: &mut Block) {
+        node.stmts.retain_mut(|stmt| match stmt {
+            Stmt::Item(item) => self.keep_item(item),
+            Stmt::Local(local) => self.keep(&mut local.attrs),
+            _ => true,
+        });
+        visit_mut::visit_block_mut(self, node);
+    }
+}
+
+fn item_attrs_mut(item: &mut Item) -> Option<&mut Vec<Attribute>> {
+    match item {
+        Item::Const(item) => Some(&mut item.attrs),
+        Item::Enum(item) => Some(&mut item.attrs),
+        Item::ExternCrate(item) => Some(&mut item.attrs),
+        Item::Fn(item) => Some(&mut item.attrs),
+        Item::ForeignMod(item) => Some(&mut item.attrs),
+        Item::Impl(item) => Some(&mut item.attrs),
+        Item::Macro(item) => Some(&mut item.attrs),
+        Item::Mod(item) => Some(&mut item.attrs),
+        Item::Static(item) => Some(&mut item.attrs),
+        Item::Struct(item) => Some(&mut item.attrs),
+        Item::Trait(item) => Some(&mut item.attrs),
+        Item::TraitAlias(item) => Some(&mut item.attrs),
+        Item::Type(item) => Some(&mut item.attrs),
+        Item::Union(item) => Some(&mut item.attrs),
+        Item::Use(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+fn impl_item_attrs_mut(item: &mut ImplItem) -> Option<&mut Vec<Attribute>> {
+    match item {
+        ImplItem::Const(item) => Some(&mut item.attrs),
+        ImplItem::Fn(item) => Some(&mut item.attrs),
+        ImplItem::Type(item) => Some(&mut item.attrs),
+        ImplItem::Macro(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+fn trait_item_attrs_mut(item: &mut TraitItem) -> Option<&mut Vec<Attribute>> {
+    match item {
+        TraitItem::Const(item) => Some(&mut item.attrs),
+        TraitItem::Fn(item) => Some(&mut item.attrs),
+        TraitItem::Type(item) => Some(&mut item.attrs),
+        TraitItem::Macro(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::rust::attributes::has_section;
+use crate::rust::model::{FileItems, FnItem, Location};
+
+// 单个模块中公开条目的文档覆盖情况
+#[derive(Debug, Clone, Default)]
+pub struct ModuleCoverage {
+    pub module: String,
+    pub documented: usize,
+    pub total: usize,
+}
+
+impl ModuleCoverage {
+    pub fn percent(&self) -> f64 {
+        percent(self.documented, self.total)
+    }
+}
+
+// 缺少文档的公开条目，或文档缺少某个小节的函数
+#[derive(Debug, Clone)]
+pub struct CoverageIssue {
+    pub path: String,
+    pub file: PathBuf,
+    pub location: Location,
+    // 缺少的小节标题，例如 "Errors"；条目完全没有文档时为 None
+    pub missing_section: Option<&'static str>,
+}
+
+// 一个 crate 的文档覆盖率报告，只统计下游可以访问的公开条目
+#[derive(Debug, Clone, Default)]
+pub struct DocCoverage {
+    pub modules: Vec<ModuleCoverage>,
+    pub undocumented: Vec<CoverageIssue>,
+    pub missing_sections: Vec<CoverageIssue>,
+}
+
+impl DocCoverage {
+    // 统计函数、类型、trait、类型别名、常量，以及公开类型的方法、pub 字段和枚举变体。
+    // trait 实现中的方法沿用 trait 的文档，#[doc(hidden)] 的条目不出现在文档中，都不计入
+    pub fn compute<'a>(files: impl IntoIterator<Item = &'a FileItems>) -> Self {
+        let mut coverage = DocCoverage::default();
+        let mut modules: BTreeMap<String, ModuleCoverage> = BTreeMap::new();
+        for items in files {
+            let mut record = |path: &str, module: &str, location: &Location, docs: &Option<String>| {
+                let entry = modules.entry(module.to_string()).or_insert_with(|| ModuleCoverage {
+                    module: module.to_string(),
+                    ..ModuleCoverage::default()
+                });
+                entry.total += 1;
+                if docs.is_some() {
+                    entry.documented += 1;
+                } else {
+                    coverage.undocumented.push(CoverageIssue {
+                        path: path.to_string(),
+                        file: items.file.clone(),
+                        location: *location,
+                        missing_section: None,
+                    });
+                }
+            };
+
+            let mut fns: Vec<&FnItem> = Vec::new();
+            for func in items.functions.iter().filter(|f| f.exported && !f.attrs.doc_hidden) {
+                record(display_path(&func.path, &func.canonical_path), parent(&func.path), &func.location, &func.docs);
+                fns.push(func);
+            }
+            for item in items.types.iter().filter(|t| t.exported && !t.attrs.doc_hidden) {
+                let module = parent(&item.path);
+                let owner = display_path(&item.path, &item.canonical_path);
+                if item.kind.is_some() {
+                    record(owner, module, &item.location, &item.docs);
+                }
+                for field in item.fields.iter().filter(|f| f.vis.is_public() && !f.attrs.doc_hidden) {
+                    record(&format!("{}::{}", owner, field.name), module, &field.location, &field.docs);
+                }
+                for variant in item.variants.iter().filter(|v| !v.attrs.doc_hidden) {
+                    record(&format!("{}::{}", owner, variant.name), module, &variant.location, &variant.docs);
+                }
+                for method in item.methods.iter().filter(|m| m.exported && !m.attrs.doc_hidden) {
+                    record(display_path(&method.path, &method.canonical_path), module, &method.location, &method.docs);
+                    fns.push(method);
+                }
+            }
+            for def in items.traits.iter().filter(|d| d.exported && !d.attrs.doc_hidden) {
+                let module = parent(&def.path);
+                record(display_path(&def.path, &def.canonical_path), module, &def.location, &def.docs);
+                for method in def.methods.iter().filter(|m| !m.attrs.doc_hidden) {
+                    record(display_path(&method.path, &method.canonical_path), module, &method.location, &method.docs);
+                    fns.push(method);
+                }
+            }
+            for alias in items.aliases.iter().filter(|a| a.exported && !a.attrs.doc_hidden) {
+                record(display_path(&alias.path, &alias.canonical_path), parent(&alias.path), &alias.location, &alias.docs);
+            }
+            for value in items.values.iter().filter(|v| v.exported && !v.attrs.doc_hidden) {
+                record(display_path(&value.path, &value.canonical_path), parent(&value.path), &value.location, &value.docs);
+            }
+
+            // 已有文档的函数检查是否缺少应有的小节
+            for func in fns {
+                let Some(docs) = &func.docs else {
+                    continue;
+                };
+                for section in required_sections(func) {
+                    if !has_section(docs, section) {
+                        coverage.missing_sections.push(CoverageIssue {
+                            path: display_path(&func.path, &func.canonical_path).to_string(),
+                            file: items.file.clone(),
+                            location: func.location,
+                            missing_section: Some(section),
+                        });
+                    }
+                }
+            }
+        }
+        coverage.modules = modules.into_values().collect();
+        coverage
+    }
+
+    pub fn documented(&self) -> usize {
+        self.modules.iter().map(|m| m.documented).sum()
+    }
+
+    pub fn total(&self) -> usize {
+        self.modules.iter().map(|m| m.total).sum()
+    }
+
+    pub fn percent(&self) -> f64 {
+        percent(self.documented(), self.total())
+    }
+}
+
+// 函数文档应有的小节：返回 Result 的要说明 Errors，会 panic 的要说明 Panics，unsafe 的要说明 Safety
+fn required_sections(func: &FnItem) -> Vec<&'static str> {
+    let mut sections = Vec::new();
+    if func.sig.output.as_deref().is_some_and(is_result) {
+        sections.push("Errors");
+    }
+    if func.calls_panic {
+        sections.push("Panics");
+    }
+    if func.sig.is_unsafe {
+        sections.push("Safety");
+    }
+    sections
+}
+
+// 返回类型的最后一段是否为 Result，例如 "Result<T, E>"、"io::Result<()>"
+fn is_result(ty: &str) -> bool {
+    let base = ty.split('<').next().unwrap_or(ty);
+    base.rsplit("::").next() == Some("Result")
+}
+
+fn display_path<'a>(path: &'a str, canonical_path: &'a Option<String>) -> &'a str {
+    canonical_path.as_deref().unwrap_or(path)
+}
+
+// 条目所在模块的路径
+fn parent(path: &str) -> &str {
+    path.rsplit_once("::").map_or("", |(parent, _)| parent)
+}
+
+fn percent(documented: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        documented as f64 * 100.0 / total as f64
+    }
+}
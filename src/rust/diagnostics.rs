@@ -0,0 +1,64 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 单个文件无法分析的原因，例如读取失败、不是 UTF-8 编码或语法错误
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    // 从 1 开始的行号和列号，无法定位时为 None
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(file: &Path, message: impl Into<String>) -> Self {
+        Diagnostic {
+            file: file.to_path_buf(),
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    // 语法错误的位置取自 syn::Error 的 span（syn 的列号从 0 开始）
+    pub fn from_syn(file: &Path, error: &syn::Error) -> Self {
+        let start = error.span().start();
+        Diagnostic {
+            file: file.to_path_buf(),
+            line: Some(start.line),
+            column: Some(start.column + 1),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, ":{}:{}", line, column)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+// 读取并解析 Rust 源文件，失败时返回带位置的诊断信息
+pub fn parse_source(path: &Path) -> Result<syn::File, Diagnostic> {
+    let bytes = fs::read(path).map_err(|e| Diagnostic::new(path, format!("无法读取文件: {}", e)))?;
+    let content = String::from_utf8(bytes).map_err(|e| {
+        // 定位到第一个无效字节
+        let valid = &e.as_bytes()[..e.utf8_error().valid_up_to()];
+        let line = valid.iter().filter(|&&b| b == b'\n').count() + 1;
+        let line_start = valid.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        let column = String::from_utf8_lossy(&valid[line_start..]).chars().count() + 1;
+        Diagnostic {
+            file: path.to_path_buf(),
+            line: Some(line),
+            column: Some(column),
+            message: "文件不是有效的 UTF-8 编码".to_string(),
+        }
+    })?;
+    syn::parse_file(&content).map_err(|e| Diagnostic::from_syn(path, &e))
+}
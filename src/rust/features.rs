@@ -0,0 +1,97 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Cargo.toml 中声明的特性，以及可选依赖隐式定义的同名特性
+#[derive(Debug, Clone, Default)]
+pub struct FeatureTable {
+    pub manifest: PathBuf,
+    // 特性名到它启用的其他条目，例如 "full" => ["std", "dep:serde", "tokio/rt"]
+    pub features: BTreeMap<String, Vec<String>>,
+}
+
+impl FeatureTable {
+    pub fn load(manifest: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(manifest).map_err(|e| format!("无法读取 {}: {}", manifest.display(), e))?;
+        let value: toml::Table =
+            toml::from_str(&content).map_err(|e| format!("无法解析 {}: {}", manifest.display(), e))?;
+
+        let mut features: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        if let Some(table) = value.get("features").and_then(|v| v.as_table()) {
+            for (name, enables) in table {
+                let enables = enables
+                    .as_array()
+                    .map(|list| list.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                    .unwrap_or_default();
+                features.insert(name.clone(), enables);
+            }
+        }
+
+        // 可选依赖在任何特性都没有用 "dep:名称" 引用它时，隐式定义一个同名特性
+        let explicit_deps: BTreeSet<&str> = features
+            .values()
+            .flatten()
+            .filter_map(|entry| entry.strip_prefix("dep:"))
+            .collect();
+        let mut implicit = Vec::new();
+        for section in ["dependencies", "build-dependencies"] {
+            let Some(deps) = value.get(section).and_then(|v| v.as_table()) else {
+                continue;
+            };
+            for (name, spec) in deps {
+                let optional = spec.get("optional").and_then(|v| v.as_bool()).unwrap_or(false);
+                if optional && !explicit_deps.contains(name.as_str()) && !features.contains_key(name) {
+                    implicit.push(name.clone());
+                }
+            }
+        }
+        for name in implicit {
+            features.insert(name.clone(), vec![format!("dep:{}", name)]);
+        }
+
+        Ok(FeatureTable {
+            manifest: manifest.to_path_buf(),
+            features,
+        })
+    }
+
+    // 从 crate 根文件所在目录向上查找最近的 Cargo.toml
+    pub fn find_manifest(root: &Path) -> Option<PathBuf> {
+        root.ancestors().skip(1).map(|dir| dir.join("Cargo.toml")).find(|path| path.is_file())
+    }
+
+    // 计算实际启用的特性：请求的特性、default（除非 --no-default-features），以及它们传递启用的特性。
+    // "dep:x" 只启用依赖；"x/y" 启用依赖 x 对应的同名特性（如果有）；"x?/y" 不启用 x
+    pub fn resolve(&self, requested: &[String], default_features: bool) -> BTreeSet<String> {
+        let mut enabled = BTreeSet::new();
+        let mut pending: Vec<String> = requested.to_vec();
+        if default_features && self.features.contains_key("default") {
+            pending.push("default".to_string());
+        }
+        while let Some(feature) = pending.pop() {
+            if !enabled.insert(feature.clone()) {
+                continue;
+            }
+            for entry in self.features.get(&feature).into_iter().flatten() {
+                if entry.starts_with("dep:") {
+                    continue;
+                }
+                match entry.split_once('/') {
+                    Some((dep, _)) if dep.ends_with('?') => {}
+                    Some((dep, _)) => {
+                        if self.features.contains_key(dep) {
+                            pending.push(dep.to_string());
+                        }
+                    }
+                    None => pending.push(entry.clone()),
+                }
+            }
+        }
+        enabled
+    }
+
+    // Cargo.toml 中没有声明的特性
+    pub fn unknown<'a>(&self, requested: &'a [String]) -> Vec<&'a String> {
+        requested.iter().filter(|f| !self.features.contains_key(*f)).collect()
+    }
+}
@@ -0,0 +1,52 @@
+use crate::rust::model::{FnItem, FnKind};
+
+// 可用于筛选报告的函数特征
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FnQualifier {
+    Const,
+    Async,
+    Unsafe,
+    Extern,
+    Static,
+    Constructor,
+}
+
+pub const QUALIFIER_NAMES: &[&str] = &["const", "async", "unsafe", "extern", "static", "constructor"];
+
+impl FnQualifier {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "const" => Some(FnQualifier::Const),
+            "async" => Some(FnQualifier::Async),
+            "unsafe" => Some(FnQualifier::Unsafe),
+            "extern" => Some(FnQualifier::Extern),
+            "static" => Some(FnQualifier::Static),
+            "constructor" => Some(FnQualifier::Constructor),
+            _ => None,
+        }
+    }
+
+    pub fn matches(self, item: &FnItem) -> bool {
+        match self {
+            FnQualifier::Const => item.sig.is_const,
+            FnQualifier::Async => item.sig.is_async,
+            FnQualifier::Unsafe => item.sig.is_unsafe,
+            FnQualifier::Extern => item.sig.abi.is_some(),
+            // 普通函数本来就没有接收者，只有关联函数才区分静态与否
+            FnQualifier::Static => item.kind != FnKind::Function && item.is_static(),
+            FnQualifier::Constructor => item.kind == FnKind::Constructor,
+        }
+    }
+}
+
+// 解析逗号分隔的限定符列表，例如 "async,unsafe"
+pub fn parse_qualifiers(list: &str) -> Result<Vec<FnQualifier>, String> {
+    list.split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| {
+            FnQualifier::parse(name).ok_or_else(|| {
+                format!("未知的限定符 '{}'，可选值: {}", name.trim(), QUALIFIER_NAMES.join(", "))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::rust::callgraph::{self, CallGraph};
+use crate::rust::coverage::DocCoverage;
+use crate::rust::metrics::{self, MetricKey, MetricsSummary};
+use crate::rust::model::{FileItems, FnMetrics, Location};
+use crate::rust::project::CrateAnalysis;
+use crate::rust::trait_matrix::TraitMatrix;
+
+// code-parser 默认的输出目录，日志和调用图等附加输出都写在这里
+pub const DEFAULT_OUTPUT_DIR: &str = "~/.parser/output";
+
+// 把开头的 ~ 换成主目录
+pub fn expand_home(dir: &str) -> PathBuf {
+    match (dir.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(dir),
+    }
+}
+
+// code-parser 配置的输出目录：~/.parser/config.json 中的 output_dir，没有配置时为 DEFAULT_OUTPUT_DIR
+pub fn configured_output_dir() -> PathBuf {
+    let configured = dirs::home_dir()
+        .and_then(|home| fs::read_to_string(home.join(".parser").join("config.json")).ok())
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .and_then(|config| config["output_dir"].as_str().map(String::from));
+    expand_home(configured.as_deref().unwrap_or(DEFAULT_OUTPUT_DIR))
+}
+
+// 条目位置，附在每行末尾，例如 " @12:5-14:6 (120..180)"
+pub fn at(location: &Location) -> String {
+    format!(" @{}", location.render())
+}
+
+// 写入一个 crate 的文档覆盖率：各模块的百分比、缺少文档的公开条目、缺少的文档小节
+pub fn write_coverage(log_file: &mut impl Write, root: &Path, coverage: &DocCoverage) -> Result<()> {
+    writeln!(log_file, "文档覆盖率 ({}):", root.display())?;
+    for module in &coverage.modules {
+        writeln!(
+            log_file,
+            "  {}: {}/{} ({:.1}%)",
+            module.module,
+            module.documented,
+            module.total,
+            module.percent()
+        )?;
+    }
+    writeln!(
+        log_file,
+        "  总计: {}/{} ({:.1}%)",
+        coverage.documented(),
+        coverage.total(),
+        coverage.percent()
+    )?;
+    if !coverage.undocumented.is_empty() {
+        writeln!(log_file, "  缺少文档的公开条目:")?;
+        for issue in &coverage.undocumented {
+            writeln!(log_file, "    {} ({}:{})", issue.path, issue.file.display(), issue.location.start_line)?;
+        }
+    }
+    if !coverage.missing_sections.is_empty() {
+        writeln!(log_file, "  缺少的文档小节:")?;
+        for issue in &coverage.missing_sections {
+            writeln!(
+                log_file,
+                "    {}: # {} ({}:{})",
+                issue.path,
+                issue.missing_section.unwrap_or_default(),
+                issue.file.display(),
+                issue.location.start_line
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// 写入一个 crate 的特性矩阵：每个特性传递启用的特性，以及它新增的条目
+pub fn write_feature_matrix(
+    log_file: &mut impl Write,
+    root: &Path,
+    matrix: &[(String, BTreeSet<String>, Vec<String>)],
+) -> Result<()> {
+    writeln!(log_file, "特性矩阵 ({}):", root.display())?;
+    for (feature, enabled, added) in matrix {
+        let enabled: Vec<&str> = enabled.iter().map(String::as_str).collect();
+        writeln!(log_file, "  {} (启用 {}): 新增 {} 个条目", feature, enabled.join(", "), added.len())?;
+        for path in added {
+            writeln!(log_file, "    + {}", path)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn format_metrics(metrics: &FnMetrics) -> String {
+    format!(
+        "圈复杂度 {}，认知复杂度 {}，最大嵌套 {}，参数 {}，行数 {}，语句数 {}",
+        metrics.cyclomatic, metrics.cognitive, metrics.max_nesting, metrics.params, metrics.loc, metrics.statements
+    )
+}
+
+pub fn format_summary(summary: &MetricsSummary) -> String {
+    format!(
+        "{} 个函数，圈复杂度 合计 {} 平均 {:.1} 最大 {}，认知复杂度 合计 {} 最大 {}，最大嵌套 {}，行数 {}，语句数 {}",
+        summary.functions,
+        summary.cyclomatic,
+        summary.average_cyclomatic(),
+        summary.max_cyclomatic,
+        summary.cognitive,
+        summary.max_cognitive,
+        summary.max_nesting,
+        summary.loc,
+        summary.statements
+    )
+}
+
+// 写入一个 crate 中每个函数的度量，以及按文件和按模块的汇总
+pub fn write_metrics(log_file: &mut impl Write, root: &Path, files: &[FileItems]) -> Result<()> {
+    writeln!(log_file, "复杂度 ({}):", root.display())?;
+    for items in files {
+        let summary = metrics::summarize_file(items);
+        if summary.functions == 0 {
+            continue;
+        }
+        writeln!(log_file, "  文件 {}: {}", items.file.display(), format_summary(&summary))?;
+        for (func, metrics) in metrics::measured_fns(items) {
+            writeln!(log_file, "    {}{}: {}", func.path, at(&func.location), format_metrics(metrics))?;
+        }
+    }
+    for (module, summary) in metrics::summarize_modules(files) {
+        writeln!(log_file, "  模块 {}: {}", module, format_summary(&summary))?;
+    }
+    Ok(())
+}
+
+// 写入所有 crate 中按某项度量从高到低排列的前 top 个函数
+pub fn write_ranking(log_file: &mut impl Write, crates: &[CrateAnalysis], key: MetricKey, top: usize) -> Result<()> {
+    let ranked = metrics::rank(crates.iter().flat_map(|c| &c.files), key);
+    writeln!(log_file, "复杂度排名（按{}，前 {} 个）:", key.label(), top.min(ranked.len()))?;
+    for (index, (items, func, metrics)) in ranked.iter().take(top).enumerate() {
+        writeln!(
+            log_file,
+            "  {}. {} = {}: {} ({}:{})",
+            index + 1,
+            func.path,
+            key.value(metrics),
+            format_metrics(metrics),
+            items.file.display(),
+            func.location.start_line
+        )?;
+    }
+    Ok(())
+}
+
+// 把各 crate 的调用图写入 dir 下的 call_graph.json / .dot / .mmd，返回提示信息
+pub fn write_call_graph(dir: &Path, crates: &[CrateAnalysis], format: &str) -> Result<String> {
+    let graphs: Vec<CallGraph> = crates
+        .iter()
+        .map(|c| CallGraph::build(&c.root, &c.files))
+        .filter(|graph| !graph.nodes.is_empty())
+        .collect();
+    let (content, extension) = match format {
+        "dot" => (callgraph::to_dot(&graphs), "dot"),
+        "mermaid" => (callgraph::to_mermaid(&graphs), "mmd"),
+        _ => (callgraph::to_json(&graphs)?, "json"),
+    };
+    let graph_path = dir.join(format!("call_graph.{}", extension));
+    fs::write(&graph_path, content)?;
+    let edges: usize = graphs.iter().map(|graph| graph.edges.len()).sum();
+    Ok(format!("调用图已写入到 {}（{} 个 crate，{} 条调用）", graph_path.display(), graphs.len(), edges))
+}
+
+// 把 trait 实现矩阵写入 dir 下的 trait_matrix.json / .md / .html，返回提示信息。
+// 矩阵跨越所有 crate，类型路径开头的 crate 换成编译目标的名称
+pub fn write_trait_matrix(dir: &Path, crates: &[CrateAnalysis], format: &str) -> Result<String> {
+    let matrix = TraitMatrix::build(
+        crates
+            .iter()
+            .map(|c| (c.crate_name(), c.files.as_slice())),
+    );
+    let (content, extension) = match format {
+        "markdown" => (matrix.to_markdown(), "md"),
+        "html" => (matrix.to_html(), "html"),
+        _ => (matrix.to_json()?, "json"),
+    };
+    let matrix_path = dir.join(format!("trait_matrix.{}", extension));
+    fs::write(&matrix_path, content)?;
+    Ok(format!(
+        "trait 实现矩阵已写入到 {}（{} 个类型，{} 个 trait）",
+        matrix_path.display(),
+        matrix.types.len(),
+        matrix.traits.len()
+    ))
+}
@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Delimiter, Group, Ident, Spacing, TokenStream, TokenTree};
+use syn::ext::IdentExt;
+use syn::parse::{ParseStream, Parser};
+use syn::visit::{self, Visit};
+use syn::{Item, ItemMacro, Macro};
+
+// 展开宏时识别的片段类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fragment {
+    Ident,
+    Ty,
+    Expr,
+    Path,
+    Literal,
+    Lifetime,
+    Block,
+    Pat,
+    Item,
+    Stmt,
+    Vis,
+    Meta,
+    Tt,
+}
+
+impl Fragment {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "ident" => Fragment::Ident,
+            "ty" => Fragment::Ty,
+            "expr" | "expr_2021" => Fragment::Expr,
+            "path" => Fragment::Path,
+            "literal" => Fragment::Literal,
+            "lifetime" => Fragment::Lifetime,
+            "block" => Fragment::Block,
+            "pat" | "pat_param" => Fragment::Pat,
+            "item" => Fragment::Item,
+            "stmt" => Fragment::Stmt,
+            "vis" => Fragment::Vis,
+            "meta" => Fragment::Meta,
+            "tt" => Fragment::Tt,
+            _ => return None,
+        })
+    }
+
+    // 从 tokens 开头解析一个片段，返回消耗的 token 数量
+    fn consume(self, tokens: &[TokenTree]) -> Option<usize> {
+        if self == Fragment::Tt {
+            return (!tokens.is_empty()).then_some(1);
+        }
+        let stream: TokenStream = tokens.iter().cloned().collect();
+        let parser = |input: ParseStream| -> syn::Result<usize> {
+            match self {
+                Fragment::Ident => drop(input.call(syn::Ident::parse_any)?),
+                Fragment::Ty => drop(input.parse::<syn::Type>()?),
+                Fragment::Expr => drop(input.parse::<syn::Expr>()?),
+                Fragment::Path => drop(input.parse::<syn::Path>()?),
+                Fragment::Literal => drop(input.parse::<syn::Lit>()?),
+                Fragment::Lifetime => drop(input.parse::<syn::Lifetime>()?),
+                Fragment::Block => drop(input.parse::<syn::Block>()?),
+                Fragment::Pat => drop(syn::Pat::parse_single(input)?),
+                Fragment::Item => drop(input.parse::<syn::Item>()?),
+                Fragment::Stmt => drop(input.parse::<syn::Stmt>()?),
+                Fragment::Vis => drop(input.parse::<syn::Visibility>()?),
+                Fragment::Meta => drop(input.parse::<syn::Meta>()?),
+                Fragment::Tt => unreachable!(),
+            }
+            let rest: TokenStream = input.parse()?;
+            Ok(rest.into_iter().count())
+        };
+        let rest = parser.parse2(stream).ok()?;
+        Some(tokens.len() - rest)
+    }
+}
+
+// 宏规则左侧（匹配器）的组成部分
+#[derive(Debug, Clone)]
+enum Matcher {
+    // 需要逐字匹配的 token
+    Token(String),
+    Group(Delimiter, Vec<Matcher>),
+    Fragment(String, Fragment),
+    // `$( ... ) sep op`，没有分隔符时 sep 为空
+    Repeat(Vec<Matcher>, Vec<String>, char),
+}
+
+// 宏规则右侧（展开模板）的组成部分
+#[derive(Debug, Clone)]
+enum Transcriber {
+    Token(TokenTree),
+    Group(Delimiter, Vec<Transcriber>),
+    Var(Ident),
+    Repeat(Vec<Transcriber>, Vec<TokenTree>),
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    matcher: Vec<Matcher>,
+    transcriber: Vec<Transcriber>,
+}
+
+// 匹配得到的变量绑定，重复中的变量按每次重复保存
+#[derive(Debug, Clone)]
+enum Binding {
+    One(Fragment, TokenStream),
+    Many(Vec<Bindings>),
+}
+
+type Bindings = HashMap<String, Binding>;
+
+// crate 中定义的 macro_rules! 宏，按名称索引
+#[derive(Debug, Clone, Default)]
+pub struct MacroTable {
+    macros: HashMap<String, Vec<Rule>>,
+}
+
+impl MacroTable {
+    // 收集语法树中（包括内联模块和函数体中）的所有 macro_rules! 定义。
+    // 不区分宏的文本作用域，同名宏以后出现的定义为准
+    pub fn collect<'a>(files: impl IntoIterator<Item = &'a syn::File>) -> Self {
+        let mut collector = MacroCollector::default();
+        for file in files {
+            collector.visit_file(file);
+        }
+        collector.table
+    }
+
+    // 宏调用对应本地的 macro_rules! 宏时返回宏名
+    pub fn lookup(&self, mac: &Macro) -> Option<String> {
+        let name = mac.path.segments.last()?.ident.to_string();
+        self.macros.contains_key(&name).then_some(name)
+    }
+
+    // 展开条目位置的宏调用，得到生成的条目。
+    // 没有规则能匹配或展开结果不是合法的条目时返回错误信息
+    pub fn expand(&self, mac: &Macro) -> Result<Vec<Item>, String> {
+        let name = self.lookup(mac).ok_or_else(|| "不是本地定义的宏".to_string())?;
+        let input: Vec<TokenTree> = mac.tokens.clone().into_iter().collect();
+        for rule in &self.macros[&name] {
+            let mut bindings = Bindings::new();
+            if match_sequence(&rule.matcher, &input, &mut bindings) != Some(input.len()) {
+                continue;
+            }
+            let mut output = TokenStream::new();
+            transcribe(&rule.transcriber, &[&bindings], &mut output)?;
+            let file: syn::File = syn::parse2(output).map_err(|e| format!("展开 {}! 的结果无法解析: {}", name, e))?;
+            return Ok(file.items);
+        }
+        Err(format!("{}! 的调用没有匹配的规则", name))
+    }
+}
+
+#[derive(Default)]
+struct MacroCollector {
+    table: MacroTable,
+}
+
+impl<'ast> Visit<'ast> for MacroCollector {
+    fn visit_item_macro(&mut self, node: &'ast ItemMacro) {
+        if let Some(ident) = &node.ident {
+            if node.mac.path.is_ident("macro_rules") {
+                if let Some(rules) = parse_rules(node.mac.tokens.clone()) {
+                    self.table.macros.insert(ident.to_string(), rules);
+                }
+            }
+        }
+        visit::visit_item_macro(self, node);
+    }
+}
+
+// 解析 macro_rules! 的规则列表：`(matcher) => { transcriber };`
+fn parse_rules(tokens: TokenStream) -> Option<Vec<Rule>> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut rules = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let (TokenTree::Group(matcher), Some(TokenTree::Punct(eq)), Some(TokenTree::Punct(gt)), Some(TokenTree::Group(body))) =
+            (&tokens[i], tokens.get(i + 1), tokens.get(i + 2), tokens.get(i + 3))
+        else {
+            return None;
+        };
+        if eq.as_char() != '=' || gt.as_char() != '>' {
+            return None;
+        }
+        rules.push(Rule {
+            matcher: parse_matcher(matcher.stream())?,
+            transcriber: parse_transcriber(body.stream()),
+        });
+        i += 4;
+        if matches!(tokens.get(i), Some(TokenTree::Punct(p)) if p.as_char() == ';') {
+            i += 1;
+        }
+    }
+    Some(rules)
+}
+
+fn parse_matcher(stream: TokenStream) -> Option<Vec<Matcher>> {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            TokenTree::Punct(p) if p.as_char() == '$' => match tokens.get(i + 1) {
+                // `$name:fragment`
+                Some(TokenTree::Ident(name)) => {
+                    let fragment = match (tokens.get(i + 2), tokens.get(i + 3)) {
+                        (Some(TokenTree::Punct(colon)), Some(TokenTree::Ident(kind))) if colon.as_char() == ':' => {
+                            Fragment::parse(&kind.to_string())?
+                        }
+                        _ => return None,
+                    };
+                    out.push(Matcher::Fragment(name.unraw().to_string(), fragment));
+                    i += 4;
+                }
+                // `$( ... ) sep op`
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    let inner = parse_matcher(group.stream())?;
+                    let (separator, op, used) = repeat_suffix(&tokens[i + 2..])?;
+                    out.push(Matcher::Repeat(inner, separator.iter().map(TokenTree::to_string).collect(), op));
+                    i += 2 + used;
+                }
+                _ => return None,
+            },
+            TokenTree::Group(group) => {
+                out.push(Matcher::Group(group.delimiter(), parse_matcher(group.stream())?));
+                i += 1;
+            }
+            token => {
+                out.push(Matcher::Token(token.to_string()));
+                i += 1;
+            }
+        }
+    }
+    Some(out)
+}
+
+fn parse_transcriber(stream: TokenStream) -> Vec<Transcriber> {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match (&tokens[i], tokens.get(i + 1)) {
+            (TokenTree::Punct(p), Some(TokenTree::Ident(name))) if p.as_char() == '$' => {
+                out.push(Transcriber::Var(name.clone()));
+                i += 2;
+            }
+            (TokenTree::Punct(p), Some(TokenTree::Group(group)))
+                if p.as_char() == '$' && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                match repeat_suffix(&tokens[i + 2..]) {
+                    Some((separator, _, used)) => {
+                        out.push(Transcriber::Repeat(parse_transcriber(group.stream()), separator));
+                        i += 2 + used;
+                    }
+                    None => {
+                        out.push(Transcriber::Token(tokens[i].clone()));
+                        i += 1;
+                    }
+                }
+            }
+            (TokenTree::Group(group), _) => {
+                out.push(Transcriber::Group(group.delimiter(), parse_transcriber(group.stream())));
+                i += 1;
+            }
+            (token, _) => {
+                out.push(Transcriber::Token(token.clone()));
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+// 解析重复后面的可选分隔符和重复运算符（`*`、`+`、`?`），返回 (分隔符, 运算符, 消耗的 token 数)。
+// 分隔符是一个标识符、字面量或运算符，由多个字符组成的运算符（例如 `=>`、`::`）在 TokenStream 中是多个连写的标点
+fn repeat_suffix(tokens: &[TokenTree]) -> Option<(Vec<TokenTree>, char, usize)> {
+    let is_op = |token: Option<&TokenTree>| match token {
+        Some(TokenTree::Punct(p)) if matches!(p.as_char(), '*' | '+' | '?') => Some(p.as_char()),
+        _ => None,
+    };
+    if let Some(op) = is_op(tokens.first()) {
+        return Some((Vec::new(), op, 1));
+    }
+    // 连写的标点，运算符紧跟在分隔符后面时也会连写，例如 `=>*`、`,*`
+    let mut end = 0;
+    while let (Some(TokenTree::Punct(p)), Some(TokenTree::Punct(_))) = (tokens.get(end), tokens.get(end + 1)) {
+        if p.spacing() != Spacing::Joint {
+            break;
+        }
+        end += 1;
+    }
+    if end > 0 {
+        if let Some(op) = is_op(tokens.get(end)) {
+            return Some((tokens[..end].to_vec(), op, end + 1));
+        }
+    }
+    let op = is_op(tokens.get(end + 1))?;
+    Some((tokens[..=end].to_vec(), op, end + 2))
+}
+
+// 按顺序匹配，成功时返回消耗的 token 数量
+fn match_sequence(matchers: &[Matcher], tokens: &[TokenTree], bindings: &mut Bindings) -> Option<usize> {
+    let mut pos = 0;
+    for matcher in matchers {
+        let rest = &tokens[pos..];
+        match matcher {
+            Matcher::Token(text) => match rest.first() {
+                Some(token) if token.to_string() == *text => pos += 1,
+                _ => return None,
+            },
+            Matcher::Group(delimiter, inner) => match rest.first() {
+                Some(TokenTree::Group(group)) if group.delimiter() == *delimiter => {
+                    let inner_tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+                    if match_sequence(inner, &inner_tokens, bindings)? != inner_tokens.len() {
+                        return None;
+                    }
+                    pos += 1;
+                }
+                _ => return None,
+            },
+            Matcher::Fragment(name, fragment) => {
+                let used = fragment.consume(rest)?;
+                bindings.insert(name.clone(), Binding::One(*fragment, rest[..used].iter().cloned().collect()));
+                pos += used;
+            }
+            Matcher::Repeat(inner, separator, op) => {
+                let (iterations, used) = match_repeat(inner, separator, *op, rest)?;
+                for name in fragment_names(inner) {
+                    let per_iteration = iterations
+                        .iter()
+                        .filter_map(|b| b.get(&name).map(|binding| Bindings::from([(name.clone(), binding.clone())])))
+                        .collect();
+                    bindings.insert(name, Binding::Many(per_iteration));
+                }
+                pos += used;
+            }
+        }
+    }
+    Some(pos)
+}
+
+fn match_repeat(inner: &[Matcher], separator: &[String], op: char, tokens: &[TokenTree]) -> Option<(Vec<Bindings>, usize)> {
+    let mut iterations = Vec::new();
+    let mut pos = 0;
+    loop {
+        let mut start = pos;
+        if !iterations.is_empty() && !separator.is_empty() {
+            let found = tokens.get(pos..pos + separator.len()).is_some_and(|candidate| {
+                candidate.iter().zip(separator).all(|(token, text)| token.to_string() == *text)
+            });
+            if !found {
+                break;
+            }
+            start += separator.len();
+        }
+        let mut bindings = Bindings::new();
+        match match_sequence(inner, &tokens[start..], &mut bindings) {
+            // 不消耗 token 的重复会无限循环
+            Some(used) if used > 0 => {
+                iterations.push(bindings);
+                pos = start + used;
+            }
+            _ => break,
+        }
+        if op == '?' {
+            break;
+        }
+    }
+    if op == '+' && iterations.is_empty() {
+        return None;
+    }
+    Some((iterations, pos))
+}
+
+fn fragment_names(matchers: &[Matcher]) -> Vec<String> {
+    let mut names = Vec::new();
+    for matcher in matchers {
+        match matcher {
+            Matcher::Fragment(name, _) => names.push(name.clone()),
+            Matcher::Group(_, inner) | Matcher::Repeat(inner, _, _) => names.extend(fragment_names(inner)),
+            Matcher::Token(_) => {}
+        }
+    }
+    names
+}
+
+// 在绑定栈中查找变量，内层重复的绑定优先
+fn find<'a>(scopes: &[&'a Bindings], name: &str) -> Option<&'a Binding> {
+    scopes.iter().rev().find_map(|scope| scope.get(name))
+}
+
+fn transcribe(parts: &[Transcriber], scopes: &[&Bindings], out: &mut TokenStream) -> Result<(), String> {
+    for part in parts {
+        match part {
+            Transcriber::Token(token) => out.extend([token.clone()]),
+            Transcriber::Group(delimiter, inner) => {
+                let mut stream = TokenStream::new();
+                transcribe(inner, scopes, &mut stream)?;
+                out.extend([TokenTree::Group(Group::new(*delimiter, stream))]);
+            }
+            Transcriber::Var(name) if name == "crate" => {
+                out.extend([TokenTree::Ident(Ident::new("crate", name.span()))]);
+            }
+            Transcriber::Var(name) => match find(scopes, &name.unraw().to_string()) {
+                // 表达式和类型片段保持为一个整体，避免展开后改变运算优先级
+                Some(Binding::One(Fragment::Expr | Fragment::Ty, tokens)) => {
+                    out.extend([TokenTree::Group(Group::new(Delimiter::None, tokens.clone()))]);
+                }
+                Some(Binding::One(_, tokens)) => out.extend(tokens.clone()),
+                Some(Binding::Many(_)) => return Err(format!("变量 ${} 仍在重复中，不能直接使用", name)),
+                None => {
+                    let dollar = proc_macro2::Punct::new('$', Spacing::Alone);
+                    out.extend([TokenTree::Punct(dollar), TokenTree::Ident(name.clone())]);
+                }
+            },
+            Transcriber::Repeat(inner, separator) => {
+                let mut names = Vec::new();
+                repeat_vars(inner, &mut names);
+                // 重复次数由其中用到的、处于重复中的变量决定
+                let repeated: Vec<&Vec<Bindings>> = names
+                    .iter()
+                    .filter_map(|name| match find(scopes, name) {
+                        Some(Binding::Many(iterations)) => Some(iterations),
+                        _ => None,
+                    })
+                    .collect();
+                let Some(count) = repeated.first().map(|iterations| iterations.len()) else {
+                    return Err("重复中没有使用任何重复变量".to_string());
+                };
+                if repeated.iter().any(|iterations| iterations.len() != count) {
+                    return Err("重复中的变量重复次数不一致".to_string());
+                }
+                for index in 0..count {
+                    if index > 0 {
+                        out.extend(separator.iter().cloned());
+                    }
+                    let mut merged = Bindings::new();
+                    for iterations in &repeated {
+                        merged.extend(iterations[index].iter().map(|(k, v)| (k.clone(), v.clone())));
+                    }
+                    let mut inner_scopes = scopes.to_vec();
+                    inner_scopes.push(&merged);
+                    transcribe(inner, &inner_scopes, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn repeat_vars(parts: &[Transcriber], names: &mut Vec<String>) {
+    for part in parts {
+        match part {
+            Transcriber::Var(name) => names.push(name.unraw().to_string()),
+            Transcriber::Group(_, inner) | Transcriber::Repeat(inner, _) => repeat_vars(inner, names),
+            Transcriber::Token(_) => {}
+        }
+    }
+}
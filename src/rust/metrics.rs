@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    BinOp, Block, Expr, ExprBinary, ExprBreak, ExprClosure, ExprContinue, ExprForLoop, ExprIf, ExprLoop, ExprMatch,
+    ExprTry, ExprWhile, Item, Signature, Stmt, Token,
+};
+
+use crate::rust::model::{FileItems, FnItem, FnMetrics, Location};
+
+// 计算函数的复杂度和规模
+pub fn fn_metrics(sig: &Signature, body: &Block, location: &Location) -> FnMetrics {
+    let mut counter = Counter {
+        cyclomatic: 1,
+        ..Counter::default()
+    };
+    counter.visit_block(body);
+    FnMetrics {
+        cyclomatic: counter.cyclomatic,
+        cognitive: counter.cognitive,
+        max_nesting: counter.max_nesting,
+        params: sig.inputs.len(),
+        loc: location.end_line.saturating_sub(location.start_line) + 1,
+        statements: counter.statements,
+    }
+}
+
+#[derive(Default)]
+struct Counter {
+    cyclomatic: usize,
+    cognitive: usize,
+    nesting: usize,
+    max_nesting: usize,
+    statements: usize,
+    // 正在访问的逻辑运算符序列中的运算符，同一序列中相同的运算符只计一次认知复杂度
+    logical: Option<bool>,
+}
+
+impl Counter {
+    // 控制流结构的主体嵌套一层
+    fn nested(&mut self, f: impl FnOnce(&mut Self)) {
+        self.nesting += 1;
+        self.max_nesting = self.max_nesting.max(self.nesting);
+        let logical = self.logical.take();
+        f(self);
+        self.logical = logical;
+        self.nesting -= 1;
+    }
+
+    // if 及其 else if / else 分支：只有开头的 if 按嵌套层数加权
+    fn visit_if_chain(&mut self, node: &ExprIf) {
+        self.visit_expr(&node.cond);
+        self.nested(|v| v.visit_block(&node.then_branch));
+        if let Some((_, else_branch)) = &node.else_branch {
+            self.cognitive += 1;
+            match &**else_branch {
+                Expr::If(inner) => {
+                    self.cyclomatic += 1;
+                    self.visit_if_chain(inner);
+                }
+                other => self.nested(|v| v.visit_expr(other)),
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Counter {
+    fn visit_expr_if(&mut self, node: &'ast ExprIf) {
+        self.cyclomatic += 1;
+        self.cognitive += 1 + self.nesting;
+        self.visit_if_chain(node);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast ExprMatch) {
+        self.cyclomatic += node.arms.len().saturating_sub(1);
+        self.cyclomatic += node.arms.iter().filter(|arm| arm.guard.is_some()).count();
+        self.cognitive += 1 + self.nesting;
+        self.visit_expr(&node.expr);
+        self.nested(|v| {
+            for arm in &node.arms {
+                v.visit_arm(arm);
+            }
+        });
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast ExprForLoop) {
+        self.cyclomatic += 1;
+        self.cognitive += 1 + self.nesting;
+        self.visit_expr(&node.expr);
+        self.nested(|v| v.visit_block(&node.body));
+    }
+
+    fn visit_expr_while(&mut self, node: &'ast ExprWhile) {
+        self.cyclomatic += 1;
+        self.cognitive += 1 + self.nesting;
+        self.visit_expr(&node.cond);
+        self.nested(|v| v.visit_block(&node.body));
+    }
+
+    fn visit_expr_loop(&mut self, node: &'ast ExprLoop) {
+        self.cognitive += 1 + self.nesting;
+        self.nested(|v| v.visit_block(&node.body));
+    }
+
+    fn visit_expr_closure(&mut self, node: &'ast ExprClosure) {
+        self.nested(|v| v.visit_expr(&node.body));
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast ExprBinary) {
+        let logical = match node.op {
+            BinOp::And(_) => Some(true),
+            BinOp::Or(_) => Some(false),
+            _ => None,
+        };
+        if logical.is_some() {
+            self.cyclomatic += 1;
+            if self.logical != logical {
+                self.cognitive += 1;
+            }
+        }
+        let saved = std::mem::replace(&mut self.logical, logical);
+        visit::visit_expr_binary(self, node);
+        self.logical = saved;
+    }
+
+    fn visit_expr_try(&mut self, node: &'ast ExprTry) {
+        self.cyclomatic += 1;
+        visit::visit_expr_try(self, node);
+    }
+
+    fn visit_expr_break(&mut self, node: &'ast ExprBreak) {
+        if node.label.is_some() {
+            self.cognitive += 1;
+        }
+        visit::visit_expr_break(self, node);
+    }
+
+    fn visit_expr_continue(&mut self, node: &'ast ExprContinue) {
+        if node.label.is_some() {
+            self.cognitive += 1;
+        }
+        visit::visit_expr_continue(self, node);
+    }
+
+    fn visit_stmt(&mut self, node: &'ast Stmt) {
+        if !matches!(node, Stmt::Item(_)) {
+            self.statements += 1;
+        }
+        visit::visit_stmt(self, node);
+    }
+
+    // 宏的参数按逗号分隔的表达式解析，例如 `assert!(a && b)`
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if let Ok(args) = node.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+
+    // 嵌套函数有自己的度量
+    fn visit_item(&mut self, _: &'ast Item) {}
+}
+
+// 排序和筛选时使用的度量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKey {
+    Complexity,
+    Cognitive,
+    Nesting,
+    Params,
+    Loc,
+    Statements,
+}
+
+impl MetricKey {
+    pub const KEYWORDS: &'static [&'static str] = &["complexity", "cognitive", "nesting", "params", "loc", "statements"];
+
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "complexity" => Some(MetricKey::Complexity),
+            "cognitive" => Some(MetricKey::Cognitive),
+            "nesting" => Some(MetricKey::Nesting),
+            "params" => Some(MetricKey::Params),
+            "loc" => Some(MetricKey::Loc),
+            "statements" => Some(MetricKey::Statements),
+            _ => None,
+        }
+    }
+
+    pub fn keyword(self) -> &'static str {
+        match self {
+            MetricKey::Complexity => "complexity",
+            MetricKey::Cognitive => "cognitive",
+            MetricKey::Nesting => "nesting",
+            MetricKey::Params => "params",
+            MetricKey::Loc => "loc",
+            MetricKey::Statements => "statements",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MetricKey::Complexity => "圈复杂度",
+            MetricKey::Cognitive => "认知复杂度",
+            MetricKey::Nesting => "最大嵌套",
+            MetricKey::Params => "参数个数",
+            MetricKey::Loc => "行数",
+            MetricKey::Statements => "语句数",
+        }
+    }
+
+    pub fn value(self, metrics: &FnMetrics) -> usize {
+        match self {
+            MetricKey::Complexity => metrics.cyclomatic,
+            MetricKey::Cognitive => metrics.cognitive,
+            MetricKey::Nesting => metrics.max_nesting,
+            MetricKey::Params => metrics.params,
+            MetricKey::Loc => metrics.loc,
+            MetricKey::Statements => metrics.statements,
+        }
+    }
+}
+
+// 一组函数的度量汇总
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsSummary {
+    pub functions: usize,
+    pub cyclomatic: usize,
+    pub max_cyclomatic: usize,
+    pub cognitive: usize,
+    pub max_cognitive: usize,
+    pub max_nesting: usize,
+    pub loc: usize,
+    pub statements: usize,
+}
+
+impl MetricsSummary {
+    pub fn add(&mut self, metrics: &FnMetrics) {
+        self.functions += 1;
+        self.cyclomatic += metrics.cyclomatic;
+        self.max_cyclomatic = self.max_cyclomatic.max(metrics.cyclomatic);
+        self.cognitive += metrics.cognitive;
+        self.max_cognitive = self.max_cognitive.max(metrics.cognitive);
+        self.max_nesting = self.max_nesting.max(metrics.max_nesting);
+        self.loc += metrics.loc;
+        self.statements += metrics.statements;
+    }
+
+    pub fn average_cyclomatic(&self) -> f64 {
+        if self.functions == 0 {
+            0.0
+        } else {
+            self.cyclomatic as f64 / self.functions as f64
+        }
+    }
+}
+
+// 文件中有函数体的函数及其度量
+pub fn measured_fns(items: &FileItems) -> impl Iterator<Item = (&FnItem, &FnMetrics)> {
+    items.all_fns().filter_map(|func| func.metrics.as_ref().map(|metrics| (func, metrics)))
+}
+
+// 所有文件中有函数体的函数，按某项度量从高到低排列，相同时按路径排列
+pub fn rank<'a>(
+    files: impl IntoIterator<Item = &'a FileItems>,
+    key: MetricKey,
+) -> Vec<(&'a FileItems, &'a FnItem, &'a FnMetrics)> {
+    let mut ranked: Vec<(&FileItems, &FnItem, &FnMetrics)> = files
+        .into_iter()
+        .flat_map(|items| measured_fns(items).map(move |(func, metrics)| (items, func, metrics)))
+        .collect();
+    ranked.sort_by(|a, b| key.value(b.2).cmp(&key.value(a.2)).then_with(|| a.1.path.cmp(&b.1.path)));
+    ranked
+}
+
+// 按文件对应的模块汇总，文件中内联模块里的函数计入文件的模块
+pub fn summarize_modules(files: &[FileItems]) -> BTreeMap<&str, MetricsSummary> {
+    let mut modules: BTreeMap<&str, MetricsSummary> = BTreeMap::new();
+    for items in files {
+        let summary = modules.entry(&items.module).or_default();
+        for (_, metrics) in measured_fns(items) {
+            summary.add(metrics);
+        }
+    }
+    modules.retain(|_, summary| summary.functions > 0);
+    modules
+}
+
+// 文件的汇总
+pub fn summarize_file(items: &FileItems) -> MetricsSummary {
+    let mut summary = MetricsSummary::default();
+    for (_, metrics) in measured_fns(items) {
+        summary.add(metrics);
+    }
+    summary
+}
@@ -0,0 +1,23 @@
+// Rust 分析器的各个组成部分
+pub mod attributes;
+pub mod callgraph;
+pub mod cfg;
+pub mod coverage;
+pub mod diagnostics;
+pub mod features;
+pub mod filter;
+pub mod log;
+pub mod macros;
+pub mod metrics;
+pub mod model;
+pub mod modules;
+pub mod panic_audit;
+pub mod project;
+pub mod report;
+pub mod reexports;
+pub mod signature;
+pub mod trait_matrix;
+pub mod unsafe_audit;
+pub mod visibility;
+pub mod visitor;
+pub mod workspace;
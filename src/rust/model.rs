@@ -0,0 +1,754 @@
+use std::path::PathBuf;
+
+use proc_macro2::Span;
+
+use crate::report::LocationReport;
+use crate::rust::signature::FnSignature;
+use crate::rust::visibility::Visibility;
+
+// 条目在源文件中的范围：行号和列号从 1 开始，结束位置不包含在内；字节偏移从文件开头算起
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Location {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub byte_start: usize,
+    pub byte_end: usize,
+}
+
+impl Location {
+    pub fn from_span(span: Span) -> Self {
+        let (start, end, bytes) = (span.start(), span.end(), span.byte_range());
+        Location {
+            start_line: start.line,
+            start_column: start.column + 1,
+            end_line: end.line,
+            end_column: end.column + 1,
+            byte_start: bytes.start,
+            byte_end: bytes.end,
+        }
+    }
+
+    // 渲染为 "12:5-14:2 (120..180)"，见 LocationReport::render
+    pub fn render(&self) -> String {
+        LocationReport::from(*self).render()
+    }
+}
+
+// `#[deprecated]` 的参数
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deprecation {
+    pub since: Option<String>,
+    pub note: Option<String>,
+}
+
+// 对 API 使用者有意义的属性
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemAttrs {
+    pub deprecated: Option<Deprecation>,
+    // `#[must_use]` 的说明文字，没有说明时为空字符串
+    pub must_use: Option<String>,
+    pub non_exhaustive: bool,
+    // `#[inline]` 为 "inline"，`#[inline(always)]` 为 "always"
+    pub inline: Option<String>,
+    pub derives: Vec<String>,
+    // derives 中各项按模块树解析出的完整路径，例如 `use serde::Serialize;` 之后的 "Serialize" 为 "serde::Serialize"
+    pub resolved_derives: Vec<String>,
+    // `#[repr(C, u8)]` 中的各项
+    pub repr: Vec<String>,
+    // `#[doc(hidden)]`，位于隐藏模块中的条目也视为隐藏
+    pub doc_hidden: bool,
+    // `#[cfg(...)]` 的条件，例如 "feature = \"serde\""
+    pub cfg: Vec<String>,
+}
+
+impl ItemAttrs {
+    // 渲染为属性列表，例如 ["#[derive(Debug, Clone)]", "#[must_use]"]
+    pub fn render(&self) -> Vec<String> {
+        let mut attrs = Vec::new();
+        if let Some(deprecation) = &self.deprecated {
+            let mut args = Vec::new();
+            if let Some(since) = &deprecation.since {
+                args.push(format!("since = {:?}", since));
+            }
+            if let Some(note) = &deprecation.note {
+                args.push(format!("note = {:?}", note));
+            }
+            if args.is_empty() {
+                attrs.push("#[deprecated]".to_string());
+            } else {
+                attrs.push(format!("#[deprecated({})]", args.join(", ")));
+            }
+        }
+        match self.must_use.as_deref() {
+            Some("") => attrs.push("#[must_use]".to_string()),
+            Some(message) => attrs.push(format!("#[must_use = {:?}]", message)),
+            None => {}
+        }
+        if self.non_exhaustive {
+            attrs.push("#[non_exhaustive]".to_string());
+        }
+        match self.inline.as_deref() {
+            Some("inline") => attrs.push("#[inline]".to_string()),
+            Some(mode) => attrs.push(format!("#[inline({})]", mode)),
+            None => {}
+        }
+        if !self.derives.is_empty() {
+            attrs.push(format!("#[derive({})]", self.derives.join(", ")));
+        }
+        if !self.repr.is_empty() {
+            attrs.push(format!("#[repr({})]", self.repr.join(", ")));
+        }
+        if self.doc_hidden {
+            attrs.push("#[doc(hidden)]".to_string());
+        }
+        for cfg in &self.cfg {
+            attrs.push(format!("#[cfg({})]", cfg));
+        }
+        attrs
+    }
+}
+
+// 函数的分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FnKind {
+    // 模块级的普通函数
+    Function,
+    // impl 块或 trait 中的方法
+    Method,
+    // 没有 self 接收者且返回 Self/所属类型的关联函数
+    Constructor,
+}
+
+impl FnKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FnKind::Function => "function",
+            FnKind::Method => "method",
+            FnKind::Constructor => "constructor",
+        }
+    }
+}
+
+// 调用的种类：`util::parse(..)`、`conn.send(..)`、`println!(..)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    Path,
+    Method,
+    Macro,
+}
+
+impl CallKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            CallKind::Path => "path",
+            CallKind::Method => "method",
+            CallKind::Macro => "macro",
+        }
+    }
+}
+
+// 函数体中的一次调用
+#[derive(Debug, Clone)]
+pub struct Call {
+    // 源码中的写法，去掉泛型参数：路径调用为 "util::parse"，方法调用为方法名，宏调用为宏名
+    pub callee: String,
+    pub kind: CallKind,
+    // 方法调用的接收者是 self
+    pub on_self: bool,
+    pub location: Location,
+}
+
+// unsafe 审计中的一处代码的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UnsafeKind {
+    Fn,
+    Block,
+    Impl,
+    Trait,
+    ExternBlock,
+    StaticMut,
+    RawDeref,
+}
+
+impl UnsafeKind {
+    pub const ALL: [UnsafeKind; 7] = [
+        UnsafeKind::Fn,
+        UnsafeKind::Block,
+        UnsafeKind::Impl,
+        UnsafeKind::Trait,
+        UnsafeKind::ExternBlock,
+        UnsafeKind::StaticMut,
+        UnsafeKind::RawDeref,
+    ];
+
+    pub fn keyword(self) -> &'static str {
+        match self {
+            UnsafeKind::Fn => "unsafe fn",
+            UnsafeKind::Block => "unsafe block",
+            UnsafeKind::Impl => "unsafe impl",
+            UnsafeKind::Trait => "unsafe trait",
+            UnsafeKind::ExternBlock => "extern block",
+            UnsafeKind::StaticMut => "static mut",
+            UnsafeKind::RawDeref => "raw pointer deref",
+        }
+    }
+
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        UnsafeKind::ALL.into_iter().find(|kind| kind.keyword() == keyword)
+    }
+}
+
+// 一处 unsafe 代码
+#[derive(Debug, Clone)]
+pub struct UnsafeSite {
+    pub kind: UnsafeKind,
+    // 所在函数的完整路径，不在函数中时为所在模块的路径；unsafe fn 本身记为该函数
+    pub enclosing: String,
+    pub location: Location,
+    // 前面紧挨着 `// SAFETY:` 注释（unsafe fn / trait 也接受文档中的 `# Safety` 小节）；
+    // 解引用裸指针时所在 unsafe 块的注释同样算数
+    pub has_safety_comment: bool,
+}
+
+// 可能 panic 的代码的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PanicKind {
+    Unwrap,
+    Expect,
+    Panic,
+    Todo,
+    Unimplemented,
+    Unreachable,
+    // assert!、assert_eq!、assert_ne!
+    Assert,
+    // 下标和切片表达式，例如 `v[i]`、`s[1..]`
+    Index,
+}
+
+impl PanicKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            PanicKind::Unwrap => "unwrap",
+            PanicKind::Expect => "expect",
+            PanicKind::Panic => "panic!",
+            PanicKind::Todo => "todo!",
+            PanicKind::Unimplemented => "unimplemented!",
+            PanicKind::Unreachable => "unreachable!",
+            PanicKind::Assert => "assert!",
+            PanicKind::Index => "index",
+        }
+    }
+}
+
+// 函数中一处可能 panic 的代码
+#[derive(Debug, Clone)]
+pub struct PanicSite {
+    pub kind: PanicKind,
+    // 所在函数的完整路径
+    pub enclosing: String,
+    pub location: Location,
+    // 源码写法，过长时截断，例如 "dirs::home_dir().unwrap()"、"assert_eq!"
+    pub code: String,
+}
+
+// 函数的复杂度和规模，由 metrics::fn_metrics 根据函数体计算
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FnMetrics {
+    // 圈复杂度：1 加上 if、while、for、多出的 match 分支、match 守卫、&&、|| 和 ? 的数量
+    pub cyclomatic: usize,
+    // 认知复杂度：控制流结构按所在嵌套层数加权，else、标签跳转和逻辑运算符序列各加 1
+    pub cognitive: usize,
+    // 控制流结构和闭包的最大嵌套层数
+    pub max_nesting: usize,
+    // 参数个数，包括 self
+    pub params: usize,
+    // 函数从签名到函数体结束跨越的行数
+    pub loc: usize,
+    // 语句数，包括嵌套块中的语句，不包括嵌套条目
+    pub statements: usize,
+}
+
+// 函数或方法
+#[derive(Debug, Clone)]
+pub struct FnItem {
+    // 完整路径，例如 "crate::net::tcp::connect"、"crate::net::Conn::new"
+    pub path: String,
+    pub sig: FnSignature,
+    pub kind: FnKind,
+    // trait 中的方法沿用 trait 的可见性，trait 实现中的方法视为 pub
+    pub vis: Visibility,
+    // 下游 crate 是否能访问到（属于公开 API）
+    pub exported: bool,
+    // 下游可以使用的所有路径（包括 pub use 重导出），按长度排序
+    pub public_paths: Vec<String>,
+    // 最短的公开路径，与 rustdoc 显示的路径一致
+    pub canonical_path: Option<String>,
+    // 由本地 macro_rules! 宏展开生成时记录宏名
+    pub expanded_from: Option<String>,
+    // 在源文件中的位置，宏展开生成的条目取宏调用处的位置
+    pub location: Location,
+    // 文档注释的文本，没有文档时为 None
+    pub docs: Option<String>,
+    pub attrs: ItemAttrs,
+    // 函数体中调用了 panic!、assert! 等可能 panic 的宏
+    pub calls_panic: bool,
+    // 函数体中的调用，包括闭包中的调用，不包括嵌套函数中的调用
+    pub calls: Vec<Call>,
+    // trait 中带默认实现的方法
+    pub provided: bool,
+    // 没有函数体的 trait 方法为 None
+    pub metrics: Option<FnMetrics>,
+}
+
+impl FnItem {
+    // 没有 self 接收者的关联函数视为静态方法
+    pub fn is_static(&self) -> bool {
+        self.sig.receiver.is_none()
+    }
+}
+
+// 类型的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeKind {
+    Struct,
+    Enum,
+    Union,
+}
+
+impl TypeKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            TypeKind::Struct => "struct",
+            TypeKind::Enum => "enum",
+            TypeKind::Union => "union",
+        }
+    }
+}
+
+// 结构体和枚举变体的字段写法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldStyle {
+    // `struct S { a: T }`
+    Named,
+    // `struct S(T);`
+    Tuple,
+    // `struct S;`
+    #[default]
+    Unit,
+}
+
+// 结构体、联合体或枚举变体的字段
+#[derive(Debug, Clone)]
+pub struct Field {
+    // 元组字段为下标，例如 "0"
+    pub name: String,
+    // 枚举变体的字段视为 pub
+    pub vis: Visibility,
+    pub ty: String,
+    pub location: Location,
+    pub docs: Option<String>,
+    pub attrs: ItemAttrs,
+}
+
+impl Field {
+    pub fn render(&self, style: FieldStyle) -> String {
+        match style {
+            FieldStyle::Tuple => self.ty.clone(),
+            _ => format!("{}: {}", self.name, self.ty),
+        }
+    }
+}
+
+// 枚举变体
+#[derive(Debug, Clone)]
+pub struct Variant {
+    pub name: String,
+    pub style: FieldStyle,
+    pub fields: Vec<Field>,
+    // 显式判别值，例如 `A = 1` 中的 "1"
+    pub discriminant: Option<String>,
+    pub location: Location,
+    pub docs: Option<String>,
+    pub attrs: ItemAttrs,
+}
+
+impl Variant {
+    // 渲染为源码形式，例如 "Move { x: i32, y: i32 }"、"Write(String)"、"Quit = 3"
+    pub fn render(&self) -> String {
+        let fields: Vec<String> = self.fields.iter().map(|f| f.render(self.style)).collect();
+        let mut text = self.name.clone();
+        match self.style {
+            FieldStyle::Named => text.push_str(&format!(" {{ {} }}", fields.join(", "))),
+            FieldStyle::Tuple => text.push_str(&format!("({})", fields.join(", "))),
+            FieldStyle::Unit => {}
+        }
+        if let Some(discriminant) = &self.discriminant {
+            text.push_str(&format!(" = {}", discriminant));
+        }
+        text
+    }
+}
+
+// 结构体/枚举/联合体及其固有方法（inherent impl 中的方法）
+#[derive(Debug, Clone)]
+pub struct TypeItem {
+    pub name: String,
+    pub path: String,
+    // 类型声明不在当前文件中时为 None（例如只有 impl 块）
+    pub kind: Option<TypeKind>,
+    pub vis: Visibility,
+    pub exported: bool,
+    pub public_paths: Vec<String>,
+    pub canonical_path: Option<String>,
+    pub expanded_from: Option<String>,
+    // 类型声明的位置，声明不在当前文件中时为第一个 impl 块的位置
+    pub location: Location,
+    pub docs: Option<String>,
+    pub attrs: ItemAttrs,
+    pub generics: Vec<String>,
+    pub where_predicates: Vec<String>,
+    // 结构体和联合体的字段
+    pub style: FieldStyle,
+    pub fields: Vec<Field>,
+    pub variants: Vec<Variant>,
+    pub methods: Vec<FnItem>,
+}
+
+// 类型别名，例如 `pub type Result<T> = std::result::Result<T, Error>;`
+#[derive(Debug, Clone)]
+pub struct TypeAlias {
+    pub name: String,
+    pub path: String,
+    pub vis: Visibility,
+    pub exported: bool,
+    pub public_paths: Vec<String>,
+    pub canonical_path: Option<String>,
+    pub expanded_from: Option<String>,
+    pub location: Location,
+    pub docs: Option<String>,
+    pub attrs: ItemAttrs,
+    pub generics: Vec<String>,
+    pub where_predicates: Vec<String>,
+    pub ty: String,
+}
+
+// 常量和静态变量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Const,
+    Static,
+    StaticMut,
+}
+
+impl ValueKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            ValueKind::Const => "const",
+            ValueKind::Static => "static",
+            ValueKind::StaticMut => "static mut",
+        }
+    }
+}
+
+// 模块级的 const / static 条目
+#[derive(Debug, Clone)]
+pub struct ValueItem {
+    pub name: String,
+    pub path: String,
+    pub kind: ValueKind,
+    pub vis: Visibility,
+    pub exported: bool,
+    pub public_paths: Vec<String>,
+    pub canonical_path: Option<String>,
+    pub expanded_from: Option<String>,
+    pub location: Location,
+    pub docs: Option<String>,
+    pub attrs: ItemAttrs,
+    pub ty: String,
+    // 初始化表达式是闭包，例如 `const DOUBLE: fn(i32) -> i32 = |x| x * 2;`
+    pub closure: bool,
+}
+
+// trait 的关联类型，例如 `type Item: Display = String;`
+#[derive(Debug, Clone)]
+pub struct AssocType {
+    pub name: String,
+    pub generics: Vec<String>,
+    pub bounds: Vec<String>,
+    pub where_predicates: Vec<String>,
+    pub default: Option<String>,
+    pub location: Location,
+    pub docs: Option<String>,
+}
+
+impl AssocType {
+    pub fn render(&self) -> String {
+        let mut text = format!("type {}", self.name);
+        if !self.generics.is_empty() {
+            text.push_str(&format!("<{}>", self.generics.join(", ")));
+        }
+        if !self.bounds.is_empty() {
+            text.push_str(&format!(": {}", self.bounds.join(" + ")));
+        }
+        if !self.where_predicates.is_empty() {
+            text.push_str(&format!(" where {}", self.where_predicates.join(", ")));
+        }
+        if let Some(default) = &self.default {
+            text.push_str(&format!(" = {}", default));
+        }
+        text
+    }
+}
+
+// trait 的关联常量，例如 `const N: usize = 3;`
+#[derive(Debug, Clone)]
+pub struct AssocConst {
+    pub name: String,
+    pub ty: String,
+    pub default: Option<String>,
+    pub location: Location,
+    pub docs: Option<String>,
+}
+
+impl AssocConst {
+    pub fn render(&self) -> String {
+        match &self.default {
+            Some(default) => format!("const {}: {} = {}", self.name, self.ty, default),
+            None => format!("const {}: {}", self.name, self.ty),
+        }
+    }
+}
+
+// trait 定义
+#[derive(Debug, Clone)]
+pub struct TraitDef {
+    pub name: String,
+    pub path: String,
+    pub vis: Visibility,
+    pub exported: bool,
+    pub public_paths: Vec<String>,
+    pub canonical_path: Option<String>,
+    pub expanded_from: Option<String>,
+    pub location: Location,
+    pub docs: Option<String>,
+    pub attrs: ItemAttrs,
+    pub is_unsafe: bool,
+    pub is_auto: bool,
+    pub generics: Vec<String>,
+    pub where_predicates: Vec<String>,
+    // 父 trait 及生命周期约束，例如 "Clone"、"'static"
+    pub supertraits: Vec<String>,
+    pub assoc_types: Vec<AssocType>,
+    pub assoc_consts: Vec<AssocConst>,
+    pub methods: Vec<FnItem>,
+    // 本次分析的所有 crate 中为该 trait 编写的 impl 块的实现者类型，例如 "Data<T>"
+    pub implementors: Vec<String>,
+}
+
+// `impl Trait for Type` 块
+#[derive(Debug, Clone)]
+pub struct TraitImpl {
+    // 实现者的类型名（路径的最后一段），例如 "Data"
+    pub type_name: String,
+    // 实现者类型按模块树解析出的完整路径，用于和 TypeItem 对应，例如 "crate::model::Data"
+    pub type_path: String,
+    // 完整的实现者类型，例如 "Data<T>"
+    pub self_ty: String,
+    // trait 名称（路径的最后一段），例如 "Display"
+    pub trait_name: String,
+    // trait 路径，否定实现带 `!` 前缀，例如 "Printable"、"!Send"
+    pub trait_path: String,
+    // trait 按模块树解析出的完整路径（不带 `!`），例如 "crate::fmt::Printable"、"std::fmt::Display"
+    pub resolved_trait: String,
+    pub generics: Vec<String>,
+    pub where_predicates: Vec<String>,
+    // 实现者类型是泛型参数时（blanket impl），泛型参数列表和 where 子句对它的 trait 约束，
+    // 每项为源码写法和按模块树解析出的完整路径，例如 ("Display", "std::fmt::Display")
+    pub self_bounds: Vec<(String, String)>,
+    // 实现者类型和 trait 都对下游可见时才算公开 API
+    pub exported: bool,
+    pub expanded_from: Option<String>,
+    pub location: Location,
+    pub methods: Vec<FnItem>,
+}
+
+impl TraitImpl {
+    // 渲染 impl 头部，例如 "impl<T: Display> Printable for Data<T>"
+    pub fn header(&self) -> String {
+        let mut text = String::from("impl");
+        if !self.generics.is_empty() {
+            text.push('<');
+            text.push_str(&self.generics.join(", "));
+            text.push('>');
+        }
+        text.push_str(&format!(" {} for {}", self.trait_path, self.self_ty));
+        if !self.where_predicates.is_empty() {
+            text.push_str(" where ");
+            text.push_str(&self.where_predicates.join(", "));
+        }
+        text
+    }
+}
+
+// 模块级别定义的名称：函数、类型、trait、常量等条目和子模块，用于解析 use 路径
+#[derive(Debug, Clone)]
+pub struct NameDef {
+    // 所在模块，例如 "crate::net"
+    pub module: String,
+    pub name: String,
+    // 定义处的完整路径
+    pub path: String,
+    pub is_module: bool,
+    pub vis: Visibility,
+}
+
+// 展开后的一条 use 声明，例如 `pub use inner::{Thing as Other, *};` 会产生两条
+#[derive(Debug, Clone)]
+pub struct UseDecl {
+    pub module: String,
+    // 引入的名称（别名或路径最后一段），glob 导入为 None
+    pub name: Option<String>,
+    // 源路径，以 `::` 开头的绝对路径首段记为 "::"
+    pub source: Vec<String>,
+    pub vis: Visibility,
+}
+
+// 单个文件的分析结果
+#[derive(Debug, Clone, Default)]
+pub struct FileItems {
+    pub file: PathBuf,
+    // 文件对应的模块路径，例如 "crate::net::tcp"
+    pub module: String,
+    // 文件开头 `//!` 形式的模块文档
+    pub module_docs: Option<String>,
+    // 没有被任何 crate 根通过 `mod` 声明引用到的文件
+    pub orphaned: bool,
+    pub functions: Vec<FnItem>,
+    pub types: Vec<TypeItem>,
+    pub traits: Vec<TraitDef>,
+    pub trait_impls: Vec<TraitImpl>,
+    pub aliases: Vec<TypeAlias>,
+    pub values: Vec<ValueItem>,
+    pub defs: Vec<NameDef>,
+    pub uses: Vec<UseDecl>,
+    // 文件中（包括函数体中）定义的 macro_rules! 宏
+    pub macro_defs: Vec<String>,
+    // 文件中的 unsafe 代码，按出现顺序排列
+    pub unsafe_sites: Vec<UnsafeSite>,
+    // 非测试代码中可能 panic 的代码，按出现顺序排列
+    pub panic_sites: Vec<PanicSite>,
+    // 分析过程中遇到的问题，例如宏展开失败
+    pub warnings: Vec<String>,
+}
+
+impl FileItems {
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+            && self.types.is_empty()
+            && self.traits.is_empty()
+            && self.trait_impls.is_empty()
+            && self.aliases.is_empty()
+            && self.values.is_empty()
+    }
+
+    // 文件中的所有函数和方法，包括 trait 中和 trait 实现中的方法
+    pub fn all_fns(&self) -> impl Iterator<Item = &FnItem> {
+        self.functions
+            .iter()
+            .chain(self.types.iter().flat_map(|item| &item.methods))
+            .chain(self.traits.iter().flat_map(|def| &def.methods))
+            .chain(self.trait_impls.iter().flat_map(|imp| &imp.methods))
+    }
+
+    // 文件中所有条目的路径，字段和变体写作 "类型::名称"，trait 实现写作其头部
+    pub fn item_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.functions.iter().map(|f| f.path.clone()).collect();
+        for item in &self.types {
+            if item.kind.is_some() {
+                paths.push(item.path.clone());
+            }
+            paths.extend(item.fields.iter().map(|f| format!("{}::{}", item.path, f.name)));
+            paths.extend(item.variants.iter().map(|v| format!("{}::{}", item.path, v.name)));
+            paths.extend(item.methods.iter().map(|m| m.path.clone()));
+        }
+        for def in &self.traits {
+            paths.push(def.path.clone());
+            paths.extend(def.methods.iter().map(|m| m.path.clone()));
+        }
+        paths.extend(self.trait_impls.iter().map(TraitImpl::header));
+        paths.extend(self.aliases.iter().map(|a| a.path.clone()));
+        paths.extend(self.values.iter().map(|v| v.path.clone()));
+        paths
+    }
+
+    // 只保留满足条件的函数和方法，筛选后没有方法的类型、trait 和 impl 块一并去掉，
+    // 类型别名、常量和静态变量也不再输出
+    pub fn retain_fns(&mut self, mut keep: impl FnMut(&FnItem) -> bool) {
+        self.aliases.clear();
+        self.values.clear();
+        self.functions.retain(&mut keep);
+        for item in &mut self.types {
+            item.methods.retain(&mut keep);
+        }
+        self.types.retain(|item| !item.methods.is_empty());
+        for def in &mut self.traits {
+            def.methods.retain(&mut keep);
+        }
+        self.traits.retain(|def| !def.methods.is_empty());
+        for imp in &mut self.trait_impls {
+            imp.methods.retain(&mut keep);
+        }
+        self.trait_impls.retain(|imp| !imp.methods.is_empty());
+    }
+
+    // 只保留公开 API：可导出的函数、类型、trait 及其可导出的方法和 pub 字段，
+    // 标记为 #[doc(hidden)] 的条目不属于文档化的公开 API，一并去掉
+    pub fn retain_exported(&mut self) {
+        self.functions.retain(|f| f.exported && !f.attrs.doc_hidden);
+        self.types.retain(|item| item.exported && !item.attrs.doc_hidden);
+        for item in &mut self.types {
+            item.methods.retain(|m| m.exported && !m.attrs.doc_hidden);
+            item.fields.retain(|f| f.vis.is_public() && !f.attrs.doc_hidden);
+            item.variants.retain(|v| !v.attrs.doc_hidden);
+        }
+        self.aliases.retain(|alias| alias.exported && !alias.attrs.doc_hidden);
+        self.values.retain(|value| value.exported && !value.attrs.doc_hidden);
+        self.traits.retain(|def| def.exported && !def.attrs.doc_hidden);
+        for def in &mut self.traits {
+            def.methods.retain(|m| !m.attrs.doc_hidden);
+        }
+        self.trait_impls.retain(|imp| imp.exported);
+    }
+
+    // 按完整路径查找类型，不存在时创建一个（impl 块可能出现在类型声明之前或其他文件中）
+    pub fn type_entry(&mut self, name: &str, path: &str) -> &mut TypeItem {
+        let index = match self.types.iter().position(|t| t.path == path) {
+            Some(index) => index,
+            None => {
+                self.types.push(TypeItem {
+                    name: name.to_string(),
+                    path: path.to_string(),
+                    kind: None,
+                    vis: Visibility::Private,
+                    exported: false,
+                    public_paths: Vec::new(),
+                    canonical_path: None,
+                    expanded_from: None,
+                    location: Location::default(),
+                    docs: None,
+                    attrs: ItemAttrs::default(),
+                    generics: Vec::new(),
+                    where_predicates: Vec::new(),
+                    style: FieldStyle::default(),
+                    fields: Vec::new(),
+                    variants: Vec::new(),
+                    methods: Vec::new(),
+                });
+                self.types.len() - 1
+            }
+        };
+        &mut self.types[index]
+    }
+}
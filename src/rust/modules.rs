@@ -0,0 +1,345 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use syn::ext::IdentExt;
+use syn::{Attribute, Expr, Item, Lit, Meta};
+
+use crate::rust::attributes::item_attrs;
+use crate::rust::cfg::CfgSet;
+use crate::rust::diagnostics::{parse_source, Diagnostic};
+use crate::rust::model::FileItems;
+use crate::rust::reexports::resolve_public_paths;
+use crate::rust::visibility::Visibility;
+
+// 模块从 crate 根到自身的各级声明上继承下来的性质
+#[derive(Debug, Clone, Default)]
+pub struct ModuleScope {
+    // 从 crate 根到该模块的每一级声明都是 pub
+    pub exported: bool,
+    // 模块或其某一级父模块标记了 #[doc(hidden)]
+    pub hidden: bool,
+    // 各级模块声明上的 #[cfg(...)] 条件
+    pub cfg: Vec<String>,
+}
+
+impl ModuleScope {
+    // crate 根的作用域
+    pub fn root() -> Self {
+        ModuleScope {
+            exported: true,
+            ..ModuleScope::default()
+        }
+    }
+
+    // 在本作用域中声明的子模块的作用域
+    pub fn child(&self, vis: &syn::Visibility, attrs: &[Attribute]) -> Self {
+        let attrs = item_attrs(attrs);
+        let mut cfg = self.cfg.clone();
+        cfg.extend(attrs.cfg);
+        ModuleScope {
+            exported: self.exported && Visibility::from_syn(vis).is_public(),
+            hidden: self.hidden || attrs.doc_hidden,
+            cfg,
+        }
+    }
+}
+
+// crate 模块树中的一个源文件
+pub struct ModuleFile {
+    pub file: PathBuf,
+    // 文件对应的模块路径，例如 ["crate", "net", "tcp"]
+    pub module_path: Vec<String>,
+    pub scope: ModuleScope,
+    pub syntax: syn::File,
+}
+
+// 找不到对应文件的 `mod foo;` 声明
+pub struct MissingModule {
+    pub module_path: String,
+    pub declared_in: PathBuf,
+}
+
+// 从 lib.rs / main.rs 出发，沿 `mod` 声明建立的模块树
+pub struct CrateTree {
+    pub root: PathBuf,
+    pub files: Vec<ModuleFile>,
+    pub missing: Vec<MissingModule>,
+    // 无法读取或解析的模块文件，这些文件及其子模块不参与分析
+    pub diagnostics: Vec<Diagnostic>,
+    // cfg 条件不成立、因而没有加载的模块文件
+    pub excluded: Vec<PathBuf>,
+    // 已经加载过的文件，按规范化路径记录
+    seen: HashSet<PathBuf>,
+}
+
+// 在某个文件中声明的子模块文件
+struct ChildModule {
+    module_path: Vec<String>,
+    scope: ModuleScope,
+    // 解析出的文件路径，以及该文件是否按 mod.rs 规则查找它自己的子模块
+    resolved: Option<(PathBuf, bool)>,
+}
+
+// 多个模块树共用的源文件：每个文件只读取和解析一次，include 不接受的文件（例如位于被忽略的目录中）不加载
+pub struct Sources<'a> {
+    include: &'a dyn Fn(&Path) -> bool,
+    parsed: HashMap<PathBuf, Result<syn::File, Diagnostic>>,
+}
+
+impl<'a> Sources<'a> {
+    pub fn new(include: &'a dyn Fn(&Path) -> bool) -> Self {
+        Sources {
+            include,
+            parsed: HashMap::new(),
+        }
+    }
+
+    // 加载所有文件
+    pub fn all() -> Sources<'static> {
+        Sources::new(&|_| true)
+    }
+
+    fn parse(&mut self, file: &Path, canonical: PathBuf) -> Result<syn::File, Diagnostic> {
+        self.parsed.entry(canonical).or_insert_with(|| parse_source(file)).clone()
+    }
+}
+
+impl CrateTree {
+    // 给出 cfg 时先按它裁剪每个文件的语法树，条件不成立的 `mod foo;` 不会被加载
+    pub fn build(root: &Path, cfg: Option<&CfgSet>, sources: &mut Sources) -> Self {
+        let mut tree = CrateTree {
+            root: root.to_path_buf(),
+            files: Vec::new(),
+            missing: Vec::new(),
+            diagnostics: Vec::new(),
+            excluded: Vec::new(),
+            seen: HashSet::new(),
+        };
+        tree.load(root.to_path_buf(), vec!["crate".to_string()], ModuleScope::root(), true, cfg, sources);
+        tree
+    }
+
+    fn load(
+        &mut self,
+        file: PathBuf,
+        module_path: Vec<String>,
+        scope: ModuleScope,
+        mod_rs: bool,
+        cfg: Option<&CfgSet>,
+        sources: &mut Sources,
+    ) {
+        let canonical = fs::canonicalize(&file).unwrap_or_else(|_| file.clone());
+        if !(sources.include)(&file) || !self.seen.insert(canonical.clone()) {
+            return;
+        }
+        let mut syntax = match sources.parse(&file, canonical) {
+            Ok(syntax) => syntax,
+            Err(diagnostic) => {
+                self.diagnostics.push(diagnostic);
+                return;
+            }
+        };
+
+        // crate 根和 mod.rs 的子模块与自身同目录，其他文件 foo.rs 的子模块在 foo/ 下
+        let file_dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+        let module_dir = if mod_rs {
+            file_dir.clone()
+        } else {
+            let stem = file.file_stem().unwrap_or_default();
+            file_dir.join(stem)
+        };
+
+        let mut children = Vec::new();
+        if let Some(cfg) = cfg {
+            // 裁剪前后各收集一次子模块，只在裁剪前出现的文件被 cfg 排除
+            collect_child_modules(&syntax.items, &file_dir, &module_dir, &module_path, &scope, &mut children);
+            cfg.strip_file(&mut syntax);
+        }
+        let declared: Vec<PathBuf> = children.drain(..).filter_map(|child| child.resolved).map(|(path, _)| path).collect();
+        collect_child_modules(&syntax.items, &file_dir, &module_dir, &module_path, &scope, &mut children);
+        self.excluded.extend(
+            declared
+                .into_iter()
+                .filter(|path| !children.iter().any(|child| child.resolved.as_ref().is_some_and(|(p, _)| p == path))),
+        );
+        self.files.push(ModuleFile {
+            file: file.clone(),
+            module_path,
+            scope,
+            syntax,
+        });
+
+        for child in children {
+            match child.resolved {
+                Some((path, child_mod_rs)) => {
+                    self.load(path, child.module_path, child.scope, child_mod_rs, cfg, sources)
+                }
+                None => self.missing.push(MissingModule {
+                    module_path: child.module_path.join("::"),
+                    declared_in: file.clone(),
+                }),
+            }
+        }
+    }
+}
+
+// 收集 `mod foo;` 声明；内联的 `mod foo { ... }` 递归进入，其中的声明相对于 foo/ 目录解析。
+// path_base 是 #[path] 的相对基准：在内联模块外是当前文件所在目录，在内联模块内是对应的模块目录
+fn collect_child_modules(
+    items: &[Item],
+    path_base: &Path,
+    module_dir: &Path,
+    module_path: &[String],
+    scope: &ModuleScope,
+    out: &mut Vec<ChildModule>,
+) {
+    for item in items {
+        let Item::Mod(item_mod) = item else {
+            continue;
+        };
+        let name = item_mod.ident.unraw().to_string();
+        let mut child_path = module_path.to_vec();
+        child_path.push(name.clone());
+        let child_scope = scope.child(&item_mod.vis, &item_mod.attrs);
+        let path_attr = path_attribute(&item_mod.attrs);
+
+        match &item_mod.content {
+            Some((_, inner)) => {
+                let inner_dir = module_dir.join(path_attr.unwrap_or(name));
+                collect_child_modules(inner, &inner_dir, &inner_dir, &child_path, &child_scope, out);
+            }
+            None => {
+                // 通过 #[path] 加载的文件按 mod.rs 规则处理其子模块
+                let resolved = match path_attr {
+                    Some(path) => Some(path_base.join(path)).filter(|p| p.is_file()).map(|p| (p, true)),
+                    None => {
+                        let file = module_dir.join(format!("{}.rs", name));
+                        let mod_file = module_dir.join(&name).join("mod.rs");
+                        if file.is_file() {
+                            Some((file, false))
+                        } else if mod_file.is_file() {
+                            Some((mod_file, true))
+                        } else {
+                            None
+                        }
+                    }
+                };
+                out.push(ChildModule {
+                    module_path: child_path,
+                    scope: child_scope,
+                    resolved,
+                });
+            }
+        }
+    }
+}
+
+// 读取 #[path = "..."] 属性的值
+fn path_attribute(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(meta) if meta.path.is_ident("path") => match &meta.value {
+            Expr::Lit(expr) => match &expr.lit {
+                Lit::Str(value) => Some(value.value()),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+// 判断文件是否是 crate 根
+pub fn is_crate_root(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == "lib.rs" || name == "main.rs")
+}
+
+// 计算同一个 crate 中各条目是否属于公开 API：先按模块可见性和 pub use 得到
+// 函数、类型和 trait 的公开路径，再据此推算方法和 trait 实现。
+// impl 块与类型声明不在同一文件时，以声明处为准。crate_name 是公开路径开头的 crate 名称
+pub fn resolve_exports(files: &mut [FileItems], crate_name: &str) {
+    resolve_public_paths(files, crate_name);
+
+    // 同名的类型或 trait 可以在不同模块中声明（例如 net::Error 和 io::Error），按完整路径对应
+    let mut declared_types = HashMap::new();
+    let mut declared_traits = HashMap::new();
+    for items in files.iter() {
+        for item in items.types.iter().filter(|t| t.kind.is_some()) {
+            declared_types.insert(item.path.clone(), (item.exported, item.public_paths.clone()));
+        }
+        for def in &items.traits {
+            declared_traits.insert(def.path.clone(), (def.exported, def.public_paths.clone()));
+        }
+    }
+
+    for items in files.iter_mut() {
+        for item in &mut items.types {
+            if item.kind.is_none() {
+                if let Some((exported, paths)) = declared_types.get(&item.path) {
+                    item.exported = *exported;
+                    item.public_paths = paths.clone();
+                    item.canonical_path = item.public_paths.first().cloned();
+                }
+            }
+            for method in &mut item.methods {
+                method.exported = item.exported && method.vis.is_public();
+                if method.exported {
+                    method.public_paths = method_paths(&item.public_paths, &method.sig.name);
+                    method.canonical_path = method.public_paths.first().cloned();
+                }
+            }
+        }
+        for def in &mut items.traits {
+            for method in &mut def.methods {
+                method.exported = def.exported;
+                method.public_paths = method_paths(&def.public_paths, &method.sig.name);
+                method.canonical_path = method.public_paths.first().cloned();
+            }
+        }
+        for imp in &mut items.trait_impls {
+            // 类型不在本 crate 中声明时（例如为 String 实现本地 trait），沿用 impl 块所在作用域的估计
+            let type_exported = declared_types.get(&imp.type_path).map_or(imp.exported, |(e, _)| *e);
+            let trait_exported = declared_traits.get(&imp.resolved_trait).is_none_or(|(e, _)| *e);
+            imp.exported = type_exported && trait_exported;
+            for method in &mut imp.methods {
+                method.exported = imp.exported;
+            }
+        }
+    }
+}
+
+// 把各个 crate 中的 trait 实现按 trait 的完整路径归到对应的 trait 定义下（否定实现不计入）。
+// crates 中每项为 crate 被其他 crate 引用时的名称（只有 lib 目标有）及其中的文件，
+// 以这些名称开头的 trait 路径（例如 `impl mylib::Printable for Cli`）对应到该 crate 中的定义
+pub fn link_implementors<'a>(crates: impl IntoIterator<Item = (Option<&'a str>, &'a mut [FileItems])>) {
+    let mut crates: Vec<(Option<&str>, &mut [FileItems])> = crates.into_iter().collect();
+    // 每个实现记为 (trait 所在 crate 的序号, trait 在该 crate 中的路径, 实现者类型)
+    let mut implementors: Vec<(usize, String, String)> = Vec::new();
+    for (index, (_, files)) in crates.iter().enumerate() {
+        for imp in files.iter().flat_map(|items| &items.trait_impls).filter(|imp| !imp.trait_path.starts_with('!')) {
+            // 通过 use 引入（解析后的路径）或直接写出（源码中的路径）的其他 crate 中的 trait
+            let written = imp.trait_path.trim_start_matches("::");
+            let other_crate = [imp.resolved_trait.as_str(), written].into_iter().find_map(|path| {
+                let (first, rest) = path.split_once("::")?;
+                let other = crates.iter().position(|(name, _)| *name == Some(first))?;
+                Some((other, format!("crate::{}", rest)))
+            });
+            let (owner, path) = other_crate.unwrap_or_else(|| (index, imp.resolved_trait.clone()));
+            implementors.push((owner, path, imp.self_ty.clone()));
+        }
+    }
+    for (index, (_, files)) in crates.iter_mut().enumerate() {
+        for def in files.iter_mut().flat_map(|items| &mut items.traits) {
+            def.implementors = implementors
+                .iter()
+                .filter(|(owner, path, _)| *owner == index && *path == def.path)
+                .map(|(_, _, self_ty)| self_ty.clone())
+                .collect();
+        }
+    }
+}
+
+// 方法的公开路径：所属类型或 trait 的每个公开路径加上方法名
+fn method_paths(owner_paths: &[String], name: &str) -> Vec<String> {
+    owner_paths.iter().map(|path| format!("{}::{}", path, name)).collect()
+}
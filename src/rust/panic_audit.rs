@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    Attribute, Expr, ExprIndex, ExprMethodCall, ImplItemFn, ItemFn, ItemImpl, ItemMod, ItemTrait, Signature, Token,
+    TraitItemFn,
+};
+
+use crate::rust::cfg::CfgExpr;
+use crate::rust::model::{FileItems, Location, PanicKind, PanicSite};
+use crate::rust::signature::render_tokens;
+use crate::rust::visitor::type_name;
+
+// 记录的源码写法的最大长度
+const MAX_CODE_LEN: usize = 60;
+
+// 收集函数中可能 panic 的代码，跳过测试代码：#[test] / #[bench] 函数和只在 cfg(test) 下编译的条目
+pub struct PanicVisitor {
+    pub sites: Vec<PanicSite>,
+    module_path: Vec<String>,
+    // 当前 impl 块的目标类型或 trait 的名称
+    owner: Option<String>,
+    // 外层到内层的函数路径
+    functions: Vec<String>,
+}
+
+impl PanicVisitor {
+    pub fn new(module_path: Vec<String>) -> Self {
+        PanicVisitor {
+            sites: Vec::new(),
+            module_path,
+            owner: None,
+            functions: Vec::new(),
+        }
+    }
+
+    // 收集一个文件中可能 panic 的代码，文件本身只在 cfg(test) 下编译时返回空
+    pub fn collect(module_path: Vec<String>, syntax: &syn::File) -> Vec<PanicSite> {
+        if is_test_only(&syntax.attrs) {
+            return Vec::new();
+        }
+        let mut visitor = PanicVisitor::new(module_path);
+        visitor.visit_file(syntax);
+        visitor.sites
+    }
+
+    fn record<T: Spanned + quote::ToTokens>(&mut self, kind: PanicKind, node: &T, code: Option<String>) {
+        // 函数之外的 const / static 初始化表达式在编译期求值，不会在运行时 panic
+        let Some(enclosing) = self.functions.last() else {
+            return;
+        };
+        let code = code.unwrap_or_else(|| render_tokens(node));
+        self.sites.push(PanicSite {
+            kind,
+            enclosing: enclosing.clone(),
+            location: Location::from_span(node.span()),
+            code: shorten(code),
+        });
+    }
+
+    fn visit_function<F>(&mut self, attrs: &[Attribute], sig: &Signature, visit_body: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        if is_test_only(attrs) {
+            return;
+        }
+        let mut segments = self.module_path.clone();
+        segments.extend(self.owner.clone());
+        segments.push(sig.ident.unraw().to_string());
+        self.functions.push(segments.join("::"));
+        // 函数体中的函数和 impl 块不属于外层的 impl
+        let owner = self.owner.take();
+        visit_body(self);
+        self.owner = owner;
+        self.functions.pop();
+    }
+}
+
+impl<'ast> Visit<'ast> for PanicVisitor {
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        if is_test_only(&node.attrs) {
+            return;
+        }
+        self.module_path.push(node.ident.unraw().to_string());
+        visit::visit_item_mod(self, node);
+        self.module_path.pop();
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.visit_function(&node.attrs, &node.sig, |v| visit::visit_item_fn(v, node));
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.visit_function(&node.attrs, &node.sig, |v| visit::visit_impl_item_fn(v, node));
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast TraitItemFn) {
+        self.visit_function(&node.attrs, &node.sig, |v| visit::visit_trait_item_fn(v, node));
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        if is_test_only(&node.attrs) {
+            return;
+        }
+        let owner = self.owner.replace(type_name(&node.self_ty));
+        visit::visit_item_impl(self, node);
+        self.owner = owner;
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast ItemTrait) {
+        if is_test_only(&node.attrs) {
+            return;
+        }
+        let owner = self.owner.replace(node.ident.unraw().to_string());
+        visit::visit_item_trait(self, node);
+        self.owner = owner;
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        let kind = if node.method == "unwrap" {
+            Some(PanicKind::Unwrap)
+        } else if node.method == "expect" {
+            Some(PanicKind::Expect)
+        } else {
+            None
+        };
+        if let Some(kind) = kind {
+            self.record(kind, node, None);
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_expr_index(&mut self, node: &'ast ExprIndex) {
+        self.record(PanicKind::Index, node, None);
+        visit::visit_expr_index(self, node);
+    }
+
+    // 宏的参数按逗号分隔的表达式解析，以便找到 `println!("{}", x.unwrap())` 之类的写法
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if let Some(segment) = node.path.segments.last() {
+            let kind = match segment.ident.to_string().as_str() {
+                "panic" => Some(PanicKind::Panic),
+                "todo" => Some(PanicKind::Todo),
+                "unimplemented" => Some(PanicKind::Unimplemented),
+                "unreachable" => Some(PanicKind::Unreachable),
+                "assert" | "assert_eq" | "assert_ne" => Some(PanicKind::Assert),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                self.record(kind, node, Some(format!("{}!", segment.ident)));
+            }
+        }
+        if let Ok(args) = node.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+// 条目是否只属于测试代码：#[test]、#[bench]、#[tokio::test] 之类的测试函数，或 cfg 条件要求 test
+fn is_test_only(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let is_test_attr = attr
+            .path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "test" || segment.ident == "bench");
+        is_test_attr || matches!(CfgExpr::from_attr(attr), Some(Ok(expr)) if expr.requires("test"))
+    })
+}
+
+fn shorten(code: String) -> String {
+    match code.char_indices().nth(MAX_CODE_LEN) {
+        Some((index, _)) => format!("{}...", &code[..index]),
+        None => code,
+    }
+}
+
+// 按函数分组的 panic 位置，函数按路径排序，每个函数中的位置按出现顺序排列
+pub fn by_function<'a>(files: &'a [FileItems], module: Option<&str>) -> BTreeMap<&'a str, Vec<(&'a FileItems, &'a PanicSite)>> {
+    let mut grouped: BTreeMap<&str, Vec<(&FileItems, &PanicSite)>> = BTreeMap::new();
+    for items in files {
+        for site in &items.panic_sites {
+            if module.is_none_or(|module| in_module(&site.enclosing, module)) {
+                grouped.entry(&site.enclosing).or_default().push((items, site));
+            }
+        }
+    }
+    grouped
+}
+
+// 路径是否位于模块 module 中（或就是 module），按路径段比较，"crate::net" 不包含 "crate::network"
+pub fn in_module(path: &str, module: &str) -> bool {
+    path == module || path.strip_prefix(module).is_some_and(|rest| rest.starts_with("::"))
+}
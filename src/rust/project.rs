@@ -0,0 +1,338 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use syn::visit::Visit;
+use walkdir::WalkDir;
+
+use crate::rust::cfg::CfgSet;
+use crate::rust::diagnostics::{parse_source, Diagnostic};
+use crate::rust::features::FeatureTable;
+use crate::rust::macros::MacroTable;
+use crate::rust::model::FileItems;
+use crate::rust::modules::{is_crate_root, link_implementors, resolve_exports, CrateTree, ModuleScope, Sources};
+use crate::rust::panic_audit::PanicVisitor;
+use crate::rust::unsafe_audit::UnsafeVisitor;
+use crate::rust::visitor::FunctionVisitor;
+use crate::rust::workspace::{Target, TargetKind, Workspace};
+
+// 分析一个目录时的选项，也原样发送给插件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Options {
+    // 要忽略的目录名
+    pub ignore_dirs: Vec<String>,
+    // 最大目录递归深度，None 表示不限
+    pub max_depth: Option<usize>,
+    // 启用的 Cargo 特性
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+    // 视为成立的 cfg 选项，例如 "unix"、"target_os=\"linux\""
+    pub cfgs: Vec<String>,
+    // 报告中只保留下游可以访问的公开 API
+    pub public_only: bool,
+}
+
+impl Options {
+    fn cfgs(&self) -> Vec<&str> {
+        self.cfgs.iter().map(String::as_str).collect()
+    }
+
+    // 是否明确给出了要启用的特性
+    fn selects_features(&self) -> bool {
+        !self.features.is_empty() || self.no_default_features
+    }
+}
+
+// 一个 crate 的分析结果
+#[derive(Debug, Clone)]
+pub struct CrateAnalysis {
+    // 来自 Cargo.toml 的编译目标，没有 Cargo.toml 时为 None
+    pub target: Option<Target>,
+    // crate 根文件，孤立文件为文件本身
+    pub root: PathBuf,
+    pub files: Vec<FileItems>,
+    // crate 的 Cargo.toml 特性表
+    pub manifest: Option<FeatureTable>,
+    // 有 Cargo.toml 时实际启用的特性，包括 default 及其传递启用的特性
+    pub enabled_features: Option<Vec<String>>,
+}
+
+impl CrateAnalysis {
+    // 公开路径开头的 crate 名称，没有 Cargo.toml 时为 crate
+    pub fn crate_name(&self) -> &str {
+        self.target.as_ref().map_or("crate", |target| target.name.as_str())
+    }
+}
+
+// 一个目录的分析结果：每个编译目标（没有 Cargo.toml 时为每个 lib.rs / main.rs）一个 crate，
+// 没有被任何 crate 根引用到的文件各自作为一个 crate
+#[derive(Debug, Clone)]
+pub struct ProjectAnalysis {
+    pub workspace: Option<Workspace>,
+    pub crates: Vec<CrateAnalysis>,
+    // 目录中参与分析的 .rs 文件
+    pub rs_files: Vec<PathBuf>,
+    // 无法读取或解析的文件，跳过它们继续分析其余文件
+    pub diagnostics: Vec<Diagnostic>,
+    // 分析过程中的警告，例如找不到模块文件、未声明的特性
+    pub warnings: Vec<String>,
+}
+
+impl ProjectAnalysis {
+    pub fn analyze(dir: &Path, options: &Options) -> Self {
+        let cfgs = options.cfgs();
+        let default_features = !options.no_default_features;
+        let mut warnings = Vec::new();
+
+        // 目录下有 Cargo.toml 时按其中声明的包和编译目标分析
+        let workspace = Workspace::discover(dir).unwrap_or_else(|message| {
+            warnings.push(format!("{}，改为从目录中的 lib.rs / main.rs 出发分析", message));
+            None
+        });
+        let excluded_dirs = workspace.as_ref().map(|w| w.excluded.clone()).unwrap_or_default();
+        let rs_files: Vec<PathBuf> = WalkDir::new(dir)
+            .max_depth(options.max_depth.unwrap_or(usize::MAX))
+            .into_iter()
+            .filter_entry(|e| !(e.file_type().is_dir() && options.ignore_dirs.iter().any(|d| e.file_name() == d.as_str())))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+            .map(|e| e.into_path())
+            .filter(|path| !excluded_dirs.iter().any(|dir| path.starts_with(dir)))
+            .collect();
+
+        // 从每个编译目标的根文件（没有 Cargo.toml 时为 lib.rs / main.rs）出发沿 mod 声明建立模块树。
+        // 编译目标的根文件和模块文件与遍历目录时一样受 ignore_dirs / max_depth 限制
+        let include = |path: &Path| included(dir, path, options);
+        let roots: Vec<(Option<Target>, PathBuf)> = match &workspace {
+            Some(workspace) => workspace
+                .targets()
+                .filter(|target| include(&target.root))
+                .map(|target| (Some(target.clone()), target.root.clone()))
+                .collect(),
+            None => rs_files.iter().filter(|path| is_crate_root(path)).map(|path| (None, path.clone())).collect(),
+        };
+        let mut sources = Sources::new(&include);
+        let mut reached = HashSet::new();
+        let mut failed = HashSet::new();
+        let mut crates = Vec::new();
+        let mut diagnostics = Vec::new();
+        for (target, root) in &roots {
+            // 编译目标各自独立分析，同一文件可以属于多个目标
+            if target.is_none() && reached.contains(&canonical(root)) {
+                continue;
+            }
+            let manifest = match target {
+                Some(target) => Some(target.manifest.clone()),
+                None => FeatureTable::find_manifest(root),
+            };
+            let table = match manifest.map(|manifest| FeatureTable::load(&manifest)) {
+                Some(Ok(table)) => Some(table),
+                Some(Err(message)) => {
+                    warnings.push(message);
+                    None
+                }
+                None => None,
+            };
+            // 按 Cargo.toml 中的 default 特性和给出的特性裁剪条目
+            let cfg = crate_cfg(table.as_ref(), options, &cfgs);
+            let mut enabled_features = None;
+            if let Some(table) = &table {
+                for feature in table.unknown(&options.features) {
+                    warnings.push(format!("{} 中没有声明特性 {}", table.manifest.display(), feature));
+                }
+                enabled_features = Some(table.resolve(&options.features, default_features).into_iter().collect());
+            }
+            let tree = CrateTree::build(root, Some(&cfg), &mut sources);
+            for missing in &tree.missing {
+                warnings.push(format!(
+                    "未找到模块 {} 的源文件（声明于 {}）",
+                    missing.module_path,
+                    missing.declared_in.display()
+                ));
+            }
+            // 多个目标共用的文件无法解析时只报告一次
+            for diagnostic in &tree.diagnostics {
+                reached.insert(canonical(&diagnostic.file));
+                if failed.insert(canonical(&diagnostic.file)) {
+                    diagnostics.push(diagnostic.clone());
+                }
+            }
+            for module in &tree.files {
+                reached.insert(canonical(&module.file));
+            }
+            // 被 cfg 排除的模块文件不是孤立文件，也不再单独分析
+            for file in &tree.excluded {
+                reached.insert(canonical(file));
+            }
+            crates.push(CrateAnalysis {
+                target: target.clone(),
+                root: root.clone(),
+                files: analyze_tree(&tree, Some(&cfg)),
+                manifest: table,
+                enabled_features,
+            });
+        }
+
+        // 没有被任何 crate 根引用到的文件：目录中有 crate 根时标记为孤立文件，
+        // 否则（例如一组零散的 .rs 文件）每个文件按独立的 crate 处理
+        let orphan_cfg = crate_cfg(None, options, &cfgs);
+        for path in &rs_files {
+            if reached.contains(&canonical(path)) {
+                continue;
+            }
+            let result = if roots.is_empty() {
+                process_file(path, vec!["crate".to_string()], ModuleScope::root(), Some(&orphan_cfg))
+            } else {
+                process_file(path, Vec::new(), ModuleScope::default(), Some(&orphan_cfg)).map(|mut items| {
+                    items.orphaned = true;
+                    items
+                })
+            };
+            match result {
+                Ok(items) => crates.push(CrateAnalysis {
+                    target: None,
+                    root: path.clone(),
+                    files: vec![items],
+                    manifest: None,
+                    enabled_features: None,
+                }),
+                Err(diagnostic) => diagnostics.push(diagnostic),
+            }
+        }
+
+        for analysis in &mut crates {
+            let crate_name = analysis.target.as_ref().map_or("crate", |target| target.name.as_str());
+            resolve_exports(&mut analysis.files, crate_name);
+            for items in &analysis.files {
+                for warning in &items.warnings {
+                    warnings.push(format!("{}: {}", items.file.display(), warning));
+                }
+            }
+        }
+        link_implementors(crates.iter_mut().map(|analysis| {
+            let lib_name = analysis.target.as_ref().filter(|target| target.kind == TargetKind::Lib);
+            (lib_name.map(|target| target.name.as_str()), analysis.files.as_mut_slice())
+        }));
+
+        ProjectAnalysis {
+            workspace,
+            crates,
+            rs_files,
+            diagnostics,
+            warnings,
+        }
+    }
+
+    // 只保留各文件中的公开 API，见 FileItems::retain_exported
+    pub fn retain_exported(&mut self) {
+        for items in self.crates.iter_mut().flat_map(|c| c.files.iter_mut()) {
+            items.retain_exported();
+        }
+    }
+}
+
+fn analyze_syntax(
+    path: &Path,
+    syntax_tree: &syn::File,
+    module_path: Vec<String>,
+    scope: ModuleScope,
+    macros: &MacroTable,
+    cfg: Option<&CfgSet>,
+) -> FileItems {
+    let mut visitor = FunctionVisitor::new(module_path.clone(), scope)
+        .with_macros(macros.clone())
+        .with_cfg(cfg.cloned());
+    visitor.visit_file(syntax_tree);
+    let mut items = visitor.items;
+    items.file = path.to_path_buf();
+    // 语法树中没有普通注释，查找 SAFETY 注释需要源码
+    let source = fs::read_to_string(path).unwrap_or_default();
+    items.unsafe_sites = UnsafeVisitor::collect(&source, module_path.clone(), syntax_tree);
+    items.panic_sites = PanicVisitor::collect(module_path, syntax_tree);
+    items
+}
+
+// 单独分析一个不属于任何模块树的文件
+fn process_file(
+    path: &Path,
+    module_path: Vec<String>,
+    scope: ModuleScope,
+    cfg: Option<&CfgSet>,
+) -> Result<FileItems, Diagnostic> {
+    let mut syntax_tree = parse_source(path)?;
+    if let Some(cfg) = cfg {
+        cfg.strip_file(&mut syntax_tree);
+    }
+    let macros = MacroTable::collect([&syntax_tree]);
+    Ok(analyze_syntax(path, &syntax_tree, module_path, scope, &macros, cfg))
+}
+
+// 分析模块树中的所有文件，宏定义在整个 crate 范围内共享
+fn analyze_tree(tree: &CrateTree, cfg: Option<&CfgSet>) -> Vec<FileItems> {
+    let macros = MacroTable::collect(tree.files.iter().map(|module| &module.syntax));
+    tree.files
+        .iter()
+        .map(|module| {
+            analyze_syntax(
+                &module.file,
+                &module.syntax,
+                module.module_path.clone(),
+                module.scope.clone(),
+                &macros,
+                cfg,
+            )
+        })
+        .collect()
+}
+
+// crate 在给定特性下的 cfg 选项。有 Cargo.toml 时按其中的 [features] 展开 default 和传递启用的特性，
+// 没有 Cargo.toml 又没有给出特性选项时不知道启用了哪些特性
+fn crate_cfg(table: Option<&FeatureTable>, options: &Options, cfgs: &[&str]) -> CfgSet {
+    match table {
+        Some(table) => CfgSet::new(&table.resolve(&options.features, !options.no_default_features), cfgs),
+        None if options.selects_features() => CfgSet::new(&options.features, cfgs),
+        None => CfgSet::without_features(cfgs),
+    }
+}
+
+// 依次只启用每个特性（不启用 default），与不启用任何特性时相比新增的条目
+pub fn feature_matrix(root: &Path, table: &FeatureTable, cfgs: &[String]) -> Vec<(String, BTreeSet<String>, Vec<String>)> {
+    let cfgs: Vec<&str> = cfgs.iter().map(String::as_str).collect();
+    let mut sources = Sources::all();
+    let mut paths = |cfg: &CfgSet| -> BTreeSet<String> {
+        let tree = CrateTree::build(root, Some(cfg), &mut sources);
+        analyze_tree(&tree, Some(cfg)).iter().flat_map(FileItems::item_paths).collect()
+    };
+    let baseline = paths(&CfgSet::new(&[], &cfgs));
+    table
+        .features
+        .keys()
+        .map(|feature| {
+            let enabled = table.resolve(std::slice::from_ref(feature), false);
+            let added = paths(&CfgSet::new(&enabled, &cfgs)).difference(&baseline).cloned().collect();
+            (feature.clone(), enabled, added)
+        })
+        .collect()
+}
+
+// 文件是否在 ignore_dirs / max_depth 限定的范围内，规则与遍历目录时相同。
+// 目录之外的文件（例如 #[path] 指向的文件）不受限制
+fn included(dir: &Path, path: &Path, options: &Options) -> bool {
+    let path = canonical(path);
+    let dir = canonical(dir);
+    let Ok(relative) = path.strip_prefix(&dir) else {
+        return true;
+    };
+    let ignored = relative
+        .parent()
+        .into_iter()
+        .flat_map(Path::iter)
+        .any(|name| options.ignore_dirs.iter().any(|ignored| name == ignored.as_str()));
+    !ignored && options.max_depth.is_none_or(|max_depth| relative.components().count() <= max_depth)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
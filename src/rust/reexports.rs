@@ -0,0 +1,223 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::rust::model::{FileItems, NameDef, UseDecl};
+
+// use 路径和 glob 展开的最大递归深度，防止循环重导出导致死循环
+const MAX_DEPTH: usize = 32;
+// 公开路径经过的最大模块层数
+const MAX_SEGMENTS: usize = 16;
+
+// 名称解析的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    // 条目，保存定义处的完整路径
+    Item(String),
+    // 模块，保存模块路径
+    Module(String),
+}
+
+#[derive(Default)]
+struct ModuleScope<'a> {
+    defs: Vec<&'a NameDef>,
+    uses: Vec<&'a UseDecl>,
+}
+
+// 在一个 crate 的所有模块中解析名称
+pub struct Resolver<'a> {
+    modules: HashMap<&'a str, ModuleScope<'a>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(files: &'a [FileItems]) -> Self {
+        let mut modules: HashMap<&str, ModuleScope> = HashMap::new();
+        for items in files {
+            for def in &items.defs {
+                modules.entry(def.module.as_str()).or_default().defs.push(def);
+            }
+            for decl in &items.uses {
+                modules.entry(decl.module.as_str()).or_default().uses.push(decl);
+            }
+        }
+        Resolver { modules }
+    }
+
+    // 在模块中查找名称：先找直接定义，再找 use 引入的名称，最后找 glob 导入
+    fn lookup(&self, module: &str, name: &str, depth: usize) -> Option<Target> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let scope = self.modules.get(module)?;
+        if let Some(def) = scope.defs.iter().find(|def| def.name == name) {
+            return Some(if def.is_module {
+                Target::Module(def.path.clone())
+            } else {
+                Target::Item(def.path.clone())
+            });
+        }
+        for decl in &scope.uses {
+            if decl.name.as_deref() == Some(name) {
+                if let Some(target) = self.resolve_path(module, &decl.source, depth + 1) {
+                    return Some(target);
+                }
+            }
+        }
+        for decl in scope.uses.iter().filter(|decl| decl.name.is_none()) {
+            if let Some(Target::Module(glob)) = self.resolve_path(module, &decl.source, depth + 1) {
+                if let Some(target) = self.lookup(&glob, name, depth + 1) {
+                    return Some(target);
+                }
+            }
+        }
+        None
+    }
+
+    // 解析 use 的源路径。以 `::` 开头或找不到首段的路径视为外部 crate，返回 None
+    fn resolve_path(&self, module: &str, segments: &[String], depth: usize) -> Option<Target> {
+        let mut current = module.to_string();
+        let mut rest = segments;
+        match segments.first().map(String::as_str) {
+            Some("crate") => {
+                current = "crate".to_string();
+                rest = &segments[1..];
+            }
+            Some("::") | None => return None,
+            _ => {}
+        }
+        while let Some(first) = rest.first() {
+            match first.as_str() {
+                "self" => {}
+                "super" => current = parent_module(&current)?,
+                _ => break,
+            }
+            rest = &rest[1..];
+        }
+
+        for (index, segment) in rest.iter().enumerate() {
+            match self.lookup(&current, segment, depth + 1)? {
+                Target::Module(module) => current = module,
+                // 条目只能出现在路径末尾（Enum::Variant 之类的路径不处理）
+                Target::Item(path) => return (index + 1 == rest.len()).then_some(Target::Item(path)),
+            }
+        }
+        Some(Target::Module(current))
+    }
+
+    // 解析 module 中表达式里的路径，例如 `helper`、`util::parse`、`Config::new`，返回定义处的完整路径。
+    // 路径的某个前缀解析为类型等条目时，其余各段接在条目路径后面（关联函数）
+    pub fn resolve_item(&self, module: &str, segments: &[String]) -> Option<String> {
+        (1..=segments.len()).rev().find_map(|split| match self.resolve_path(module, &segments[..split], 0) {
+            Some(Target::Item(path)) => Some(
+                std::iter::once(path)
+                    .chain(segments[split..].iter().cloned())
+                    .collect::<Vec<_>>()
+                    .join("::"),
+            ),
+            _ => None,
+        })
+    }
+
+    // 模块对外公开的名称：pub 定义、pub use 以及 pub use glob 展开的名称
+    fn public_names(&self, module: &str, depth: usize) -> Vec<(String, Target)> {
+        let mut names = Vec::new();
+        if depth > MAX_DEPTH {
+            return names;
+        }
+        let Some(scope) = self.modules.get(module) else {
+            return names;
+        };
+        for def in scope.defs.iter().filter(|def| def.vis.is_public()) {
+            let target = if def.is_module {
+                Target::Module(def.path.clone())
+            } else {
+                Target::Item(def.path.clone())
+            };
+            names.push((def.name.clone(), target));
+        }
+        for decl in scope.uses.iter().filter(|decl| decl.vis.is_public()) {
+            let Some(target) = self.resolve_path(module, &decl.source, depth + 1) else {
+                continue;
+            };
+            match (&decl.name, target) {
+                (Some(name), target) => names.push((name.clone(), target)),
+                (None, Target::Module(glob)) => names.extend(self.public_names(&glob, depth + 1)),
+                (None, Target::Item(_)) => {}
+            }
+        }
+        names
+    }
+}
+
+fn parent_module(module: &str) -> Option<String> {
+    module.rsplit_once("::").map(|(parent, _)| parent.to_string())
+}
+
+// 把开头的 crate 换成 crate 名称
+pub fn qualify(crate_name: &str, path: &str) -> String {
+    match path.strip_prefix("crate") {
+        Some(rest) if rest.is_empty() || rest.starts_with("::") => format!("{}{}", crate_name, rest),
+        _ => path.to_string(),
+    }
+}
+
+// 从 crate 根出发，沿 pub 模块和 pub use 找出每个条目能被下游使用的所有路径，
+// 并据此更新函数、类型、trait、类型别名和常量的公开路径和 exported 标记。
+// 公开路径与 rustdoc 一致，以 crate 名称开头，例如 `mycrate::a::B`
+pub fn resolve_public_paths(files: &mut [FileItems], crate_name: &str) {
+    let mut public_paths: HashMap<String, Vec<String>> = HashMap::new();
+    {
+        let resolver = Resolver::new(files);
+        // 队列中保存模块、它的公开路径以及到达它所经过的模块，
+        // 重导出回到已经经过的模块（例如 `pub use crate::api as me`）时不再展开
+        let mut queue = VecDeque::from([("crate".to_string(), crate_name.to_string(), vec!["crate".to_string()])]);
+        while let Some((module, public, chain)) = queue.pop_front() {
+            if chain.len() > MAX_SEGMENTS {
+                continue;
+            }
+            for (name, target) in resolver.public_names(&module, 0) {
+                let path = format!("{}::{}", public, name);
+                match target {
+                    Target::Item(def) => public_paths.entry(def).or_default().push(path),
+                    Target::Module(next) if !chain.contains(&next) => {
+                        let mut chain = chain.clone();
+                        chain.push(next.clone());
+                        queue.push_back((next, path, chain));
+                    }
+                    Target::Module(_) => {}
+                }
+            }
+        }
+    }
+    for paths in public_paths.values_mut() {
+        paths.sort_by(|a, b| a.split("::").count().cmp(&b.split("::").count()).then(a.cmp(b)));
+        paths.dedup();
+    }
+
+    let lookup = |path: &str| public_paths.get(path).cloned().unwrap_or_default();
+    for items in files.iter_mut() {
+        for func in &mut items.functions {
+            func.public_paths = lookup(&func.path);
+            func.canonical_path = func.public_paths.first().cloned();
+            func.exported = !func.public_paths.is_empty();
+        }
+        for item in items.types.iter_mut().filter(|t| t.kind.is_some()) {
+            item.public_paths = lookup(&item.path);
+            item.canonical_path = item.public_paths.first().cloned();
+            item.exported = !item.public_paths.is_empty();
+        }
+        for def in &mut items.traits {
+            def.public_paths = lookup(&def.path);
+            def.canonical_path = def.public_paths.first().cloned();
+            def.exported = !def.public_paths.is_empty();
+        }
+        for alias in &mut items.aliases {
+            alias.public_paths = lookup(&alias.path);
+            alias.canonical_path = alias.public_paths.first().cloned();
+            alias.exported = !alias.public_paths.is_empty();
+        }
+        for value in &mut items.values {
+            value.public_paths = lookup(&value.path);
+            value.canonical_path = value.public_paths.first().cloned();
+            value.exported = !value.public_paths.is_empty();
+        }
+    }
+}
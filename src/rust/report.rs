@@ -0,0 +1,758 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::report::{
+    ClassReport, DiagnosticReport, FileReport, LocationReport, ProjectReport, RankedFunction, RankingReport, Symbol,
+    SymbolKind,
+};
+use crate::rust::metrics::MetricKey;
+use crate::rust::model::{
+    AssocConst, AssocType, Call, FileItems, FnItem, FnKind, FnMetrics, Location, TraitDef, TraitImpl, TypeAlias, TypeItem,
+    ValueItem,
+};
+use crate::rust::project::{CrateAnalysis, ProjectAnalysis};
+use crate::rust::visibility::Visibility;
+
+// Rust 代码的详细分析报告，包含可见性、签名、度量等各语言通用报告中没有的信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RustReport {
+    // 分析的目录
+    pub root: String,
+    pub crates: Vec<CrateReport>,
+    pub diagnostics: Vec<DiagnosticReport>,
+    pub warnings: Vec<String>,
+}
+
+// 一个 crate（编译目标或孤立文件）的报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrateReport {
+    // crate 根文件，相对于分析的目录
+    pub root: String,
+    pub target: Option<TargetReport>,
+    pub files: Vec<RustFileReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetReport {
+    pub package: String,
+    // lib / bin / example / test / bench / build
+    pub kind: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RustFileReport {
+    // 相对于分析的目录
+    pub path: String,
+    // 模块路径，孤立文件为空字符串
+    pub module: String,
+    pub orphaned: bool,
+    pub functions: Vec<FunctionReport>,
+    pub types: Vec<TypeReport>,
+    pub traits: Vec<TraitReport>,
+    pub trait_impls: Vec<ImplReport>,
+    pub aliases: Vec<AliasReport>,
+    // 模块级的 const / static 条目
+    pub values: Vec<ValueReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionReport {
+    pub name: String,
+    pub path: String,
+    // 渲染后的签名（不含可见性），例如 "async fn fetch(url: &str) -> Result<String>"
+    pub signature: String,
+    // function / method / constructor
+    pub kind: String,
+    // "pub"、"pub(crate)" 等源码写法，私有条目为 "private"
+    pub visibility: String,
+    pub exported: bool,
+    // 下游 crate 可以使用的路径，包括 pub use 重新导出的路径
+    pub public_paths: Vec<String>,
+    // 文档中显示的路径，没有公开路径时为 None
+    pub canonical_path: Option<String>,
+    // 由 macro_rules! 宏展开得到时为宏名
+    pub expanded_from: Option<String>,
+    pub is_async: bool,
+    pub is_const: bool,
+    pub is_unsafe: bool,
+    // 没有 self 接收者
+    pub is_static: bool,
+    // 带有默认实现的 trait 方法
+    pub provided: bool,
+    pub deprecated: bool,
+    // 渲染后的属性，例如 "#[must_use]"、"#[cfg(unix)]"
+    pub attrs: Vec<String>,
+    // `#[cfg(...)]` 的条件
+    pub cfg: Vec<String>,
+    pub docs: Option<String>,
+    pub location: LocationReport,
+    // 没有函数体的 trait 方法为 None
+    pub metrics: Option<MetricsReport>,
+    pub calls: Vec<CallReport>,
+}
+
+// 函数体中的一次调用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallReport {
+    // 源码中的写法，例如 "util::parse"；方法调用为方法名，宏调用为宏名
+    pub callee: String,
+    // path / method / macro
+    pub kind: String,
+    pub on_self: bool,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeReport {
+    pub name: String,
+    pub path: String,
+    // struct / enum / union；类型在其他文件中声明、本文件中只有 impl 块时为 None
+    pub kind: Option<String>,
+    pub visibility: String,
+    pub exported: bool,
+    pub public_paths: Vec<String>,
+    pub canonical_path: Option<String>,
+    pub expanded_from: Option<String>,
+    pub deprecated: bool,
+    pub attrs: Vec<String>,
+    pub cfg: Vec<String>,
+    pub derives: Vec<String>,
+    pub fields: Vec<FieldReport>,
+    pub variants: Vec<String>,
+    pub methods: Vec<FunctionReport>,
+    pub docs: Option<String>,
+    pub location: LocationReport,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldReport {
+    pub name: String,
+    pub ty: String,
+    pub visibility: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraitReport {
+    pub name: String,
+    pub path: String,
+    pub visibility: String,
+    pub exported: bool,
+    pub public_paths: Vec<String>,
+    pub canonical_path: Option<String>,
+    pub expanded_from: Option<String>,
+    pub deprecated: bool,
+    pub attrs: Vec<String>,
+    pub cfg: Vec<String>,
+    pub supertraits: Vec<String>,
+    pub assoc_types: Vec<AssocTypeReport>,
+    pub assoc_consts: Vec<AssocConstReport>,
+    pub methods: Vec<FunctionReport>,
+    pub implementors: Vec<String>,
+    pub docs: Option<String>,
+    pub location: LocationReport,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssocTypeReport {
+    pub name: String,
+    // 例如 "type Item: Display = String"
+    pub declaration: String,
+    pub docs: Option<String>,
+    pub location: LocationReport,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssocConstReport {
+    pub name: String,
+    // 例如 "const N: usize = 3"
+    pub declaration: String,
+    pub docs: Option<String>,
+    pub location: LocationReport,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImplReport {
+    // 例如 "impl<T: Display> Printable for Data<T>"
+    pub header: String,
+    pub trait_path: String,
+    // trait 按模块树解析出的完整路径，例如 "std::fmt::Display"
+    pub resolved_trait: String,
+    pub self_ty: String,
+    // 实现者类型的完整路径，例如 "crate::model::Data"
+    pub type_path: String,
+    pub exported: bool,
+    pub expanded_from: Option<String>,
+    pub methods: Vec<FunctionReport>,
+    pub location: LocationReport,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AliasReport {
+    pub name: String,
+    pub path: String,
+    // 例如 "type Result<T> = std::result::Result<T, Error>"
+    pub declaration: String,
+    pub visibility: String,
+    pub exported: bool,
+    pub public_paths: Vec<String>,
+    pub canonical_path: Option<String>,
+    pub expanded_from: Option<String>,
+    pub deprecated: bool,
+    pub attrs: Vec<String>,
+    pub cfg: Vec<String>,
+    pub docs: Option<String>,
+    pub location: LocationReport,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueReport {
+    pub name: String,
+    pub path: String,
+    // const / static / static mut
+    pub kind: String,
+    pub ty: String,
+    pub visibility: String,
+    pub exported: bool,
+    pub public_paths: Vec<String>,
+    pub canonical_path: Option<String>,
+    pub expanded_from: Option<String>,
+    pub deprecated: bool,
+    pub attrs: Vec<String>,
+    pub cfg: Vec<String>,
+    pub docs: Option<String>,
+    pub location: LocationReport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricsReport {
+    pub cyclomatic: usize,
+    pub cognitive: usize,
+    pub max_nesting: usize,
+    pub params: usize,
+    pub loc: usize,
+    pub statements: usize,
+}
+
+impl RustReport {
+    // 把分析结果转换为报告，路径都记录为相对于 dir 的形式
+    pub fn from_analysis(dir: &Path, analysis: &ProjectAnalysis) -> Self {
+        let relative = |path: &Path| path.strip_prefix(dir).unwrap_or(path).display().to_string();
+        RustReport {
+            root: dir.display().to_string(),
+            crates: analysis.crates.iter().map(|c| CrateReport::from_analysis(c, &relative)).collect(),
+            diagnostics: diagnostics(analysis, &relative),
+            warnings: analysis.warnings.clone(),
+        }
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &RustFileReport> {
+        self.crates.iter().flat_map(|c| &c.files)
+    }
+
+    // 按某项度量从高到低排列的前 top 个函数，相同时按路径排列。同一文件属于多个编译目标时只计一次
+    pub fn ranking(&self, key: MetricKey, top: usize) -> RankingReport {
+        let mut seen = HashSet::new();
+        let mut ranked: Vec<(&RustFileReport, &FunctionReport, usize)> = self
+            .files()
+            .filter(|file| seen.insert(file.path.as_str()))
+            .flat_map(|file| {
+                file.all_functions()
+                    .filter_map(move |func| func.metrics.map(|metrics| (file, func, key.value(&metrics.into()))))
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.1.path.cmp(&b.1.path)));
+        RankingReport {
+            metric: key.keyword().to_string(),
+            functions: ranked
+                .into_iter()
+                .take(top)
+                .map(|(file, func, value)| RankedFunction {
+                    path: func.path.clone(),
+                    file: file.path.clone(),
+                    line: func.location.start_line,
+                    value,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl RustFileReport {
+    // 模块级函数以及类型、trait 和 trait 实现中的方法
+    pub fn all_functions(&self) -> impl Iterator<Item = &FunctionReport> {
+        self.functions
+            .iter()
+            .chain(self.types.iter().flat_map(|item| &item.methods))
+            .chain(self.traits.iter().flat_map(|def| &def.methods))
+            .chain(self.trait_impls.iter().flat_map(|imp| &imp.methods))
+    }
+}
+
+// 转换为各语言通用的报告：模块级函数和闭包常量作为函数，类型的固有方法归到类型下。
+// 通用格式只覆盖这些条目，trait 及其实现、类型别名、常量等只在 details 中的 RustReport 里输出。
+// 同一文件属于多个编译目标时只输出一次
+pub fn project_report(dir: &Path, analysis: &ProjectAnalysis) -> ProjectReport {
+    let relative = |path: &Path| path.strip_prefix(dir).unwrap_or(path).display().to_string();
+    let mut report = ProjectReport::new(Vec::new());
+    for items in analysis.crates.iter().flat_map(|c| &c.files) {
+        let path = relative(&items.file);
+        if report.files.iter().any(|file| file.path == path) {
+            continue;
+        }
+        let mut functions: Vec<(usize, Symbol)> = items
+            .functions
+            .iter()
+            .map(|func| (func.location.byte_start, symbol(func)))
+            .collect();
+        functions.extend(items.values.iter().filter(|value| value.closure).map(|value| {
+            let symbol = Symbol {
+                name: value.name.clone(),
+                kind: SymbolKind::Arrow,
+                is_static: None,
+                is_async: false,
+                location: Some(value.location.into()),
+            };
+            (value.location.byte_start, symbol)
+        }));
+        functions.sort_by_key(|(start, _)| *start);
+        report.files.push(FileReport {
+            path,
+            language: None,
+            functions: functions.into_iter().map(|(_, symbol)| symbol).collect(),
+            classes: items
+                .types
+                .iter()
+                .filter(|item| item.kind.is_some() || !item.methods.is_empty())
+                .map(|item| ClassReport {
+                    name: item.name.clone(),
+                    methods: item.methods.iter().map(symbol).collect(),
+                })
+                .collect(),
+        });
+    }
+    report.diagnostics = diagnostics(analysis, &relative);
+    report.details.rust = Some(RustReport::from_analysis(dir, analysis));
+    report
+}
+
+fn diagnostics(analysis: &ProjectAnalysis, relative: &dyn Fn(&Path) -> String) -> Vec<DiagnosticReport> {
+    analysis
+        .diagnostics
+        .iter()
+        .map(|diagnostic| DiagnosticReport {
+            file: relative(&diagnostic.file),
+            line: diagnostic.line,
+            column: diagnostic.column,
+            message: diagnostic.message.clone(),
+        })
+        .collect()
+}
+
+fn symbol(func: &FnItem) -> Symbol {
+    let (kind, is_static) = match func.kind {
+        FnKind::Function => (SymbolKind::Function, None),
+        FnKind::Method => (SymbolKind::Method, Some(func.is_static())),
+        FnKind::Constructor => (SymbolKind::Constructor, Some(func.is_static())),
+    };
+    Symbol {
+        name: func.sig.name.clone(),
+        kind,
+        is_static,
+        is_async: func.sig.is_async,
+        location: Some(func.location.into()),
+    }
+}
+
+impl CrateReport {
+    fn from_analysis(analysis: &CrateAnalysis, relative: &dyn Fn(&Path) -> String) -> Self {
+        CrateReport {
+            root: relative(&analysis.root),
+            target: analysis.target.as_ref().map(|target| TargetReport {
+                package: target.package.clone(),
+                kind: target.kind.keyword().to_string(),
+                name: target.name.clone(),
+            }),
+            files: analysis.files.iter().map(|items| RustFileReport::from_items(items, relative)).collect(),
+        }
+    }
+}
+
+impl RustFileReport {
+    pub fn from_items(items: &FileItems, relative: &dyn Fn(&Path) -> String) -> Self {
+        RustFileReport {
+            path: relative(&items.file),
+            module: items.module.clone(),
+            orphaned: items.orphaned,
+            functions: items.functions.iter().map(FunctionReport::from_item).collect(),
+            types: items.types.iter().map(TypeReport::from_item).collect(),
+            traits: items.traits.iter().map(TraitReport::from_def).collect(),
+            trait_impls: items.trait_impls.iter().map(ImplReport::from_impl).collect(),
+            aliases: items.aliases.iter().map(AliasReport::from_alias).collect(),
+            values: items.values.iter().map(ValueReport::from_item).collect(),
+        }
+    }
+}
+
+impl FunctionReport {
+    pub fn from_item(item: &FnItem) -> Self {
+        FunctionReport {
+            name: item.sig.name.clone(),
+            path: item.path.clone(),
+            signature: item.sig.render(),
+            kind: item.kind.as_str().to_string(),
+            visibility: visibility(&item.vis),
+            exported: item.exported,
+            public_paths: item.public_paths.clone(),
+            canonical_path: item.canonical_path.clone(),
+            expanded_from: item.expanded_from.clone(),
+            is_async: item.sig.is_async,
+            is_const: item.sig.is_const,
+            is_unsafe: item.sig.is_unsafe,
+            is_static: item.is_static(),
+            provided: item.provided,
+            deprecated: item.attrs.deprecated.is_some(),
+            attrs: item.attrs.render(),
+            cfg: item.attrs.cfg.clone(),
+            docs: item.docs.clone(),
+            location: item.location.into(),
+            metrics: item.metrics.map(Into::into),
+            calls: item.calls.iter().map(CallReport::from_call).collect(),
+        }
+    }
+}
+
+impl TypeReport {
+    pub fn from_item(item: &TypeItem) -> Self {
+        TypeReport {
+            name: item.name.clone(),
+            path: item.path.clone(),
+            kind: item.kind.map(|kind| kind.keyword().to_string()),
+            visibility: visibility(&item.vis),
+            exported: item.exported,
+            public_paths: item.public_paths.clone(),
+            canonical_path: item.canonical_path.clone(),
+            expanded_from: item.expanded_from.clone(),
+            deprecated: item.attrs.deprecated.is_some(),
+            attrs: item.attrs.render(),
+            cfg: item.attrs.cfg.clone(),
+            derives: item.attrs.derives.clone(),
+            fields: item
+                .fields
+                .iter()
+                .map(|field| FieldReport {
+                    name: field.name.clone(),
+                    ty: field.ty.clone(),
+                    visibility: visibility(&field.vis),
+                })
+                .collect(),
+            variants: item.variants.iter().map(|variant| variant.name.clone()).collect(),
+            methods: item.methods.iter().map(FunctionReport::from_item).collect(),
+            docs: item.docs.clone(),
+            location: item.location.into(),
+        }
+    }
+}
+
+impl TraitReport {
+    pub fn from_def(def: &TraitDef) -> Self {
+        TraitReport {
+            name: def.name.clone(),
+            path: def.path.clone(),
+            visibility: visibility(&def.vis),
+            exported: def.exported,
+            public_paths: def.public_paths.clone(),
+            canonical_path: def.canonical_path.clone(),
+            expanded_from: def.expanded_from.clone(),
+            deprecated: def.attrs.deprecated.is_some(),
+            attrs: def.attrs.render(),
+            cfg: def.attrs.cfg.clone(),
+            supertraits: def.supertraits.clone(),
+            assoc_types: def.assoc_types.iter().map(AssocTypeReport::from_assoc).collect(),
+            assoc_consts: def.assoc_consts.iter().map(AssocConstReport::from_assoc).collect(),
+            methods: def.methods.iter().map(FunctionReport::from_item).collect(),
+            implementors: def.implementors.clone(),
+            docs: def.docs.clone(),
+            location: def.location.into(),
+        }
+    }
+}
+
+impl ImplReport {
+    pub fn from_impl(imp: &TraitImpl) -> Self {
+        ImplReport {
+            header: imp.header(),
+            trait_path: imp.trait_path.clone(),
+            resolved_trait: imp.resolved_trait.clone(),
+            self_ty: imp.self_ty.clone(),
+            type_path: imp.type_path.clone(),
+            exported: imp.exported,
+            expanded_from: imp.expanded_from.clone(),
+            methods: imp.methods.iter().map(FunctionReport::from_item).collect(),
+            location: imp.location.into(),
+        }
+    }
+}
+
+impl CallReport {
+    pub fn from_call(call: &Call) -> Self {
+        CallReport {
+            callee: call.callee.clone(),
+            kind: call.kind.keyword().to_string(),
+            on_self: call.on_self,
+            line: call.location.start_line,
+        }
+    }
+}
+
+impl AssocTypeReport {
+    pub fn from_assoc(assoc: &AssocType) -> Self {
+        AssocTypeReport {
+            name: assoc.name.clone(),
+            declaration: assoc.render(),
+            docs: assoc.docs.clone(),
+            location: assoc.location.into(),
+        }
+    }
+}
+
+impl AssocConstReport {
+    pub fn from_assoc(assoc: &AssocConst) -> Self {
+        AssocConstReport {
+            name: assoc.name.clone(),
+            declaration: assoc.render(),
+            docs: assoc.docs.clone(),
+            location: assoc.location.into(),
+        }
+    }
+}
+
+impl AliasReport {
+    pub fn from_alias(alias: &TypeAlias) -> Self {
+        let mut declaration = format!("type {}", alias.name);
+        if !alias.generics.is_empty() {
+            declaration.push_str(&format!("<{}>", alias.generics.join(", ")));
+        }
+        if !alias.where_predicates.is_empty() {
+            declaration.push_str(&format!(" where {}", alias.where_predicates.join(", ")));
+        }
+        declaration.push_str(&format!(" = {}", alias.ty));
+        AliasReport {
+            name: alias.name.clone(),
+            path: alias.path.clone(),
+            declaration,
+            visibility: visibility(&alias.vis),
+            exported: alias.exported,
+            public_paths: alias.public_paths.clone(),
+            canonical_path: alias.canonical_path.clone(),
+            expanded_from: alias.expanded_from.clone(),
+            deprecated: alias.attrs.deprecated.is_some(),
+            attrs: alias.attrs.render(),
+            cfg: alias.attrs.cfg.clone(),
+            docs: alias.docs.clone(),
+            location: alias.location.into(),
+        }
+    }
+}
+
+impl ValueReport {
+    pub fn from_item(item: &ValueItem) -> Self {
+        ValueReport {
+            name: item.name.clone(),
+            path: item.path.clone(),
+            kind: item.kind.keyword().to_string(),
+            ty: item.ty.clone(),
+            visibility: visibility(&item.vis),
+            exported: item.exported,
+            public_paths: item.public_paths.clone(),
+            canonical_path: item.canonical_path.clone(),
+            expanded_from: item.expanded_from.clone(),
+            deprecated: item.attrs.deprecated.is_some(),
+            attrs: item.attrs.render(),
+            cfg: item.attrs.cfg.clone(),
+            docs: item.docs.clone(),
+            location: item.location.into(),
+        }
+    }
+}
+
+impl From<Location> for LocationReport {
+    fn from(location: Location) -> Self {
+        LocationReport {
+            start_line: location.start_line,
+            start_column: location.start_column,
+            end_line: location.end_line,
+            end_column: location.end_column,
+            byte_start: location.byte_start,
+            byte_end: location.byte_end,
+        }
+    }
+}
+
+impl From<MetricsReport> for FnMetrics {
+    fn from(metrics: MetricsReport) -> Self {
+        FnMetrics {
+            cyclomatic: metrics.cyclomatic,
+            cognitive: metrics.cognitive,
+            max_nesting: metrics.max_nesting,
+            params: metrics.params,
+            loc: metrics.loc,
+            statements: metrics.statements,
+        }
+    }
+}
+
+impl From<FnMetrics> for MetricsReport {
+    fn from(metrics: FnMetrics) -> Self {
+        MetricsReport {
+            cyclomatic: metrics.cyclomatic,
+            cognitive: metrics.cognitive,
+            max_nesting: metrics.max_nesting,
+            params: metrics.params,
+            loc: metrics.loc,
+            statements: metrics.statements,
+        }
+    }
+}
+
+// RustReport 的 JSON Schema 定义，与上面的结构体一一对应，合并到报告 Schema 的 $defs 中。
+// 名称都以 rust_ 开头，根为 rust_report
+pub fn json_schema_defs() -> Value {
+    let string = || json!({"type": "string"});
+    let optional_string = || json!({"type": ["string", "null"]});
+    let boolean = || json!({"type": "boolean"});
+    let count = || json!({"type": "integer", "minimum": 0});
+    let strings = || json!({"type": "array", "items": {"type": "string"}});
+    let list = |name: &str| json!({"type": "array", "items": {"$ref": format!("#/$defs/{}", name)}});
+    let location = || json!({"$ref": "#/$defs/location"});
+    // 导出信息、属性和文档，函数、类型、trait、类型别名和常量共有
+    let item = || {
+        let properties = json!({
+            "name": {"type": "string"},
+            "path": {"type": "string"},
+            "visibility": {"type": "string"},
+            "exported": {"type": "boolean"},
+            "public_paths": {"type": "array", "items": {"type": "string"}},
+            "canonical_path": {"type": ["string", "null"]},
+            "expanded_from": {"type": ["string", "null"]},
+            "deprecated": {"type": "boolean"},
+            "attrs": {"type": "array", "items": {"type": "string"}},
+            "cfg": {"type": "array", "items": {"type": "string"}},
+            "docs": {"type": ["string", "null"]},
+            "location": {"$ref": "#/$defs/location"},
+        });
+        properties.as_object().cloned().unwrap_or_default()
+    };
+    let object = |mut properties: Map<String, Value>, extra: Value| {
+        properties.extend(extra.as_object().cloned().unwrap_or_default());
+        let required: Vec<&String> = properties.keys().collect();
+        json!({"type": "object", "required": required, "properties": properties})
+    };
+    json!({
+        "rust_report": object(Map::new(), json!({
+            "root": string(),
+            "crates": list("rust_crate"),
+            "diagnostics": list("diagnostic"),
+            "warnings": strings(),
+        })),
+        "rust_crate": object(Map::new(), json!({
+            "root": string(),
+            "target": {"oneOf": [{"$ref": "#/$defs/rust_target"}, {"type": "null"}]},
+            "files": list("rust_file"),
+        })),
+        "rust_target": object(Map::new(), json!({
+            "package": string(),
+            "kind": {"enum": ["lib", "bin", "example", "test", "bench", "build"]},
+            "name": string(),
+        })),
+        "rust_file": object(Map::new(), json!({
+            "path": string(),
+            "module": string(),
+            "orphaned": boolean(),
+            "functions": list("rust_function"),
+            "types": list("rust_type"),
+            "traits": list("rust_trait"),
+            "trait_impls": list("rust_impl"),
+            "aliases": list("rust_alias"),
+            "values": list("rust_value"),
+        })),
+        "rust_function": object(item(), json!({
+            "signature": string(),
+            "kind": {"enum": ["function", "method", "constructor"]},
+            "is_async": boolean(),
+            "is_const": boolean(),
+            "is_unsafe": boolean(),
+            "is_static": boolean(),
+            "provided": boolean(),
+            "metrics": {"oneOf": [{"$ref": "#/$defs/rust_metrics"}, {"type": "null"}]},
+            "calls": list("rust_call"),
+        })),
+        "rust_call": object(Map::new(), json!({
+            "callee": string(),
+            "kind": {"enum": ["path", "method", "macro"]},
+            "on_self": boolean(),
+            "line": {"type": "integer", "minimum": 1},
+        })),
+        "rust_type": object(item(), json!({
+            "kind": {"type": ["string", "null"]},
+            "derives": strings(),
+            "fields": list("rust_field"),
+            "variants": strings(),
+            "methods": list("rust_function"),
+        })),
+        "rust_field": object(Map::new(), json!({
+            "name": string(),
+            "ty": string(),
+            "visibility": string(),
+        })),
+        "rust_trait": object(item(), json!({
+            "supertraits": strings(),
+            "assoc_types": list("rust_assoc"),
+            "assoc_consts": list("rust_assoc"),
+            "methods": list("rust_function"),
+            "implementors": strings(),
+        })),
+        "rust_assoc": object(Map::new(), json!({
+            "name": string(),
+            "declaration": string(),
+            "docs": optional_string(),
+            "location": location(),
+        })),
+        "rust_impl": object(Map::new(), json!({
+            "header": string(),
+            "trait_path": string(),
+            "resolved_trait": string(),
+            "self_ty": string(),
+            "type_path": string(),
+            "exported": boolean(),
+            "expanded_from": optional_string(),
+            "methods": list("rust_function"),
+            "location": location(),
+        })),
+        "rust_alias": object(item(), json!({
+            "declaration": string(),
+        })),
+        "rust_value": object(item(), json!({
+            "kind": {"enum": ["const", "static", "static mut"]},
+            "ty": string(),
+        })),
+        "rust_metrics": object(Map::new(), json!({
+            "cyclomatic": count(),
+            "cognitive": count(),
+            "max_nesting": count(),
+            "params": count(),
+            "loc": count(),
+            "statements": count(),
+        })),
+    })
+}
+
+fn visibility(vis: &Visibility) -> String {
+    match vis {
+        Visibility::Private => "private".to_string(),
+        vis => vis.as_str(),
+    }
+}
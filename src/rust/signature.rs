@@ -0,0 +1,362 @@
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{FnArg, GenericParam, ReturnType, Signature, Type};
+
+// self 接收者的形式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReceiverKind {
+    // self / mut self
+    Value,
+    // &self / &'a self / self: &Self
+    Ref,
+    // &mut self / self: &mut Self
+    RefMut,
+    // self: Box<Self>
+    Boxed,
+    // self: Rc<Self>、self: Pin<&mut Self> 等其他写法
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct Receiver {
+    pub kind: ReceiverKind,
+    // 源码中的写法，例如 "&'a mut self"、"self: Box<Self>"
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: String,
+    pub ty: String,
+}
+
+// 从 syn::Signature 提取出的完整函数签名
+#[derive(Debug, Clone)]
+pub struct FnSignature {
+    pub name: String,
+    pub is_const: bool,
+    pub is_async: bool,
+    pub is_unsafe: bool,
+    // extern 函数的 ABI，省略时为 "C"
+    pub abi: Option<String>,
+    pub lifetimes: Vec<String>,
+    // 类型参数和常量参数，例如 "T: Display"、"const N: usize"
+    pub generics: Vec<String>,
+    pub receiver: Option<Receiver>,
+    pub params: Vec<Param>,
+    // extern "C" 函数末尾的 `...`
+    pub variadic: bool,
+    pub output: Option<String>,
+    pub where_predicates: Vec<String>,
+}
+
+impl FnSignature {
+    pub fn from_syn(sig: &Signature) -> Self {
+        let mut lifetimes = Vec::new();
+        let mut generics = Vec::new();
+        for param in &sig.generics.params {
+            match param {
+                GenericParam::Lifetime(lifetime) => lifetimes.push(render_tokens(lifetime)),
+                GenericParam::Type(ty) => generics.push(render_tokens(ty)),
+                GenericParam::Const(constant) => generics.push(render_tokens(constant)),
+            }
+        }
+
+        let mut receiver = None;
+        let mut params = Vec::new();
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(recv) => receiver = Some(Receiver::from_syn(recv)),
+                FnArg::Typed(pat_type) => params.push(Param {
+                    name: render_tokens(&pat_type.pat),
+                    ty: render_tokens(&pat_type.ty),
+                }),
+            }
+        }
+
+        let output = match &sig.output {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) => Some(render_tokens(ty)),
+        };
+
+        let where_predicates = sig
+            .generics
+            .where_clause
+            .iter()
+            .flat_map(|clause| clause.predicates.iter().map(render_tokens))
+            .collect();
+
+        let abi = sig.abi.as_ref().map(|abi| match &abi.name {
+            Some(name) => name.value(),
+            None => "C".to_string(),
+        });
+
+        FnSignature {
+            name: sig.ident.to_string(),
+            is_const: sig.constness.is_some(),
+            is_async: sig.asyncness.is_some(),
+            is_unsafe: sig.unsafety.is_some(),
+            abi,
+            lifetimes,
+            generics,
+            receiver,
+            params,
+            variadic: sig.variadic.is_some(),
+            output,
+            where_predicates,
+        }
+    }
+
+    // 渲染为单行签名，例如 "fn get<'a, T: Clone>(&'a self, key: &str) -> Option<&'a T> where T: Debug"
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        if self.is_const {
+            text.push_str("const ");
+        }
+        if self.is_async {
+            text.push_str("async ");
+        }
+        if self.is_unsafe {
+            text.push_str("unsafe ");
+        }
+        if let Some(abi) = &self.abi {
+            text.push_str(&format!("extern \"{}\" ", abi));
+        }
+        text.push_str("fn ");
+        text.push_str(&self.name);
+
+        let generics: Vec<&str> = self
+            .lifetimes
+            .iter()
+            .chain(self.generics.iter())
+            .map(String::as_str)
+            .collect();
+        if !generics.is_empty() {
+            text.push('<');
+            text.push_str(&generics.join(", "));
+            text.push('>');
+        }
+
+        let mut args: Vec<String> = Vec::new();
+        if let Some(receiver) = &self.receiver {
+            args.push(receiver.text.clone());
+        }
+        for param in &self.params {
+            args.push(format!("{}: {}", param.name, param.ty));
+        }
+        if self.variadic {
+            args.push("...".to_string());
+        }
+        text.push('(');
+        text.push_str(&args.join(", "));
+        text.push(')');
+
+        if let Some(output) = &self.output {
+            text.push_str(" -> ");
+            text.push_str(output);
+        }
+        if !self.where_predicates.is_empty() {
+            text.push_str(" where ");
+            text.push_str(&self.where_predicates.join(", "));
+        }
+        text
+    }
+}
+
+impl Receiver {
+    fn from_syn(recv: &syn::Receiver) -> Self {
+        let mutable = recv.mutability.is_some();
+
+        // 简写形式：self / mut self / &self / &'a mut self
+        if recv.colon_token.is_none() {
+            return match &recv.reference {
+                Some((_, lifetime)) => {
+                    let lifetime = lifetime
+                        .as_ref()
+                        .map(|l| format!("{} ", l))
+                        .unwrap_or_default();
+                    let kind = if mutable { ReceiverKind::RefMut } else { ReceiverKind::Ref };
+                    let text = format!("&{}{}self", lifetime, if mutable { "mut " } else { "" });
+                    Receiver { kind, text }
+                }
+                None => Receiver {
+                    kind: ReceiverKind::Value,
+                    text: if mutable { "mut self".to_string() } else { "self".to_string() },
+                },
+            };
+        }
+
+        // 显式类型形式：self: Box<Self> 等
+        let kind = match recv.ty.as_ref() {
+            Type::Reference(reference) if reference.mutability.is_some() => ReceiverKind::RefMut,
+            Type::Reference(_) => ReceiverKind::Ref,
+            Type::Path(type_path) => match type_path.path.segments.last() {
+                Some(segment) if segment.ident == "Box" => ReceiverKind::Boxed,
+                Some(segment) if segment.ident == "Self" => ReceiverKind::Value,
+                _ => ReceiverKind::Other,
+            },
+            _ => ReceiverKind::Other,
+        };
+        let text = format!(
+            "{}self: {}",
+            if mutable { "mut " } else { "" },
+            render_tokens(&recv.ty)
+        );
+        Receiver { kind, text }
+    }
+}
+
+// 上一个输出的 token，用于决定是否需要空格
+#[derive(Clone, Copy, PartialEq)]
+enum Prev {
+    Start,
+    Ident,
+    // 后面需要跟空格的关键字（例如 mut、dyn、impl）或生命周期名
+    Keyword,
+    Punct(char, Spacing),
+    // `::` 的第二个冒号
+    PathSep,
+    // `->` 或 `=>` 的第二个字符
+    Arrow,
+    // 跟在操作数后面的二元运算符 `*`、`&`，例如常量表达式 "N * 2"
+    BinaryOp,
+    // 闭包参数列表开头的 `|`
+    ClosureOpen,
+    Group,
+}
+
+#[derive(Clone, Copy)]
+enum Cur {
+    Word,
+    Punct(char, Spacing),
+    Group(Delimiter),
+}
+
+const SPACED_KEYWORDS: &[&str] = &[
+    "as", "const", "dyn", "extern", "for", "impl", "in", "move", "mut", "ref", "static",
+    "unsafe", "where",
+];
+
+// 将语法树节点渲染为接近 rustfmt 风格的单行源码文本。
+// TokenStream 自带的 to_string 会在每个 token 之间插入空格（如 "Vec < & 'a str >"），
+// 不适合直接放进报告。
+pub fn render_tokens<T: ToTokens>(node: &T) -> String {
+    let mut out = String::new();
+    write_stream(&mut out, node.to_token_stream());
+    out
+}
+
+fn write_stream(out: &mut String, stream: TokenStream) {
+    let mut prev = Prev::Start;
+    // 是否处于闭包参数列表 `|..|` 中
+    let mut closure_params = false;
+    for tt in stream {
+        match tt {
+            TokenTree::Ident(ident) => {
+                let text = ident.to_string();
+                if needs_space(prev, Cur::Word) {
+                    out.push(' ');
+                }
+                out.push_str(&text);
+                // 生命周期名后面同样需要空格，例如 "&'a [T]"
+                let is_lifetime = prev == Prev::Punct('\'', Spacing::Joint);
+                prev = if is_lifetime || SPACED_KEYWORDS.contains(&text.as_str()) {
+                    Prev::Keyword
+                } else {
+                    Prev::Ident
+                };
+            }
+            TokenTree::Literal(literal) => {
+                if needs_space(prev, Cur::Word) {
+                    out.push(' ');
+                }
+                out.push_str(&literal.to_string());
+                prev = Prev::Ident;
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '|' && closure_params => {
+                out.push('|');
+                closure_params = false;
+                prev = Prev::Punct('|', Spacing::Alone);
+            }
+            // 不跟在操作数后面的 `|` 开始闭包参数列表，例如常量泛型参数 "{ |x: u8| x + 1 }"
+            TokenTree::Punct(punct) if punct.as_char() == '|' && !matches!(prev, Prev::Ident | Prev::Group) => {
+                if needs_space(prev, Cur::Punct('|', punct.spacing())) {
+                    out.push(' ');
+                }
+                out.push('|');
+                closure_params = true;
+                prev = Prev::ClosureOpen;
+            }
+            TokenTree::Punct(punct) => {
+                let c = punct.as_char();
+                if needs_space(prev, Cur::Punct(c, punct.spacing())) {
+                    out.push(' ');
+                }
+                out.push(c);
+                prev = match (prev, c) {
+                    (Prev::Ident | Prev::Group, '*' | '&') if punct.spacing() == Spacing::Alone => Prev::BinaryOp,
+                    (Prev::Punct(':', Spacing::Joint), ':') => Prev::PathSep,
+                    (Prev::Punct('-', Spacing::Joint), '>') | (Prev::Punct('=', Spacing::Joint), '>') => {
+                        Prev::Arrow
+                    }
+                    _ => Prev::Punct(c, punct.spacing()),
+                };
+            }
+            TokenTree::Group(group) => {
+                let delimiter = group.delimiter();
+                if needs_space(prev, Cur::Group(delimiter)) {
+                    out.push(' ');
+                }
+                let mut inner = String::new();
+                write_stream(&mut inner, group.stream());
+                match delimiter {
+                    Delimiter::Parenthesis => {
+                        out.push('(');
+                        out.push_str(&inner);
+                        out.push(')');
+                    }
+                    Delimiter::Bracket => {
+                        out.push('[');
+                        out.push_str(&inner);
+                        out.push(']');
+                    }
+                    Delimiter::Brace if inner.is_empty() => out.push_str("{}"),
+                    Delimiter::Brace => {
+                        out.push_str("{ ");
+                        out.push_str(&inner);
+                        out.push_str(" }");
+                    }
+                    Delimiter::None => out.push_str(&inner),
+                }
+                prev = Prev::Group;
+            }
+        }
+    }
+}
+
+fn needs_space(prev: Prev, cur: Cur) -> bool {
+    match prev {
+        Prev::Start | Prev::PathSep | Prev::ClosureOpen => return false,
+        // 前缀符号：&T、'a、<T、*const T、#[..]、?Sized
+        Prev::Punct('&' | '\'' | '<' | '*' | '#' | '$' | '!' | '?' | '.', _) => return false,
+        // 复合符号 `::`、`->`、`=>` 的前半部分
+        Prev::Punct(':' | '-' | '=', Spacing::Joint) => return false,
+        _ => {}
+    }
+
+    match cur {
+        Cur::Punct(',' | ';' | '>', _) => false,
+        Cur::Punct(':', Spacing::Alone) => false,
+        // 路径分隔符 `::` 只在开头时与前文隔开，例如 "x: ::std::io::Error"
+        Cur::Punct(':', Spacing::Joint) => matches!(
+            prev,
+            Prev::Arrow | Prev::Punct(',' | '=' | '+' | ':', _)
+        ),
+        Cur::Punct('<', _) => !matches!(prev, Prev::Ident | Prev::Keyword),
+        Cur::Punct('!', _) => prev != Prev::Ident,
+        // 方法调用和 `?` 紧跟在操作数后面，例如 "home_dir().unwrap()"、"read(path)?"
+        Cur::Punct('.' | '?', _) => !matches!(prev, Prev::Ident | Prev::Group),
+        Cur::Group(Delimiter::Parenthesis | Delimiter::Bracket) => !matches!(prev, Prev::Ident | Prev::Group),
+        _ => true,
+    }
+}
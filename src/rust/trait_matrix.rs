@@ -0,0 +1,332 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use syn::GenericParam;
+
+use crate::rust::model::{FileItems, TraitImpl};
+use crate::rust::reexports::qualify;
+
+// 类型通过什么方式实现了 trait，同时有多种方式时取靠前的一种
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImplSource {
+    // 显式的 impl 块
+    Impl,
+    Derive,
+    // 本地声明的 `impl<T: Bound> Trait for T`，类型满足其中的约束
+    Blanket,
+    // 否定实现 `impl !Send for T`
+    Negative,
+}
+
+impl ImplSource {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            ImplSource::Impl => "impl",
+            ImplSource::Derive => "derive",
+            ImplSource::Blanket => "blanket",
+            ImplSource::Negative => "!impl",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Implementor {
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub via: ImplSource,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TraitEntry {
+    // trait 名称（路径的最后一段），用作表格的列名
+    pub name: String,
+    // 是否在分析的代码中定义
+    pub local: bool,
+    pub implementors: Vec<Implementor>,
+    // 本地声明的 blanket impl 头部
+    pub blanket: Vec<String>,
+}
+
+// 每个类型实现的 trait，以及每个 trait 的实现者。
+// 类型和 trait 都以完整路径表示（开头的 crate 换成 crate 名称），impl 目标不是本地声明的类型时以源码写法表示。
+// 标准库的 trait 统一为 std 下的路径，例如 derive 的 "Debug" 和 `impl fmt::Debug` 都是 "std::fmt::Debug"
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TraitMatrix {
+    pub types: BTreeMap<String, BTreeMap<String, ImplSource>>,
+    pub traits: BTreeMap<String, TraitEntry>,
+}
+
+// blanket impl：trait 的完整路径和对泛型参数的约束中的 trait 的完整路径
+struct Blanket {
+    trait_path: String,
+    bounds: BTreeSet<String>,
+}
+
+impl TraitMatrix {
+    // crates 中每项为 crate 名称及其中的文件
+    pub fn build<'a>(crates: impl IntoIterator<Item = (&'a str, &'a [FileItems])>) -> Self {
+        let crates: Vec<(&str, &[FileItems])> = crates.into_iter().collect();
+        let mut matrix = TraitMatrix::default();
+        // 先记下所有 crate 中定义的 trait，其他 crate 可以通过 crate 名称引用
+        for (crate_name, files) in &crates {
+            for def in files.iter().flat_map(|items| &items.traits) {
+                let entry = matrix.trait_entry(qualify(crate_name, &def.path));
+                entry.local = true;
+            }
+        }
+
+        let mut blankets = Vec::new();
+        for (crate_name, files) in &crates {
+            // 同一 crate 中声明的类型，impl 块按类型的完整路径对应到声明
+            let mut declared: BTreeMap<&str, String> = BTreeMap::new();
+            for item in files.iter().flat_map(|items| &items.types).filter(|item| item.kind.is_some()) {
+                let path = qualify(crate_name, &item.path);
+                declared.insert(&item.path, path.clone());
+                let derives = item.attrs.derives.iter().zip(&item.attrs.resolved_derives);
+                let traits: Vec<String> =
+                    derives.map(|(written, resolved)| matrix.trait_path(crate_name, written, resolved)).collect();
+                let entry = matrix.types.entry(path).or_default();
+                for trait_path in traits {
+                    entry.entry(trait_path).or_insert(ImplSource::Derive);
+                }
+            }
+            for imp in files.iter().flat_map(|items| &items.trait_impls) {
+                let trait_path = matrix.trait_path(crate_name, &imp.trait_path, &imp.resolved_trait);
+                if is_blanket(imp) {
+                    let bounds = imp
+                        .self_bounds
+                        .iter()
+                        .map(|(written, resolved)| matrix.trait_path(crate_name, written, resolved))
+                        .collect();
+                    matrix.trait_entry(trait_path.clone()).blanket.push(imp.header());
+                    blankets.push(Blanket { trait_path, bounds });
+                    continue;
+                }
+                let ty = declared.get(imp.type_path.as_str()).cloned().unwrap_or_else(|| imp.self_ty.clone());
+                let source = if imp.trait_path.starts_with('!') { ImplSource::Negative } else { ImplSource::Impl };
+                let entry = matrix.types.entry(ty).or_default();
+                let current = entry.entry(trait_path).or_insert(source);
+                *current = (*current).min(source);
+            }
+        }
+
+        // blanket impl 可以互相依赖，反复应用直到没有新的实现
+        loop {
+            let mut added = false;
+            for implemented in matrix.types.values_mut() {
+                for blanket in &blankets {
+                    let satisfied = blanket.bounds.iter().all(|bound| {
+                        implemented.get(bound).is_some_and(|source| *source != ImplSource::Negative)
+                    });
+                    if satisfied && !implemented.contains_key(&blanket.trait_path) {
+                        implemented.insert(blanket.trait_path.clone(), ImplSource::Blanket);
+                        added = true;
+                    }
+                }
+            }
+            if !added {
+                break;
+            }
+        }
+
+        let implementors: Vec<(String, String, ImplSource)> = matrix
+            .types
+            .iter()
+            .flat_map(|(ty, implemented)| {
+                implemented.iter().map(move |(trait_path, source)| (ty.clone(), trait_path.clone(), *source))
+            })
+            .collect();
+        for (ty, trait_path, via) in implementors {
+            matrix.trait_entry(trait_path).implementors.push(Implementor { ty, via });
+        }
+        matrix
+    }
+
+    fn trait_entry(&mut self, path: String) -> &mut TraitEntry {
+        let name = last_segment(&path).to_string();
+        self.traits.entry(path).or_insert_with(|| TraitEntry { name, ..TraitEntry::default() })
+    }
+
+    // trait 的完整路径。written 为源码写法，resolved 为按模块树解析出的路径：
+    // 解析到本地定义的 trait 时用 crate 名称限定，解析到 crate 之外时直接使用；
+    // 解析到 crate 中但没有这个 trait 时，trait 来自 prelude 或没有 use 就写出的外部 crate（例如 "serde::Serialize"），
+    // 改用源码写法，prelude 中的名称换成标准库路径
+    fn trait_path(&self, crate_name: &str, written: &str, resolved: &str) -> String {
+        let qualified = qualify(crate_name, resolved);
+        let path = if !is_local(resolved) {
+            resolved.to_string()
+        } else if self.traits.get(&qualified).is_some_and(|entry| entry.local) {
+            return qualified;
+        } else {
+            let written = written.trim_start_matches('!').trim_start_matches("::");
+            match prelude_path(written) {
+                Some(path) => path.to_string(),
+                None if is_local(written) => return qualified,
+                None => written.to_string(),
+            }
+        };
+        // core 和 alloc 中的 trait 也由 std 导出
+        match path.split_once("::") {
+            Some(("core" | "alloc", rest)) => format!("std::{}", rest),
+            _ => path,
+        }
+    }
+
+    // 表格的列名：trait 名称，与其他 trait 同名时用完整路径
+    fn column_name<'a>(&'a self, path: &'a str) -> &'a str {
+        let name = self.traits[path].name.as_str();
+        let duplicated = self.traits.iter().any(|(other, entry)| other != path && entry.name == name);
+        if duplicated {
+            path
+        } else {
+            name
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    // 以类型为行、trait 为列的 Markdown 表格，后面列出每个 trait 的实现者
+    pub fn to_markdown(&self) -> String {
+        let columns: Vec<&String> = self.traits.keys().collect();
+        let mut out = String::from("# trait 实现矩阵\n\n");
+        out.push_str("| 类型 |");
+        for column in &columns {
+            out.push_str(&format!(" {} |", markdown_cell(self.column_name(column))));
+        }
+        out.push_str("\n|---|");
+        out.push_str(&"---|".repeat(columns.len()));
+        out.push('\n');
+        for (ty, implemented) in &self.types {
+            out.push_str(&format!("| {} |", markdown_cell(ty)));
+            for column in &columns {
+                out.push_str(&format!(" {} |", implemented.get(*column).map_or("", |source| source.keyword())));
+            }
+            out.push('\n');
+        }
+        out.push_str("\n## trait 实现者\n\n");
+        for (path, entry) in &self.traits {
+            let implementors: Vec<String> = entry
+                .implementors
+                .iter()
+                .map(|implementor| format!("`{}` ({})", implementor.ty, implementor.via.keyword()))
+                .collect();
+            out.push_str(&format!("- **{}** (`{}`): {}\n", entry.name, path, implementors.join(", ")));
+            for header in &entry.blanket {
+                out.push_str(&format!("  - `{}`\n", header));
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let columns: Vec<&String> = self.traits.keys().collect();
+        let mut out = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>trait 实现矩阵</title>\n</head>\n<body>\n",
+        );
+        out.push_str("<h1>trait 实现矩阵</h1>\n<table border=\"1\">\n<tr><th>类型</th>");
+        for column in &columns {
+            out.push_str(&format!("<th>{}</th>", html_escape(self.column_name(column))));
+        }
+        out.push_str("</tr>\n");
+        for (ty, implemented) in &self.types {
+            out.push_str(&format!("<tr><td>{}</td>", html_escape(ty)));
+            for column in &columns {
+                let cell = implemented.get(*column).map_or("", |source| source.keyword());
+                out.push_str(&format!("<td>{}</td>", html_escape(cell)));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n<h2>trait 实现者</h2>\n<ul>\n");
+        for (path, entry) in &self.traits {
+            let implementors: Vec<String> = entry
+                .implementors
+                .iter()
+                .map(|implementor| format!("{} ({})", html_escape(&implementor.ty), implementor.via.keyword()))
+                .collect();
+            out.push_str(&format!(
+                "<li><b>{}</b> (<code>{}</code>): {}",
+                html_escape(&entry.name),
+                html_escape(path),
+                implementors.join(", ")
+            ));
+            if !entry.blanket.is_empty() {
+                out.push_str("<ul>");
+                for header in &entry.blanket {
+                    out.push_str(&format!("<li><code>{}</code></li>", html_escape(header)));
+                }
+                out.push_str("</ul>");
+            }
+            out.push_str("</li>\n");
+        }
+        out.push_str("</ul>\n</body>\n</html>\n");
+        out
+    }
+}
+
+// `impl<T: A + B> Trait for T where T: C` 形式的 impl，目标类型就是某个泛型参数
+fn is_blanket(imp: &TraitImpl) -> bool {
+    if imp.trait_path.starts_with('!') {
+        return false;
+    }
+    imp.generics.iter().filter_map(|param| syn::parse_str::<GenericParam>(param).ok()).any(|param| {
+        matches!(param, GenericParam::Type(ty) if ty.ident == imp.self_ty)
+    })
+}
+
+fn is_local(path: &str) -> bool {
+    matches!(path.split("::").next(), Some("crate" | "self" | "super"))
+}
+
+// 标准库 prelude 中的 trait 和 derive 宏对应的路径
+fn prelude_path(name: &str) -> Option<&'static str> {
+    let path = match name {
+        "Copy" => "std::marker::Copy",
+        "Send" => "std::marker::Send",
+        "Sized" => "std::marker::Sized",
+        "Sync" => "std::marker::Sync",
+        "Unpin" => "std::marker::Unpin",
+        "Drop" => "std::ops::Drop",
+        "Fn" => "std::ops::Fn",
+        "FnMut" => "std::ops::FnMut",
+        "FnOnce" => "std::ops::FnOnce",
+        "Clone" => "std::clone::Clone",
+        "PartialEq" => "std::cmp::PartialEq",
+        "PartialOrd" => "std::cmp::PartialOrd",
+        "Eq" => "std::cmp::Eq",
+        "Ord" => "std::cmp::Ord",
+        "AsRef" => "std::convert::AsRef",
+        "AsMut" => "std::convert::AsMut",
+        "Into" => "std::convert::Into",
+        "From" => "std::convert::From",
+        "TryFrom" => "std::convert::TryFrom",
+        "TryInto" => "std::convert::TryInto",
+        "Default" => "std::default::Default",
+        "Iterator" => "std::iter::Iterator",
+        "Extend" => "std::iter::Extend",
+        "IntoIterator" => "std::iter::IntoIterator",
+        "DoubleEndedIterator" => "std::iter::DoubleEndedIterator",
+        "ExactSizeIterator" => "std::iter::ExactSizeIterator",
+        "FromIterator" => "std::iter::FromIterator",
+        "ToOwned" => "std::borrow::ToOwned",
+        "ToString" => "std::string::ToString",
+        "Debug" => "std::fmt::Debug",
+        "Hash" => "std::hash::Hash",
+        _ => return None,
+    };
+    Some(path)
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
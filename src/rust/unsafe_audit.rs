@@ -0,0 +1,439 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use proc_macro2::Span;
+use serde::{Deserialize, Serialize};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    Expr, ExprUnsafe, FnArg, ImplItemFn, ItemFn, ItemForeignMod, ItemImpl, ItemMod, ItemStatic, ItemTrait, Local,
+    Pat, Signature, StaticMutability, TraitItemFn, Type, UnOp,
+};
+
+use crate::rust::model::{FileItems, Location, UnsafeKind, UnsafeSite};
+use crate::rust::visitor::type_name;
+
+// 返回裸指针的常见方法，`*v.as_ptr()` 之类的解引用视为解引用裸指针
+const POINTER_CONVERSIONS: &[&str] = &["as_ptr", "as_mut_ptr"];
+
+// 裸指针上返回裸指针的常见方法。这些方法名也出现在其他类型上（例如整数的 add），
+// 只有接收者本身是裸指针时才视为裸指针，例如 `*p.add(1)`
+const POINTER_METHODS: &[&str] = &[
+    "add",
+    "sub",
+    "offset",
+    "wrapping_add",
+    "wrapping_sub",
+    "wrapping_offset",
+    "byte_add",
+    "byte_sub",
+    "byte_offset",
+    "cast",
+    "cast_mut",
+    "cast_const",
+];
+
+// 返回裸指针的常见函数，例如 `ptr::null_mut()`、`ptr::addr_of!` 之外的 `ptr::from_ref(..)`
+const POINTER_FUNCTIONS: &[&str] = &["null", "null_mut", "from_ref", "from_mut", "dangling", "dangling_mut"];
+
+// 收集文件中的 unsafe 代码。语法树中没有类型信息，解引用只在 unsafe 上下文中、
+// 且被解引用的表达式从语法上看是裸指针时才记录：类型标注为裸指针的参数、局部变量和结构体字段，
+// 转换为裸指针的表达式，以及返回裸指针的常见方法和函数
+pub struct UnsafeVisitor<'a> {
+    pub sites: Vec<UnsafeSite>,
+    // 源文件的各行，用于查找注释
+    lines: Vec<&'a str>,
+    module_path: Vec<String>,
+    // 当前 impl 块的目标类型或 trait 的名称
+    owner: Option<String>,
+    // 外层到内层的函数路径
+    functions: Vec<String>,
+    // 外层到内层的 unsafe 上下文（unsafe fn 和 unsafe 块）是否有 SAFETY 注释
+    contexts: Vec<bool>,
+    // 每一层函数中类型为裸指针的参数和局部变量
+    pointers: Vec<HashSet<String>>,
+    // 文件中类型为裸指针的结构体字段
+    pointer_fields: HashSet<String>,
+}
+
+impl<'a> UnsafeVisitor<'a> {
+    pub fn new(source: &'a str, module_path: Vec<String>) -> Self {
+        UnsafeVisitor {
+            sites: Vec::new(),
+            lines: source.lines().collect(),
+            module_path,
+            owner: None,
+            functions: Vec::new(),
+            contexts: Vec::new(),
+            pointers: Vec::new(),
+            pointer_fields: HashSet::new(),
+        }
+    }
+
+    // 收集一个文件中的 unsafe 代码
+    pub fn collect(source: &'a str, module_path: Vec<String>, syntax: &syn::File) -> Vec<UnsafeSite> {
+        let mut visitor = UnsafeVisitor::new(source, module_path);
+        let mut fields = PointerFields::default();
+        fields.visit_file(syntax);
+        visitor.pointer_fields = fields.names;
+        visitor.visit_file(syntax);
+        visitor.sites
+    }
+
+    fn qualify(&self, name: &str) -> String {
+        let mut segments = self.module_path.clone();
+        segments.extend(self.owner.clone());
+        segments.push(name.to_string());
+        segments.join("::")
+    }
+
+    fn enclosing(&self) -> String {
+        self.functions.last().cloned().unwrap_or_else(|| self.module_path.join("::"))
+    }
+
+    // 记录一处 unsafe 代码；start 是 `unsafe`、`extern` 等关键字，位置从它开始，到整个结构结束
+    fn record(&mut self, kind: UnsafeKind, start: Span, whole: Span, doc_section: bool) -> bool {
+        let mut location = Location::from_span(whole);
+        let keyword = Location::from_span(start);
+        location.start_line = keyword.start_line;
+        location.start_column = keyword.start_column;
+        location.byte_start = keyword.byte_start;
+        let has_safety_comment = safety_comment(&self.lines, location.start_line, doc_section);
+        self.sites.push(UnsafeSite {
+            kind,
+            enclosing: self.enclosing(),
+            location,
+            has_safety_comment,
+        });
+        has_safety_comment
+    }
+
+    // 进入函数：unsafe fn 本身是一处 unsafe 代码，其函数体是 unsafe 上下文
+    fn visit_function<F>(&mut self, sig: &Signature, whole: Span, visit_body: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let path = self.qualify(&sig.ident.unraw().to_string());
+        self.functions.push(path);
+        self.pointers.push(pointer_params(sig));
+        let unsafe_fn = sig.unsafety.map(|token| self.record(UnsafeKind::Fn, token.span, whole, true));
+        if let Some(documented) = unsafe_fn {
+            self.contexts.push(documented);
+        }
+        // 函数体中的函数和 impl 块不属于外层的 impl
+        let owner = self.owner.take();
+        visit_body(self);
+        self.owner = owner;
+        if unsafe_fn.is_some() {
+            self.contexts.pop();
+        }
+        self.pointers.pop();
+        self.functions.pop();
+    }
+
+    // 从语法上判断表达式是否为裸指针
+    fn is_raw_pointer(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Path(path) => path.qself.is_none()
+                && path.path.get_ident().is_some_and(|ident| {
+                    self.pointers.last().is_some_and(|names| names.contains(&ident.to_string()))
+                }),
+            Expr::Cast(cast) => matches!(*cast.ty, Type::Ptr(_)),
+            Expr::Paren(paren) => self.is_raw_pointer(&paren.expr),
+            Expr::Group(group) => self.is_raw_pointer(&group.expr),
+            Expr::Field(field) => match &field.member {
+                syn::Member::Named(name) => self.pointer_fields.contains(&name.to_string()),
+                syn::Member::Unnamed(_) => false,
+            },
+            Expr::MethodCall(call) => {
+                let method = call.method.to_string();
+                POINTER_CONVERSIONS.contains(&method.as_str())
+                    || (POINTER_METHODS.contains(&method.as_str()) && self.is_raw_pointer(&call.receiver))
+            }
+            Expr::Call(call) => match &*call.func {
+                Expr::Path(path) => path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| POINTER_FUNCTIONS.contains(&segment.ident.to_string().as_str())),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for UnsafeVisitor<'_> {
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        self.module_path.push(node.ident.unraw().to_string());
+        visit::visit_item_mod(self, node);
+        self.module_path.pop();
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.visit_function(&node.sig, node.span(), |v| visit::visit_item_fn(v, node));
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.visit_function(&node.sig, node.span(), |v| visit::visit_impl_item_fn(v, node));
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast TraitItemFn) {
+        self.visit_function(&node.sig, node.span(), |v| visit::visit_trait_item_fn(v, node));
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        if let Some(token) = node.unsafety {
+            self.record(UnsafeKind::Impl, token.span, node.span(), false);
+        }
+        let owner = self.owner.replace(type_name(&node.self_ty));
+        visit::visit_item_impl(self, node);
+        self.owner = owner;
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast ItemTrait) {
+        if let Some(token) = node.unsafety {
+            self.record(UnsafeKind::Trait, token.span, node.span(), true);
+        }
+        let owner = self.owner.replace(node.ident.unraw().to_string());
+        visit::visit_item_trait(self, node);
+        self.owner = owner;
+    }
+
+    fn visit_item_foreign_mod(&mut self, node: &'ast ItemForeignMod) {
+        self.record(UnsafeKind::ExternBlock, node.abi.extern_token.span, node.span(), false);
+        visit::visit_item_foreign_mod(self, node);
+    }
+
+    fn visit_item_static(&mut self, node: &'ast ItemStatic) {
+        if let StaticMutability::Mut(_) = node.mutability {
+            self.record(UnsafeKind::StaticMut, node.static_token.span, node.span(), false);
+        }
+        visit::visit_item_static(self, node);
+    }
+
+    fn visit_expr_unsafe(&mut self, node: &'ast ExprUnsafe) {
+        let documented = self.record(UnsafeKind::Block, node.unsafe_token.span, node.span(), false);
+        self.contexts.push(documented);
+        visit::visit_expr_unsafe(self, node);
+        self.contexts.pop();
+    }
+
+    fn visit_expr(&mut self, node: &'ast Expr) {
+        if let Expr::Unary(unary) = node {
+            if matches!(unary.op, UnOp::Deref(_)) && !self.contexts.is_empty() && self.is_raw_pointer(&unary.expr) {
+                let in_documented = self.contexts.last().copied().unwrap_or(false);
+                let span = node.span();
+                let documented = self.record(UnsafeKind::RawDeref, span, span, false);
+                if let Some(site) = self.sites.last_mut() {
+                    site.has_safety_comment = documented || in_documented;
+                }
+            }
+        }
+        visit::visit_expr(self, node);
+    }
+
+    fn visit_local(&mut self, node: &'ast Local) {
+        let (name, ty) = match &node.pat {
+            Pat::Type(typed) => (pat_name(&typed.pat), Some(&*typed.ty)),
+            pat => (pat_name(pat), None),
+        };
+        let is_pointer = match ty {
+            Some(ty) => matches!(ty, Type::Ptr(_)),
+            None => node.init.as_ref().is_some_and(|init| self.is_raw_pointer(&init.expr)),
+        };
+        if let (Some(name), Some(names)) = (name, self.pointers.last_mut()) {
+            // 同名变量重新绑定为其他类型时不再视为裸指针
+            if is_pointer {
+                names.insert(name);
+            } else {
+                names.remove(&name);
+            }
+        }
+        visit::visit_local(self, node);
+    }
+}
+
+// 文件中类型为裸指针的具名字段
+#[derive(Default)]
+struct PointerFields {
+    names: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for PointerFields {
+    fn visit_field(&mut self, node: &'ast syn::Field) {
+        if let (Some(ident), Type::Ptr(_)) = (&node.ident, &node.ty) {
+            self.names.insert(ident.unraw().to_string());
+        }
+    }
+}
+
+// 类型为裸指针的参数
+fn pointer_params(sig: &Signature) -> HashSet<String> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(typed) if matches!(*typed.ty, Type::Ptr(_)) => pat_name(&typed.pat),
+            _ => None,
+        })
+        .collect()
+}
+
+fn pat_name(pat: &Pat) -> Option<String> {
+    match pat {
+        Pat::Ident(ident) => Some(ident.ident.unraw().to_string()),
+        _ => None,
+    }
+}
+
+// 第 line 行（从 1 开始）之前紧挨着的注释中是否有 `SAFETY:`，中间可以隔着属性；
+// doc_section 为 true 时文档注释中的 `# Safety` 小节也算数
+fn safety_comment(lines: &[&str], line: usize, doc_section: bool) -> bool {
+    let mut index = line.saturating_sub(1);
+    while index > 0 {
+        index -= 1;
+        let text = lines.get(index).map_or("", |text| text.trim());
+        let is_comment = text.starts_with("//") || text.starts_with("/*") || text.starts_with('*') || text.ends_with("*/");
+        if is_comment {
+            let body = text.trim_start_matches(['/', '*', '!', ' ']);
+            if body.starts_with("SAFETY:") || body.starts_with("Safety:") {
+                return true;
+            }
+            if doc_section && body.trim_start_matches('#').trim() == "Safety" && body.starts_with('#') {
+                return true;
+            }
+        } else if !text.starts_with("#[") {
+            return false;
+        }
+    }
+    false
+}
+
+// 各类 unsafe 代码的数量，以及缺少 SAFETY 注释的数量
+#[derive(Debug, Clone, Default)]
+pub struct UnsafeTotals {
+    pub by_kind: BTreeMap<UnsafeKind, usize>,
+    pub undocumented: usize,
+}
+
+impl UnsafeTotals {
+    pub fn compute(files: &[FileItems]) -> Self {
+        let mut totals = UnsafeTotals::default();
+        for site in files.iter().flat_map(|items| &items.unsafe_sites) {
+            *totals.by_kind.entry(site.kind).or_default() += 1;
+            if !site.has_safety_comment {
+                totals.undocumented += 1;
+            }
+        }
+        totals
+    }
+
+    pub fn total(&self) -> usize {
+        self.by_kind.values().sum()
+    }
+
+    // 例如 "unsafe block 3, raw pointer deref 1"
+    pub fn render(&self) -> String {
+        let parts: Vec<String> = self
+            .by_kind
+            .iter()
+            .map(|(kind, count)| format!("{} {}", kind.keyword(), count))
+            .collect();
+        if parts.is_empty() {
+            "无".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+// 基线中的一条记录。比较时不看行号，代码移动位置不算变化
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    // crate 根文件，相对于分析的目录
+    #[serde(rename = "crate")]
+    pub root: String,
+    pub file: String,
+    pub kind: String,
+    pub enclosing: String,
+    pub line: usize,
+}
+
+impl BaselineEntry {
+    fn key(&self) -> (&str, &str, &str, &str) {
+        (&self.root, &self.file, &self.kind, &self.enclosing)
+    }
+}
+
+// 保存下来的 unsafe 代码清单，用于发现新增的 unsafe 代码
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+// 当前代码与基线相比新增和消失的记录
+#[derive(Debug, Clone, Default)]
+pub struct BaselineDiff {
+    pub added: Vec<BaselineEntry>,
+    pub removed: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    // crates 中每项为 crate 根文件及其中的文件，路径记录为相对于 dir 的形式
+    pub fn from_crates<'a>(dir: &Path, crates: impl IntoIterator<Item = (&'a Path, &'a [FileItems])>) -> Self {
+        let relative = |path: &Path| path.strip_prefix(dir).unwrap_or(path).display().to_string();
+        let mut entries = Vec::new();
+        for (root, files) in crates {
+            for items in files {
+                for site in &items.unsafe_sites {
+                    entries.push(BaselineEntry {
+                        root: relative(root),
+                        file: relative(&items.file),
+                        kind: site.kind.keyword().to_string(),
+                        enclosing: site.enclosing.clone(),
+                        line: site.location.start_line,
+                    });
+                }
+            }
+        }
+        entries.sort();
+        Baseline { entries }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("无法读取基线 {}: {}", path.display(), e))?;
+        serde_json::from_str(&content).map_err(|e| format!("无法解析基线 {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, content + "\n").map_err(|e| format!("无法写入基线 {}: {}", path.display(), e))
+    }
+
+    // 同一位置（crate、文件、种类、所在函数）的记录按数量比较，多出来的算新增，少了的算消失
+    pub fn diff(&self, current: &Baseline) -> BaselineDiff {
+        let mut counts: BTreeMap<(&str, &str, &str, &str), isize> = BTreeMap::new();
+        for entry in &self.entries {
+            *counts.entry(entry.key()).or_default() -= 1;
+        }
+        for entry in &current.entries {
+            *counts.entry(entry.key()).or_default() += 1;
+        }
+        let mut diff = BaselineDiff::default();
+        for (entries, sign, out) in [(&current.entries, 1, &mut diff.added), (&self.entries, -1, &mut diff.removed)] {
+            let mut remaining = counts.clone();
+            // 同一位置有多条记录时，报告靠后的几条
+            for entry in entries.iter().rev() {
+                let count = remaining.entry(entry.key()).or_default();
+                if *count * sign > 0 {
+                    *count -= sign;
+                    out.push(entry.clone());
+                }
+            }
+            out.reverse();
+        }
+        diff
+    }
+}
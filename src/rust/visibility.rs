@@ -0,0 +1,49 @@
+use crate::rust::signature::render_tokens;
+
+// 条目声明的可见性
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Visibility {
+    // pub
+    Public,
+    // pub(crate)
+    Crate,
+    // pub(super)
+    Super,
+    // pub(in path)，保存路径文本，例如 "crate::net"
+    Restricted(String),
+    // 未加修饰或 pub(self)
+    Private,
+}
+
+impl Visibility {
+    pub fn from_syn(vis: &syn::Visibility) -> Self {
+        match vis {
+            syn::Visibility::Public(_) => Visibility::Public,
+            syn::Visibility::Inherited => Visibility::Private,
+            syn::Visibility::Restricted(restricted) => {
+                let path = render_tokens(&restricted.path);
+                match (restricted.in_token.is_some(), path.as_str()) {
+                    (false, "crate") => Visibility::Crate,
+                    (false, "super") => Visibility::Super,
+                    (false, "self") => Visibility::Private,
+                    _ => Visibility::Restricted(path),
+                }
+            }
+        }
+    }
+
+    pub fn is_public(&self) -> bool {
+        *self == Visibility::Public
+    }
+
+    // 源码中的修饰符，私有时为空字符串
+    pub fn as_str(&self) -> String {
+        match self {
+            Visibility::Public => "pub".to_string(),
+            Visibility::Crate => "pub(crate)".to_string(),
+            Visibility::Super => "pub(super)".to_string(),
+            Visibility::Restricted(path) => format!("pub(in {})", path),
+            Visibility::Private => String::new(),
+        }
+    }
+}
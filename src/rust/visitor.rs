@@ -0,0 +1,955 @@
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    Attribute, Block, Expr, ExprCall, ExprMethodCall, Fields, GenericArgument, Generics, Ident, ImplItem, ImplItemFn,
+    Item, ItemConst, ItemEnum, ItemFn, ItemImpl, ItemMacro, ItemMod, ItemStatic, ItemStruct, ItemTrait, ItemType,
+    ItemUnion, ItemUse, PathArguments, ReturnType, Signature, StaticMutability, Token, TraitBoundModifier, TraitItem,
+    TraitItemFn, Type, TypeParamBound, UseTree, WherePredicate,
+};
+
+use crate::rust::attributes::{doc_text, item_attrs};
+use crate::rust::cfg::CfgSet;
+use crate::rust::macros::MacroTable;
+use crate::rust::metrics::fn_metrics;
+use crate::rust::model::{
+    AssocConst, AssocType, Call, CallKind, Field, FieldStyle, FileItems, FnItem, FnKind, ItemAttrs, Location, NameDef,
+    TraitDef, TraitImpl, TypeAlias, TypeItem, TypeKind, UseDecl, ValueItem, ValueKind, Variant,
+};
+use crate::rust::modules::ModuleScope;
+use crate::rust::signature::{render_tokens, FnSignature};
+use crate::rust::visibility::Visibility;
+
+pub struct FunctionVisitor {
+    pub items: FileItems,
+    // 当前所在的模块路径，进入内联模块时追加
+    module_path: Vec<String>,
+    // 当前作用域的性质。其中 exported 表示作用域中声明为 pub 的条目能否被下游访问：
+    // 私有模块、函数体、impl 块和 trait 内部都不能
+    scope: ModuleScope,
+    // 是否处于函数体、impl 块等内部，这里的条目不属于任何模块的命名空间
+    in_block: bool,
+    // crate 中定义的 macro_rules! 宏，用于展开条目位置的宏调用
+    macros: MacroTable,
+    // 正在访问其展开结果的宏，以及宏展开的嵌套层数
+    expanding: Option<String>,
+    expansion_depth: usize,
+    // 正在展开的宏调用的位置，展开生成的条目都记录为这个位置
+    expansion_site: Option<Location>,
+    // 按 --features / --cfg 分析时的 cfg 选项，用于裁剪宏展开生成的条目
+    cfg: Option<CfgSet>,
+    // 当前模块中 use 引入的名称及其源路径，在访问模块内容之前收集，用于解析 impl 块的类型路径
+    imports: Vec<(String, Vec<String>)>,
+}
+
+// 宏展开结果中再次调用宏时的最大嵌套层数
+const MAX_EXPANSION_DEPTH: usize = 16;
+
+impl FunctionVisitor {
+    // module_path 是文件对应的模块路径，scope 是该模块从各级父模块继承的可见性、隐藏和 cfg 条件
+    pub fn new(module_path: Vec<String>, scope: ModuleScope) -> Self {
+        FunctionVisitor {
+            items: FileItems {
+                module: module_path.join("::"),
+                ..FileItems::default()
+            },
+            module_path,
+            scope,
+            in_block: false,
+            macros: MacroTable::default(),
+            expanding: None,
+            expansion_depth: 0,
+            expansion_site: None,
+            cfg: None,
+            imports: Vec::new(),
+        }
+    }
+
+    // 展开宏调用时使用的本地宏定义
+    pub fn with_macros(mut self, macros: MacroTable) -> Self {
+        self.macros = macros;
+        self
+    }
+
+    // 宏展开生成的条目同样按 cfg 裁剪
+    pub fn with_cfg(mut self, cfg: Option<CfgSet>) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    // 条目的属性，位于隐藏模块中的条目同样视为隐藏，所在模块的 cfg 条件排在条目自身的条件之前
+    fn attrs_of(&self, attrs: &[Attribute]) -> ItemAttrs {
+        let mut result = item_attrs(attrs);
+        result.resolved_derives = result
+            .derives
+            .iter()
+            .map(|derive| match syn::parse_str::<syn::Path>(derive) {
+                Ok(path) => self.resolve_path(&path_segments(&path)),
+                Err(_) => derive.clone(),
+            })
+            .collect();
+        result.doc_hidden |= self.scope.hidden;
+        result.cfg.splice(0..0, self.scope.cfg.iter().cloned());
+        result
+    }
+
+    // 当前模块下某个名称的完整路径
+    fn qualify(&self, name: &str) -> String {
+        let mut path = self.module_path.join("::");
+        if !path.is_empty() {
+            path.push_str("::");
+        }
+        path.push_str(name);
+        path
+    }
+
+    // 把源码中写出的路径解析为 crate 内的完整路径：以 crate / self / super 开头的路径按模块树解析，
+    // 首段是 use 引入的名称时先替换为其源路径，其余路径相对于当前模块
+    fn resolve_path(&self, segments: &[String]) -> String {
+        let mut segments = segments.to_vec();
+        if let Some((_, source)) = self.imports.iter().find(|(name, _)| segments.first() == Some(name)) {
+            let is_local = matches!(source.first().map(String::as_str), Some("crate" | "self" | "super"));
+            // 从外部 crate 引入的名称，例如 `use std::fmt::Display;`
+            if !is_local {
+                return source.iter().chain(&segments[1..]).cloned().collect::<Vec<_>>().join("::");
+            }
+            segments.splice(0..1, source.iter().cloned());
+        }
+        let mut path = match segments.first().map(String::as_str) {
+            Some("::") => return segments[1..].join("::"),
+            Some("crate") => {
+                segments.remove(0);
+                vec!["crate".to_string()]
+            }
+            _ => self.module_path.clone(),
+        };
+        let mut rest = segments.as_slice();
+        while let Some(first) = rest.first() {
+            match first.as_str() {
+                "self" => {}
+                "super" => {
+                    path.pop();
+                }
+                _ => break,
+            }
+            rest = &rest[1..];
+        }
+        path.extend(rest.iter().cloned());
+        path.join("::")
+    }
+
+    // impl 块目标类型是泛型参数时，它的 trait 约束的源码写法和完整路径，见 TraitImpl::self_bounds
+    fn self_bounds(&self, generics: &Generics, self_ty: &Type) -> Vec<(String, String)> {
+        let self_ty = render_tokens(self_ty);
+        let Some(param) = generics.type_params().find(|param| param.ident == self_ty) else {
+            return Vec::new();
+        };
+        let predicates = generics
+            .where_clause
+            .iter()
+            .flat_map(|clause| &clause.predicates)
+            .filter_map(|predicate| match predicate {
+                WherePredicate::Type(predicate) if render_tokens(&predicate.bounded_ty) == self_ty => {
+                    Some(&predicate.bounds)
+                }
+                _ => None,
+            })
+            .flatten();
+        // 忽略生命周期和 `?Sized`
+        param
+            .bounds
+            .iter()
+            .chain(predicates)
+            .filter_map(|bound| match bound {
+                TypeParamBound::Trait(bound) if matches!(bound.modifier, TraitBoundModifier::None) => {
+                    Some((render_tokens(&bound.path), self.resolve_path(&path_segments(&bound.path))))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    // impl 块目标类型的完整路径，不是路径的类型（&T、[u8] 等）按当前模块下的名称处理
+    fn impl_type_path(&self, ty: &Type) -> String {
+        match type_segments(ty) {
+            Some(segments) => self.resolve_path(&segments),
+            None => self.qualify(&type_name(ty)),
+        }
+    }
+
+    // 在访问模块内容之前收集其中的 use 声明
+    fn collect_imports(&mut self, items: &[Item]) {
+        self.imports.clear();
+        for item in items {
+            if let Item::Use(item_use) = item {
+                let mut prefix = Vec::new();
+                if item_use.leading_colon.is_some() {
+                    prefix.push("::".to_string());
+                }
+                let mut entries = Vec::new();
+                flatten_use_tree(&item_use.tree, &mut prefix, &mut entries);
+                self.imports.extend(entries.into_iter().filter_map(|(name, source)| Some((name?, source))));
+            }
+        }
+    }
+
+    // 记录模块级别的名称定义
+    fn define_name(&mut self, name: &str, is_module: bool, vis: &Visibility) {
+        self.items.defs.push(NameDef {
+            module: self.module_path.join("::"),
+            name: name.to_string(),
+            path: self.qualify(name),
+            is_module,
+            vis: vis.clone(),
+        });
+    }
+
+    // 条目的源码位置，宏展开生成的条目取宏调用处的位置
+    fn locate<T: Spanned>(&self, node: &T) -> Location {
+        self.expansion_site.unwrap_or_else(|| Location::from_span(node.span()))
+    }
+
+    // 记录类型声明，返回类型条目以便补充字段和变体
+    fn declare_type<T: Spanned>(
+        &mut self,
+        node: &T,
+        attrs: &[Attribute],
+        name: String,
+        kind: TypeKind,
+        vis: &syn::Visibility,
+        generics: &Generics,
+    ) -> &mut TypeItem {
+        let vis = Visibility::from_syn(vis);
+        self.define_name(&name, false, &vis);
+        let exported = self.scope.exported && vis.is_public();
+        let path = self.qualify(&name);
+        let (generics, where_predicates) = render_generics(generics);
+        let expanded_from = self.expanding.clone();
+        let location = self.locate(node);
+        let attrs_of = self.attrs_of(attrs);
+        let entry = self.items.type_entry(&name, &path);
+        entry.kind = Some(kind);
+        entry.vis = vis;
+        entry.exported = exported;
+        entry.generics = generics;
+        entry.where_predicates = where_predicates;
+        entry.expanded_from = expanded_from;
+        entry.location = location;
+        entry.docs = doc_text(attrs);
+        entry.attrs = attrs_of;
+        entry
+    }
+
+    // 记录 const / static，`const _: () = ...;` 这类匿名常量不记录
+    fn declare_value<T: ValueNode>(&mut self, node: &T, kind: ValueKind) {
+        let name = node.ident().to_string();
+        if name == "_" {
+            return;
+        }
+        let attrs = node.attrs();
+        let vis = Visibility::from_syn(node.vis());
+        self.define_name(&name, false, &vis);
+        self.items.values.push(ValueItem {
+            path: self.qualify(&name),
+            name,
+            kind,
+            exported: self.scope.exported && vis.is_public(),
+            vis,
+            public_paths: Vec::new(),
+            canonical_path: None,
+            expanded_from: self.expanding.clone(),
+            location: self.locate(node),
+            docs: doc_text(attrs),
+            attrs: self.attrs_of(attrs),
+            ty: render_tokens(node.ty()),
+            closure: matches!(node.expr(), Expr::Closure(_)),
+        });
+    }
+
+    // 根据函数或方法的语法节点生成 FnItem，exported 之后再按作用域计算
+    fn fn_item<T: FnNode>(&self, node: &T, path: String, kind: FnKind, vis: Visibility) -> FnItem {
+        FnItem {
+            path,
+            sig: FnSignature::from_syn(node.sig()),
+            kind,
+            vis,
+            exported: false,
+            public_paths: Vec::new(),
+            canonical_path: None,
+            expanded_from: self.expanding.clone(),
+            location: self.locate(node),
+            docs: doc_text(node.attrs()),
+            attrs: self.attrs_of(node.attrs()),
+            calls_panic: node.body().is_some_and(calls_panic),
+            calls: node.body().map(|body| collect_calls(body, self.expansion_site)).unwrap_or_default(),
+            provided: false,
+            metrics: node.body().map(|body| fn_metrics(node.sig(), body, &self.locate(node))),
+        }
+    }
+
+    // 对方法分类：没有接收者且返回 Self 或所属类型（包括 Result<Self>、Option<Self> 等）的视为构造函数。
+    // owner_path 是所属类型或 trait 的完整路径，exported 由 modules::resolve_exports 根据所属类型计算
+    fn method_item<T: FnNode>(&self, node: &T, owner: &str, owner_path: &str, vis: Visibility) -> FnItem {
+        let sig = node.sig();
+        let has_receiver = sig.receiver().is_some();
+        let kind = match &sig.output {
+            ReturnType::Type(_, ty) if !has_receiver && returns_owner(ty, owner) => FnKind::Constructor,
+            _ => FnKind::Method,
+        };
+        self.fn_item(node, format!("{}::{}", owner_path, sig.ident), kind, vis)
+    }
+
+    // 在不对外可见的作用域中访问子节点（函数体、impl 块等）
+    fn visit_private_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        let saved = (self.scope.exported, self.in_block);
+        self.scope.exported = false;
+        self.in_block = true;
+        f(self);
+        (self.scope.exported, self.in_block) = saved;
+    }
+}
+
+impl<'ast> Visit<'ast> for FunctionVisitor {
+    fn visit_file(&mut self, node: &'ast syn::File) {
+        self.items.module_docs = doc_text(&node.attrs);
+        let attrs = item_attrs(&node.attrs);
+        self.scope.hidden |= attrs.doc_hidden;
+        self.scope.cfg.extend(attrs.cfg);
+        self.collect_imports(&node.items);
+        visit::visit_file(self, node);
+    }
+
+    // 函数体、impl 块等内部声明的条目只在局部可见，不属于任何模块的命名空间，不记录
+    fn visit_item(&mut self, node: &'ast Item) {
+        if !self.in_block {
+            visit::visit_item(self, node);
+        }
+    }
+
+    // 提取普通函数
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        let vis = Visibility::from_syn(&node.vis);
+        let name = node.sig.ident.to_string();
+        self.define_name(&name, false, &vis);
+        let exported = self.scope.exported && vis.is_public();
+        let mut item = self.fn_item(node, self.qualify(&name), FnKind::Function, vis);
+        item.exported = exported;
+        self.items.functions.push(item);
+        self.visit_private_scope(|v| visit::visit_item_fn(v, node));
+    }
+
+    // 内联模块：只有 pub 模块中的 pub 条目才对外可见
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        let vis = Visibility::from_syn(&node.vis);
+        let name = node.ident.unraw().to_string();
+        self.define_name(&name, true, &vis);
+        let child = self.scope.child(&node.vis, &node.attrs);
+        let saved = (std::mem::replace(&mut self.scope, child), std::mem::take(&mut self.imports));
+        self.module_path.push(name);
+        if let Some((_, items)) = &node.content {
+            self.collect_imports(items);
+        }
+        visit::visit_item_mod(self, node);
+        self.module_path.pop();
+        (self.scope, self.imports) = saved;
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast ItemStruct) {
+        let fields = extract_fields(&node.fields, false, self.expansion_site);
+        let entry = self.declare_type(node, &node.attrs, node.ident.to_string(), TypeKind::Struct, &node.vis, &node.generics);
+        (entry.style, entry.fields) = fields;
+        visit::visit_item_struct(self, node);
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast ItemEnum) {
+        let site = self.expansion_site;
+        let entry = self.declare_type(node, &node.attrs, node.ident.to_string(), TypeKind::Enum, &node.vis, &node.generics);
+        entry.variants = node
+            .variants
+            .iter()
+            .map(|variant| {
+                let (style, fields) = extract_fields(&variant.fields, true, site);
+                Variant {
+                    name: variant.ident.to_string(),
+                    style,
+                    fields,
+                    discriminant: variant.discriminant.as_ref().map(|(_, expr)| render_tokens(expr)),
+                    location: site.unwrap_or_else(|| Location::from_span(variant.span())),
+                    docs: doc_text(&variant.attrs),
+                    attrs: item_attrs(&variant.attrs),
+                }
+            })
+            .collect();
+        visit::visit_item_enum(self, node);
+    }
+
+    fn visit_item_union(&mut self, node: &'ast ItemUnion) {
+        let site = self.expansion_site;
+        let entry = self.declare_type(node, &node.attrs, node.ident.to_string(), TypeKind::Union, &node.vis, &node.generics);
+        entry.style = FieldStyle::Named;
+        entry.fields = node.fields.named.iter().map(|field| named_field(field, false, site)).collect();
+        visit::visit_item_union(self, node);
+    }
+
+    fn visit_item_type(&mut self, node: &'ast ItemType) {
+        let name = node.ident.to_string();
+        let vis = Visibility::from_syn(&node.vis);
+        self.define_name(&name, false, &vis);
+        let (generics, where_predicates) = render_generics(&node.generics);
+        self.items.aliases.push(TypeAlias {
+            path: self.qualify(&name),
+            name,
+            exported: self.scope.exported && vis.is_public(),
+            vis,
+            public_paths: Vec::new(),
+            canonical_path: None,
+            expanded_from: self.expanding.clone(),
+            location: self.locate(node),
+            docs: doc_text(&node.attrs),
+            attrs: self.attrs_of(&node.attrs),
+            generics,
+            where_predicates,
+            ty: render_tokens(&node.ty),
+        });
+        visit::visit_item_type(self, node);
+    }
+
+    fn visit_item_const(&mut self, node: &'ast ItemConst) {
+        self.declare_value(node, ValueKind::Const);
+        self.visit_private_scope(|v| visit::visit_item_const(v, node));
+    }
+
+    fn visit_item_static(&mut self, node: &'ast ItemStatic) {
+        let kind = match node.mutability {
+            StaticMutability::Mut(_) => ValueKind::StaticMut,
+            _ => ValueKind::Static,
+        };
+        self.declare_value(node, kind);
+        self.visit_private_scope(|v| visit::visit_item_static(v, node));
+    }
+
+    // 提取impl块中的方法，固有方法归到类型下，trait实现单独记录。
+    // impl 块上的 #[doc(hidden)] 和 cfg 条件作用于其中的所有方法
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        let saved_scope = self.scope.clone();
+        let attrs = item_attrs(&node.attrs);
+        self.scope.hidden |= attrs.doc_hidden;
+        self.scope.cfg.extend(attrs.cfg);
+        let type_name = type_name(&node.self_ty);
+        let type_path = self.impl_type_path(&node.self_ty);
+
+        match &node.trait_ {
+            None => {
+                let methods: Vec<FnItem> = node
+                    .items
+                    .iter()
+                    .filter_map(|item| match item {
+                        ImplItem::Fn(method) => Some(self.method_item(
+                            method,
+                            &type_name,
+                            &type_path,
+                            Visibility::from_syn(&method.vis),
+                        )),
+                        _ => None,
+                    })
+                    .collect();
+                let scope_public = self.scope.exported;
+                let location = self.locate(node);
+                let entry = self.items.type_entry(&type_name, &type_path);
+                // 类型声明不在本文件中时，暂按 impl 块所在作用域估计其可见性，位置取第一个 impl 块
+                if entry.kind.is_none() {
+                    entry.exported = scope_public;
+                    if entry.location == Location::default() {
+                        entry.location = location;
+                    }
+                }
+                entry.methods.extend(methods);
+            }
+            Some((bang, path, _)) => {
+                let methods = node
+                    .items
+                    .iter()
+                    .filter_map(|item| match item {
+                        ImplItem::Fn(method) => {
+                            Some(self.method_item(method, &type_name, &type_path, Visibility::Public))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                let (generics, where_predicates) = render_generics(&node.generics);
+                let trait_name = path
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string())
+                    .unwrap_or_default();
+                self.items.trait_impls.push(TraitImpl {
+                    type_name,
+                    type_path,
+                    self_ty: render_tokens(&node.self_ty),
+                    trait_name,
+                    trait_path: format!("{}{}", if bang.is_some() { "!" } else { "" }, render_tokens(path)),
+                    resolved_trait: self.resolve_path(&path_segments(path)),
+                    generics,
+                    where_predicates,
+                    self_bounds: self.self_bounds(&node.generics, &node.self_ty),
+                    exported: self.scope.exported,
+                    expanded_from: self.expanding.clone(),
+                    location: self.locate(node),
+                    methods,
+                });
+            }
+        }
+        self.visit_private_scope(|v| visit::visit_item_impl(v, node));
+        self.scope = saved_scope;
+    }
+
+    // 提取trait的签名和关联条目，方法的可见性与 trait 相同
+    fn visit_item_trait(&mut self, node: &'ast ItemTrait) {
+        let trait_name = node.ident.to_string();
+        let trait_path = self.qualify(&trait_name);
+        let vis = Visibility::from_syn(&node.vis);
+        self.define_name(&trait_name, false, &vis);
+        let (generics, where_predicates) = render_generics(&node.generics);
+        let mut assoc_types = Vec::new();
+        let mut assoc_consts = Vec::new();
+        let mut methods = Vec::new();
+        for item in &node.items {
+            match item {
+                TraitItem::Fn(method) => {
+                    let mut method_item = self.method_item(method, &trait_name, &trait_path, vis.clone());
+                    method_item.provided = method.default.is_some();
+                    methods.push(method_item);
+                }
+                TraitItem::Type(ty) => {
+                    let (generics, where_predicates) = render_generics(&ty.generics);
+                    assoc_types.push(AssocType {
+                        name: ty.ident.to_string(),
+                        generics,
+                        bounds: ty.bounds.iter().map(render_tokens).collect(),
+                        where_predicates,
+                        default: ty.default.as_ref().map(|(_, ty)| render_tokens(ty)),
+                        location: self.locate(ty),
+                        docs: doc_text(&ty.attrs),
+                    });
+                }
+                TraitItem::Const(constant) => assoc_consts.push(AssocConst {
+                    name: constant.ident.to_string(),
+                    ty: render_tokens(&constant.ty),
+                    default: constant.default.as_ref().map(|(_, expr)| render_tokens(expr)),
+                    location: self.locate(constant),
+                    docs: doc_text(&constant.attrs),
+                }),
+                _ => {}
+            }
+        }
+        self.items.traits.push(TraitDef {
+            name: trait_name,
+            path: trait_path,
+            exported: self.scope.exported && vis.is_public(),
+            vis,
+            public_paths: Vec::new(),
+            canonical_path: None,
+            expanded_from: self.expanding.clone(),
+            location: self.locate(node),
+            docs: doc_text(&node.attrs),
+            attrs: self.attrs_of(&node.attrs),
+            is_unsafe: node.unsafety.is_some(),
+            is_auto: node.auto_token.is_some(),
+            generics,
+            where_predicates,
+            supertraits: node.supertraits.iter().map(render_tokens).collect(),
+            assoc_types,
+            assoc_consts,
+            methods,
+            implementors: Vec::new(),
+        });
+        self.visit_private_scope(|v| visit::visit_item_trait(v, node));
+    }
+
+    // 条目位置调用本地 macro_rules! 宏时，展开后访问生成的条目
+    fn visit_item_macro(&mut self, node: &'ast ItemMacro) {
+        if node.mac.path.is_ident("macro_rules") {
+            if let Some(ident) = &node.ident {
+                self.items.macro_defs.push(ident.to_string());
+            }
+            return;
+        }
+        let Some(name) = self.macros.lookup(&node.mac) else {
+            return;
+        };
+        if self.expansion_depth >= MAX_EXPANSION_DEPTH {
+            self.items.warnings.push(format!("{}! 的展开嵌套超过 {} 层，已停止展开", name, MAX_EXPANSION_DEPTH));
+            return;
+        }
+        match self.macros.expand(&node.mac) {
+            Ok(mut items) => {
+                if let Some(cfg) = &self.cfg {
+                    cfg.strip_items(&mut items);
+                }
+                let saved = (self.expanding.replace(name), self.expansion_site);
+                // 嵌套展开时保留最外层宏调用的位置，它才对应真实的源码
+                self.expansion_site.get_or_insert(Location::from_span(node.span()));
+                self.expansion_depth += 1;
+                for item in &items {
+                    self.visit_item(item);
+                }
+                self.expansion_depth -= 1;
+                (self.expanding, self.expansion_site) = saved;
+            }
+            Err(message) => self.items.warnings.push(message),
+        }
+    }
+
+    // 记录 use 声明，用于解析重导出
+    fn visit_item_use(&mut self, node: &'ast ItemUse) {
+        let vis = Visibility::from_syn(&node.vis);
+        let mut prefix = Vec::new();
+        if node.leading_colon.is_some() {
+            prefix.push("::".to_string());
+        }
+        let mut entries = Vec::new();
+        flatten_use_tree(&node.tree, &mut prefix, &mut entries);
+        let module = self.module_path.join("::");
+        for (name, source) in entries {
+            self.items.uses.push(UseDecl {
+                module: module.clone(),
+                name,
+                source,
+                vis: vis.clone(),
+            });
+        }
+    }
+}
+
+// 函数、固有方法和 trait 方法的共同部分
+trait FnNode: Spanned {
+    fn attrs(&self) -> &[Attribute];
+    fn sig(&self) -> &Signature;
+    // trait 中没有默认实现的方法没有函数体
+    fn body(&self) -> Option<&Block>;
+}
+
+impl FnNode for ItemFn {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn sig(&self) -> &Signature {
+        &self.sig
+    }
+    fn body(&self) -> Option<&Block> {
+        Some(&self.block)
+    }
+}
+
+impl FnNode for ImplItemFn {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn sig(&self) -> &Signature {
+        &self.sig
+    }
+    fn body(&self) -> Option<&Block> {
+        Some(&self.block)
+    }
+}
+
+impl FnNode for TraitItemFn {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn sig(&self) -> &Signature {
+        &self.sig
+    }
+    fn body(&self) -> Option<&Block> {
+        self.default.as_ref()
+    }
+}
+
+// const 和 static 的共同部分
+trait ValueNode: Spanned {
+    fn attrs(&self) -> &[Attribute];
+    fn ident(&self) -> &Ident;
+    fn vis(&self) -> &syn::Visibility;
+    fn ty(&self) -> &Type;
+    fn expr(&self) -> &Expr;
+}
+
+impl ValueNode for ItemConst {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn ident(&self) -> &Ident {
+        &self.ident
+    }
+    fn vis(&self) -> &syn::Visibility {
+        &self.vis
+    }
+    fn ty(&self) -> &Type {
+        &self.ty
+    }
+    fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+impl ValueNode for ItemStatic {
+    fn attrs(&self) -> &[Attribute] {
+        &self.attrs
+    }
+    fn ident(&self) -> &Ident {
+        &self.ident
+    }
+    fn vis(&self) -> &syn::Visibility {
+        &self.vis
+    }
+    fn ty(&self) -> &Type {
+        &self.ty
+    }
+    fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+// 会直接导致 panic 的宏，用于检查文档是否需要 `# Panics` 小节
+const PANIC_MACROS: &[&str] = &[
+    "panic", "assert", "assert_eq", "assert_ne", "unreachable", "todo", "unimplemented",
+];
+
+// 收集函数体中的调用。宏的参数能按逗号分隔的表达式解析时（println!、assert_eq! 等），
+// 其中的调用也一并收集
+fn collect_calls(block: &Block, site: Option<Location>) -> Vec<Call> {
+    struct CallFinder {
+        calls: Vec<Call>,
+        site: Option<Location>,
+    }
+
+    impl CallFinder {
+        fn push<T: Spanned>(&mut self, node: &T, callee: String, kind: CallKind, on_self: bool) {
+            self.calls.push(Call {
+                callee,
+                kind,
+                on_self,
+                location: self.site.unwrap_or_else(|| Location::from_span(node.span())),
+            });
+        }
+    }
+
+    impl<'ast> Visit<'ast> for CallFinder {
+        fn visit_expr_call(&mut self, node: &'ast ExprCall) {
+            if let Expr::Path(func) = &*node.func {
+                if func.qself.is_none() {
+                    self.push(node, path_text(&func.path), CallKind::Path, false);
+                }
+            }
+            visit::visit_expr_call(self, node);
+        }
+
+        fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+            let on_self = matches!(&*node.receiver, Expr::Path(receiver) if receiver.path.is_ident("self"));
+            self.push(node, node.method.to_string(), CallKind::Method, on_self);
+            visit::visit_expr_method_call(self, node);
+        }
+
+        fn visit_macro(&mut self, node: &'ast syn::Macro) {
+            if let Some(segment) = node.path.segments.last() {
+                self.push(node, segment.ident.to_string(), CallKind::Macro, false);
+            }
+            if let Ok(args) = node.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+                for arg in &args {
+                    self.visit_expr(arg);
+                }
+            }
+        }
+
+        // 嵌套函数中的调用属于嵌套函数自己
+        fn visit_item(&mut self, _: &'ast Item) {}
+    }
+
+    let mut finder = CallFinder {
+        calls: Vec::new(),
+        site,
+    };
+    finder.visit_block(block);
+    finder.calls
+}
+
+// 路径的文本形式，去掉泛型参数，例如 `Vec::<u8>::new` 为 "Vec::new"
+fn path_text(path: &syn::Path) -> String {
+    let segments: Vec<String> = path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+    let text = segments.join("::");
+    if path.leading_colon.is_some() {
+        format!("::{}", text)
+    } else {
+        text
+    }
+}
+
+// 函数体中（不含嵌套的条目）是否调用了 PANIC_MACROS 中的宏
+fn calls_panic(block: &Block) -> bool {
+    struct PanicFinder(bool);
+
+    impl<'ast> Visit<'ast> for PanicFinder {
+        fn visit_macro(&mut self, node: &'ast syn::Macro) {
+            if let Some(segment) = node.path.segments.last() {
+                self.0 |= PANIC_MACROS.iter().any(|name| segment.ident == name);
+            }
+            visit::visit_macro(self, node);
+        }
+
+        // 嵌套的函数等条目有自己的文档
+        fn visit_item(&mut self, _: &'ast Item) {}
+    }
+
+    let mut finder = PanicFinder(false);
+    finder.visit_block(block);
+    finder.0
+}
+
+// 提取结构体或枚举变体的字段，in_variant 为 true 时字段沿用枚举的可见性，记为 pub
+// site 是正在展开的宏调用的位置，展开生成的字段都记录为这个位置
+fn extract_fields(fields: &Fields, in_variant: bool, site: Option<Location>) -> (FieldStyle, Vec<Field>) {
+    match fields {
+        Fields::Named(named) => (
+            FieldStyle::Named,
+            named.named.iter().map(|field| named_field(field, in_variant, site)).collect(),
+        ),
+        Fields::Unnamed(unnamed) => (
+            FieldStyle::Tuple,
+            unnamed
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(index, field)| Field {
+                    name: index.to_string(),
+                    vis: field_vis(field, in_variant),
+                    ty: render_tokens(&field.ty),
+                    location: site.unwrap_or_else(|| Location::from_span(field.span())),
+                    docs: doc_text(&field.attrs),
+                    attrs: item_attrs(&field.attrs),
+                })
+                .collect(),
+        ),
+        Fields::Unit => (FieldStyle::Unit, Vec::new()),
+    }
+}
+
+fn named_field(field: &syn::Field, in_variant: bool, site: Option<Location>) -> Field {
+    Field {
+        name: field.ident.as_ref().map(|ident| ident.unraw().to_string()).unwrap_or_default(),
+        vis: field_vis(field, in_variant),
+        ty: render_tokens(&field.ty),
+        location: site.unwrap_or_else(|| Location::from_span(field.span())),
+        docs: doc_text(&field.attrs),
+        attrs: item_attrs(&field.attrs),
+    }
+}
+
+fn field_vis(field: &syn::Field, in_variant: bool) -> Visibility {
+    if in_variant {
+        Visibility::Public
+    } else {
+        Visibility::from_syn(&field.vis)
+    }
+}
+
+// 把 use 树展开成 (引入的名称, 源路径) 列表，glob 导入的名称为 None
+fn flatten_use_tree(tree: &UseTree, prefix: &mut Vec<String>, out: &mut Vec<(Option<String>, Vec<String>)>) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            flatten_use_tree(&path.tree, prefix, out);
+            prefix.pop();
+        }
+        // `use a::b::{self}` 引入的是 b 本身
+        UseTree::Name(name) if name.ident == "self" => {
+            out.push((prefix.last().cloned(), prefix.clone()));
+        }
+        UseTree::Name(name) => {
+            let mut source = prefix.clone();
+            source.push(name.ident.to_string());
+            out.push((Some(name.ident.unraw().to_string()), source));
+        }
+        UseTree::Rename(rename) => {
+            // `use Trait as _` 只引入 trait 方法，不产生名称
+            if rename.rename == "_" {
+                return;
+            }
+            let mut source = prefix.clone();
+            if rename.ident != "self" {
+                source.push(rename.ident.to_string());
+            }
+            out.push((Some(rename.rename.unraw().to_string()), source));
+        }
+        UseTree::Glob(_) => out.push((None, prefix.clone())),
+        UseTree::Group(group) => {
+            for item in &group.items {
+                flatten_use_tree(item, prefix, out);
+            }
+        }
+    }
+}
+
+const OWNER_WRAPPERS: &[&str] = &["Result", "Option", "Box", "Rc", "Arc"];
+
+fn returns_owner(ty: &Type, owner: &str) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    if segment.ident == "Self" || segment.ident == owner {
+        return true;
+    }
+    if !OWNER_WRAPPERS.iter().any(|wrapper| segment.ident == wrapper) {
+        return false;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(inner)) => returns_owner(inner, owner),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// impl 目标类型的名称：路径取最后一段（Data<T> -> Data），其他类型按源码渲染（&'a T、[u8]）
+pub fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => match type_path.path.segments.last() {
+            Some(segment) => segment.ident.to_string(),
+            None => render_tokens(ty),
+        },
+        Type::Group(group) => type_name(&group.elem),
+        Type::Paren(paren) => type_name(&paren.elem),
+        _ => render_tokens(ty),
+    }
+}
+
+// 路径的各段（不含泛型参数），以 `::` 开头的路径首段为 "::"
+fn path_segments(path: &syn::Path) -> Vec<String> {
+    let leading = path.leading_colon.map(|_| "::".to_string());
+    leading.into_iter().chain(path.segments.iter().map(|segment| segment.ident.to_string())).collect()
+}
+
+// 类型路径的各段，带 qself 的路径和不是路径的类型返回 None
+fn type_segments(ty: &Type) -> Option<Vec<String>> {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => Some(path_segments(&type_path.path)),
+        Type::Group(group) => type_segments(&group.elem),
+        Type::Paren(paren) => type_segments(&paren.elem),
+        _ => None,
+    }
+}
+
+// 渲染泛型参数列表和 where 子句
+pub fn render_generics(generics: &Generics) -> (Vec<String>, Vec<String>) {
+    let params = generics.params.iter().map(render_tokens).collect();
+    let predicates = generics
+        .where_clause
+        .iter()
+        .flat_map(|clause| clause.predicates.iter().map(render_tokens))
+        .collect();
+    (params, predicates)
+}
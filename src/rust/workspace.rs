@@ -0,0 +1,240 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Cargo 编译目标的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Lib,
+    Bin,
+    Example,
+    Test,
+    Bench,
+    BuildScript,
+}
+
+impl TargetKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            TargetKind::Lib => "lib",
+            TargetKind::Bin => "bin",
+            TargetKind::Example => "example",
+            TargetKind::Test => "test",
+            TargetKind::Bench => "bench",
+            TargetKind::BuildScript => "build",
+        }
+    }
+}
+
+// 包中的一个编译目标，从 root 出发建立自己的模块树
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub package: String,
+    pub manifest: PathBuf,
+    pub kind: TargetKind,
+    pub name: String,
+    pub root: PathBuf,
+}
+
+// 工作区中的一个包
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub name: String,
+    pub manifest: PathBuf,
+    pub targets: Vec<Target>,
+}
+
+// 从目录下的 Cargo.toml 出发发现的所有包
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    pub packages: Vec<Package>,
+    // workspace.exclude 中排除的目录，其中的文件不参与分析
+    pub excluded: Vec<PathBuf>,
+}
+
+impl Workspace {
+    // dir 下没有 Cargo.toml 时返回 Ok(None)
+    pub fn discover(dir: &Path) -> Result<Option<Self>, String> {
+        let manifest = dir.join("Cargo.toml");
+        if !manifest.is_file() {
+            return Ok(None);
+        }
+        let value = read_manifest(&manifest)?;
+        let mut workspace = Workspace::default();
+        if value.contains_key("package") {
+            workspace.packages.push(load_package(&manifest, &value)?);
+        }
+        if let Some(table) = value.get("workspace").and_then(|v| v.as_table()) {
+            workspace.excluded = string_list(table.get("exclude")).iter().map(|path| dir.join(path)).collect();
+            for pattern in string_list(table.get("members")) {
+                for member in expand_member(dir, &pattern) {
+                    if workspace.excluded.iter().any(|excluded| member.starts_with(excluded)) {
+                        continue;
+                    }
+                    let member_manifest = member.join("Cargo.toml");
+                    if !member_manifest.is_file() || member_manifest == manifest {
+                        continue;
+                    }
+                    let member_value = read_manifest(&member_manifest)?;
+                    workspace.packages.push(load_package(&member_manifest, &member_value)?);
+                }
+            }
+        }
+        Ok(Some(workspace))
+    }
+
+    pub fn targets(&self) -> impl Iterator<Item = &Target> {
+        self.packages.iter().flat_map(|package| &package.targets)
+    }
+}
+
+fn read_manifest(manifest: &Path) -> Result<toml::Table, String> {
+    let content = fs::read_to_string(manifest).map_err(|e| format!("无法读取 {}: {}", manifest.display(), e))?;
+    toml::from_str(&content).map_err(|e| format!("无法解析 {}: {}", manifest.display(), e))
+}
+
+fn string_list(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|list| list.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+// 展开 members 中的路径，支持最后一级中的 `*`，例如 "crates/*"、"crates/tool-*"
+fn expand_member(dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let path = dir.join(pattern);
+    let Some(last) = path.file_name().and_then(|name| name.to_str()) else {
+        return vec![path];
+    };
+    let Some((prefix, suffix)) = last.split_once('*') else {
+        return vec![path];
+    };
+    let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut members: Vec<PathBuf> = fs::read_dir(&parent)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(prefix) && name.ends_with(suffix))
+        })
+        .collect();
+    members.sort();
+    members
+}
+
+// 读取包的各个编译目标：显式声明的 [lib]、[[bin]] 等，以及 Cargo 按目录约定自动发现的目标
+fn load_package(manifest: &Path, value: &toml::Table) -> Result<Package, String> {
+    let dir = manifest.parent().map(Path::to_path_buf).unwrap_or_default();
+    let package = value
+        .get("package")
+        .and_then(|v| v.as_table())
+        .ok_or_else(|| format!("{} 中没有 [package]", manifest.display()))?;
+    let name = package
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| format!("{} 中没有 package.name", manifest.display()))?
+        .to_string();
+    let auto = |key: &str| package.get(key).and_then(|v| v.as_bool()).unwrap_or(true);
+    let mut targets = Vec::new();
+    let mut push = |kind: TargetKind, target_name: String, root: PathBuf| {
+        if root.is_file() && !targets.iter().any(|t: &Target| t.kind == kind && t.root == root) {
+            targets.push(Target {
+                package: name.clone(),
+                manifest: manifest.to_path_buf(),
+                kind,
+                name: target_name,
+                root,
+            });
+        }
+    };
+
+    let lib = value.get("lib").and_then(|v| v.as_table());
+    let lib_name = lib
+        .and_then(|lib| lib.get("name"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| name.replace('-', "_"));
+    let lib_path = lib.and_then(|lib| lib.get("path")).and_then(|v| v.as_str()).unwrap_or("src/lib.rs");
+    push(TargetKind::Lib, lib_name, dir.join(lib_path));
+
+    let sections = [
+        (TargetKind::Bin, "bin", "autobins", "src/bin"),
+        (TargetKind::Example, "example", "autoexamples", "examples"),
+        (TargetKind::Test, "test", "autotests", "tests"),
+        (TargetKind::Bench, "bench", "autobenches", "benches"),
+    ];
+    for (kind, key, auto_key, default_dir) in sections {
+        for entry in value.get(key).and_then(|v| v.as_array()).into_iter().flatten() {
+            let Some(target_name) = entry.get("name").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let root = match entry.get("path").and_then(|v| v.as_str()) {
+                Some(path) => dir.join(path),
+                None if kind == TargetKind::Bin && target_name == name => dir.join("src/main.rs"),
+                None => conventional_root(&dir.join(default_dir), target_name),
+            };
+            push(kind, target_name.to_string(), root);
+        }
+        if !auto(auto_key) {
+            continue;
+        }
+        if kind == TargetKind::Bin {
+            push(kind, name.clone(), dir.join("src/main.rs"));
+        }
+        for (target_name, root) in discover_targets(&dir.join(default_dir)) {
+            push(kind, target_name, root);
+        }
+    }
+
+    // package.build 可以是路径、false，缺省时使用 build.rs
+    let build = match package.get("build") {
+        Some(toml::Value::String(path)) => Some(dir.join(path)),
+        Some(toml::Value::Boolean(false)) => None,
+        _ => Some(dir.join("build.rs")),
+    };
+    if let Some(root) = build {
+        push(TargetKind::BuildScript, "build-script-build".to_string(), root);
+    }
+
+    Ok(Package {
+        name,
+        manifest: manifest.to_path_buf(),
+        targets,
+    })
+}
+
+// 目标目录中名为 name 的目标的根文件：name.rs 或 name/main.rs
+fn conventional_root(dir: &Path, name: &str) -> PathBuf {
+    let file = dir.join(format!("{}.rs", name));
+    if file.is_file() {
+        file
+    } else {
+        dir.join(name).join("main.rs")
+    }
+}
+
+// 按 Cargo 的约定发现目录中的目标：每个 .rs 文件，以及含有 main.rs 的子目录
+fn discover_targets(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut targets: Vec<(String, PathBuf)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_stem()?.to_str()?.to_string();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "rs") {
+                Some((name, path))
+            } else if path.join("main.rs").is_file() {
+                Some((name, path.join("main.rs")))
+            } else {
+                None
+            }
+        })
+        .collect();
+    targets.sort();
+    targets
+}
@@ -1,75 +1,490 @@
-use std::env;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::{self, File as FsFile};
 use std::io::Write;
-use std::path::Path;
-use syn::{visit::Visit, File, parse_file, Item, ImplItem, TraitItem};
-use walkdir::WalkDir;
+use std::path::{Path, PathBuf};
 use anyhow::{Result, anyhow};
+use clap::{App, Arg};
 
-struct FunctionVisitor {
-    functions: Vec<String>,
+use code_parser::rust::coverage::DocCoverage;
+use code_parser::rust::filter::{parse_qualifiers, FnQualifier};
+use code_parser::rust::log::{self, at, write_coverage, write_feature_matrix, write_metrics, write_ranking};
+use code_parser::rust::metrics::{self, MetricKey};
+use code_parser::rust::model::{FileItems, FnItem, FnKind, ItemAttrs, PanicKind, TraitDef};
+use code_parser::rust::panic_audit;
+use code_parser::rust::project::{feature_matrix, Options, ProjectAnalysis};
+use code_parser::rust::unsafe_audit::{Baseline, BaselineDiff, UnsafeTotals};
+use code_parser::rust::visibility::Visibility;
+use code_parser::rust::workspace::TargetKind;
+
+// 在声明前加上可见性修饰符，例如 "pub(crate) fn helper()"
+fn with_vis(vis: &Visibility, text: String) -> String {
+    let vis = vis.as_str();
+    if vis.is_empty() {
+        text
+    } else {
+        format!("{} {}", vis, text)
+    }
+}
+
+// 泛型参数和 where 子句，例如 "<T: Clone> where T: Debug"
+fn with_generics(generics: &[String], where_predicates: &[String]) -> String {
+    let mut text = String::new();
+    if !generics.is_empty() {
+        text.push_str(&format!("<{}>", generics.join(", ")));
+    }
+    if !where_predicates.is_empty() {
+        text.push_str(&format!(" where {}", where_predicates.join(", ")));
+    }
+    text
+}
+
+// 函数签名后附带分类标记，例如 "pub fn new() -> Self [构造函数]"。
+// trait 及其实现中的方法在源码里不写可见性，show_vis 为 false
+fn format_fn(item: &FnItem, show_vis: bool) -> String {
+    let sig = if show_vis {
+        with_vis(&item.vis, item.sig.render())
+    } else {
+        item.sig.render()
+    };
+    let text = match item.kind {
+        FnKind::Constructor => format!("{} [构造函数]", sig),
+        FnKind::Method if item.is_static() => format!("{} [静态方法]", sig),
+        _ => sig,
+    };
+    format!(
+        "{}{}{}{}",
+        text,
+        expansion_tag(&item.expanded_from),
+        deprecation_tag(&item.attrs),
+        at(&item.location)
+    )
+}
+
+// 由宏展开生成的条目附带的标记，例如 " [由 create_function! 生成]"
+fn expansion_tag(expanded_from: &Option<String>) -> String {
+    match expanded_from {
+        Some(name) => format!(" [由 {}! 生成]", name),
+        None => String::new(),
+    }
+}
+
+// 已弃用条目附带的标记，具体的 since 和 note 写在属性行中
+fn deprecation_tag(attrs: &ItemAttrs) -> &'static str {
+    if attrs.deprecated.is_some() {
+        " [已弃用]"
+    } else {
+        ""
+    }
+}
+
+// 在条目下方写入它的属性，例如 "    #[derive(Debug, Clone)] #[non_exhaustive]"
+fn write_attrs(log_file: &mut FsFile, indent: &str, attrs: &ItemAttrs) -> Result<()> {
+    let attrs = attrs.render();
+    if !attrs.is_empty() {
+        writeln!(log_file, "{}{}", indent, attrs.join(" "))?;
+    }
+    Ok(())
+}
+
+// trait 头部，例如 "unsafe trait crate::Store<K>: Clone + Send where K: Hash"
+fn trait_header(def: &TraitDef) -> String {
+    let mut text = String::new();
+    if def.is_unsafe {
+        text.push_str("unsafe ");
+    }
+    if def.is_auto {
+        text.push_str("auto ");
+    }
+    text.push_str(&format!("trait {}", def.path));
+    if !def.generics.is_empty() {
+        text.push_str(&format!("<{}>", def.generics.join(", ")));
+    }
+    if !def.supertraits.is_empty() {
+        text.push_str(&format!(": {}", def.supertraits.join(" + ")));
+    }
+    if !def.where_predicates.is_empty() {
+        text.push_str(&format!(" where {}", def.where_predicates.join(", ")));
+    }
+    text
 }
 
-impl<'ast> Visit<'ast> for FunctionVisitor {
-    // 提取普通函数
-    fn visit_item(&mut self, node: &'ast Item) {
-        if let Item::Fn(item_fn) = node {
-            self.functions.push(item_fn.sig.ident.to_string());
+// 条目通过 pub use 重导出、能以定义处以外的路径访问时，列出所有公开路径，
+// 第一个为规范路径。公开路径以 crate 名称开头，与定义处路径比较时去掉首段
+fn write_public_paths(log_file: &mut FsFile, indent: &str, path: &str, public_paths: &[String]) -> Result<()> {
+    let at_definition = |public: &String| public.split_once("::").map(|(_, rest)| rest) == path.strip_prefix("crate::");
+    if public_paths.is_empty() || (public_paths.len() == 1 && at_definition(&public_paths[0])) {
+        return Ok(());
+    }
+    let mut paths: Vec<String> = public_paths.iter().map(String::clone).collect();
+    paths[0] = format!("{} (规范)", paths[0]);
+    writeln!(log_file, "{}公开路径: {}", indent, paths.join(", "))?;
+    Ok(())
+}
+
+// 按注释形式写入文档文本，例如 "      /// 返回连接数"
+fn write_docs(log_file: &mut FsFile, indent: &str, marker: &str, docs: &Option<String>) -> Result<()> {
+    for line in docs.iter().flat_map(|docs| docs.lines()) {
+        if line.is_empty() {
+            writeln!(log_file, "{}{}", indent, marker)?;
+        } else {
+            writeln!(log_file, "{}{} {}", indent, marker, line)?;
         }
-        syn::visit::visit_item(self, node);
     }
+    Ok(())
+}
 
-    // 提取impl块中的方法
-    fn visit_impl_item(&mut self, node: &'ast ImplItem) {
-        match node {
-            ImplItem::Fn(method) => {
-                self.functions.push(method.sig.ident.to_string());
+// 写入单个文件的分析结果：普通函数、类型及其字段和方法、trait 定义、trait 实现、类型别名、常量和静态变量
+fn write_file_items(log_file: &mut FsFile, items: &FileItems) -> Result<()> {
+    write_docs(log_file, "  ", "//!", &items.module_docs)?;
+    for func in &items.functions {
+        writeln!(log_file, "  - {}: {}", func.path, format_fn(func, true))?;
+        write_attrs(log_file, "      ", &func.attrs)?;
+        write_public_paths(log_file, "      ", &func.path, &func.public_paths)?;
+        write_docs(log_file, "      ", "///", &func.docs)?;
+    }
+    for item in &items.types {
+        match item.kind {
+            Some(kind) => {
+                let header = format!(
+                    "{} {}{}",
+                    kind.keyword(),
+                    item.path,
+                    with_generics(&item.generics, &item.where_predicates)
+                );
+                writeln!(
+                    log_file,
+                    "  {}:{}{}{}",
+                    with_vis(&item.vis, header),
+                    expansion_tag(&item.expanded_from),
+                    deprecation_tag(&item.attrs),
+                    at(&item.location)
+                )?;
+                write_attrs(log_file, "    ", &item.attrs)?;
+                write_public_paths(log_file, "    ", &item.path, &item.public_paths)?;
+                write_docs(log_file, "    ", "///", &item.docs)?;
             }
-            _ => {}
+            None => writeln!(log_file, "  impl {}:{}", item.path, at(&item.location))?,
+        }
+        for field in &item.fields {
+            let decl = with_vis(&field.vis, format!("{}: {}", field.name, field.ty));
+            writeln!(log_file, "    字段 {}{}{}", decl, deprecation_tag(&field.attrs), at(&field.location))?;
+            write_attrs(log_file, "      ", &field.attrs)?;
+            write_docs(log_file, "      ", "///", &field.docs)?;
+        }
+        for variant in &item.variants {
+            writeln!(
+                log_file,
+                "    变体 {}{}{}",
+                variant.render(),
+                deprecation_tag(&variant.attrs),
+                at(&variant.location)
+            )?;
+            write_attrs(log_file, "      ", &variant.attrs)?;
+            write_docs(log_file, "      ", "///", &variant.docs)?;
+        }
+        for method in &item.methods {
+            writeln!(log_file, "    - {}", format_fn(method, true))?;
+            write_attrs(log_file, "      ", &method.attrs)?;
+            write_docs(log_file, "      ", "///", &method.docs)?;
+        }
+    }
+    for def in &items.traits {
+        writeln!(
+            log_file,
+            "  {}:{}{}{}",
+            with_vis(&def.vis, trait_header(def)),
+            expansion_tag(&def.expanded_from),
+            deprecation_tag(&def.attrs),
+            at(&def.location)
+        )?;
+        write_attrs(log_file, "    ", &def.attrs)?;
+        write_public_paths(log_file, "    ", &def.path, &def.public_paths)?;
+        write_docs(log_file, "    ", "///", &def.docs)?;
+        for ty in &def.assoc_types {
+            writeln!(log_file, "    {}{}", ty.render(), at(&ty.location))?;
+            write_docs(log_file, "      ", "///", &ty.docs)?;
+        }
+        for constant in &def.assoc_consts {
+            writeln!(log_file, "    {}{}", constant.render(), at(&constant.location))?;
+            write_docs(log_file, "      ", "///", &constant.docs)?;
+        }
+        for method in &def.methods {
+            let marker = if method.provided { "默认实现" } else { "必需" };
+            writeln!(log_file, "    - {} [{}]", format_fn(method, false), marker)?;
+            write_attrs(log_file, "      ", &method.attrs)?;
+            write_docs(log_file, "      ", "///", &method.docs)?;
+        }
+        if !def.implementors.is_empty() {
+            writeln!(log_file, "    实现者: {}", def.implementors.join(", "))?;
         }
-        syn::visit::visit_impl_item(self, node);
     }
+    for imp in &items.trait_impls {
+        writeln!(log_file, "  {}:{}{}", imp.header(), expansion_tag(&imp.expanded_from), at(&imp.location))?;
+        for method in &imp.methods {
+            writeln!(log_file, "    - {}", format_fn(method, false))?;
+        }
+    }
+    for alias in &items.aliases {
+        let decl = format!(
+            "type {}{} = {}",
+            alias.path,
+            with_generics(&alias.generics, &alias.where_predicates),
+            alias.ty
+        );
+        writeln!(
+            log_file,
+            "  {}{}{}{}",
+            with_vis(&alias.vis, decl),
+            expansion_tag(&alias.expanded_from),
+            deprecation_tag(&alias.attrs),
+            at(&alias.location)
+        )?;
+        write_attrs(log_file, "    ", &alias.attrs)?;
+        write_public_paths(log_file, "    ", &alias.path, &alias.public_paths)?;
+        write_docs(log_file, "    ", "///", &alias.docs)?;
+    }
+    for value in &items.values {
+        let decl = format!("{} {}: {}", value.kind.keyword(), value.path, value.ty);
+        writeln!(
+            log_file,
+            "  {}{}{}{}",
+            with_vis(&value.vis, decl),
+            expansion_tag(&value.expanded_from),
+            deprecation_tag(&value.attrs),
+            at(&value.location)
+        )?;
+        write_attrs(log_file, "    ", &value.attrs)?;
+        write_public_paths(log_file, "    ", &value.path, &value.public_paths)?;
+        write_docs(log_file, "    ", "///", &value.docs)?;
+    }
+    Ok(())
+}
 
-    // 提取trait中的方法
-    fn visit_trait_item(&mut self, node: &'ast TraitItem) {
-        match node {
-            TraitItem::Fn(method) => {
-                self.functions.push(method.sig.ident.to_string());
-            }
-            _ => {}
+// 写入一个 crate 的 unsafe 审计结果：按文件列出每处 unsafe 代码，最后是合计
+fn write_unsafe_audit(log_file: &mut FsFile, root: &Path, files: &[FileItems], totals: &UnsafeTotals) -> Result<()> {
+    writeln!(log_file, "unsafe 审计 ({}):", root.display())?;
+    for items in files.iter().filter(|items| !items.unsafe_sites.is_empty()) {
+        writeln!(log_file, "  文件: {}", items.file.display())?;
+        for site in &items.unsafe_sites {
+            let comment = if site.has_safety_comment { "有 SAFETY 注释" } else { "缺少 SAFETY 注释" };
+            writeln!(
+                log_file,
+                "    {}{} 于 {} [{}]",
+                site.kind.keyword(),
+                at(&site.location),
+                site.enclosing,
+                comment
+            )?;
         }
-        syn::visit::visit_trait_item(self, node);
     }
+    writeln!(
+        log_file,
+        "  合计: {} 处 ({})，缺少 SAFETY 注释 {} 处",
+        totals.total(),
+        totals.render(),
+        totals.undocumented
+    )?;
+    Ok(())
 }
 
-fn process_file(path: &Path) -> Result<Vec<String>> {
-    let content = fs::read_to_string(path)?;
-    let syntax_tree: File = parse_file(&content)?;
-    let mut visitor = FunctionVisitor {
-        functions: Vec::new(),
-    };
-    visitor.visit_file(&syntax_tree);
-    Ok(visitor.functions)
+// 写入一个 crate 的 panic 审计结果：按函数列出可能 panic 的代码，最后是合计
+fn write_panic_audit(log_file: &mut FsFile, root: &Path, files: &[FileItems], module: Option<&str>) -> Result<usize> {
+    let grouped = panic_audit::by_function(files, module);
+    if grouped.is_empty() {
+        return Ok(0);
+    }
+    writeln!(log_file, "panic 审计 ({}):", root.display())?;
+    let mut by_kind: BTreeMap<PanicKind, usize> = BTreeMap::new();
+    for (function, sites) in &grouped {
+        writeln!(log_file, "  {} ({}):", function, sites[0].0.file.display())?;
+        for (_, site) in sites {
+            writeln!(log_file, "    {}{} {}", site.kind.keyword(), at(&site.location), site.code)?;
+            *by_kind.entry(site.kind).or_default() += 1;
+        }
+    }
+    let total: usize = by_kind.values().sum();
+    let counts: Vec<String> = by_kind.iter().map(|(kind, count)| format!("{} {}", kind.keyword(), count)).collect();
+    writeln!(log_file, "  合计: {} 处，涉及 {} 个函数 ({})", total, grouped.len(), counts.join(", "))?;
+    Ok(total)
+}
+
+// 写入与 unsafe 基线的差异
+fn write_baseline_diff(log_file: &mut FsFile, baseline: &Path, diff: &BaselineDiff) -> Result<()> {
+    writeln!(log_file, "与 unsafe 基线 {} 相比:", baseline.display())?;
+    if diff.added.is_empty() && diff.removed.is_empty() {
+        writeln!(log_file, "  没有变化")?;
+    }
+    for (marker, entries) in [("+", &diff.added), ("-", &diff.removed)] {
+        for entry in entries {
+            writeln!(
+                log_file,
+                "  {} {} 于 {} ({}:{}，crate {})",
+                marker, entry.kind, entry.enclosing, entry.file, entry.line, entry.root
+            )?;
+        }
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
-    // 获取命令行参数
-    let args: Vec<String> = env::args().collect();
+    let matches = App::new("rustAPI")
+        .about("提取 Rust 项目中的函数签名")
+        .arg(Arg::with_name("directory")
+            .help("要分析的目录路径，未提供时读取 src/toRead.txt"))
+        .arg(Arg::with_name("log")
+            .help("日志文件路径，默认为 code-parser 的输出目录（~/.parser/output）下的 fns_log"))
+        .arg(Arg::with_name("public-only")
+            .help("只输出下游 crate 可以访问的公开 API")
+            .long("public-only"))
+        .arg(Arg::with_name("strict")
+            .help("有文件无法解析时以非零状态退出")
+            .long("strict"))
+        .arg(Arg::with_name("doc-coverage")
+            .help("统计公开条目的文档覆盖率，列出缺少文档或缺少 Errors/Panics/Safety 小节的条目")
+            .long("doc-coverage"))
+        .arg(Arg::with_name("features")
+            .help("启用的 Cargo 特性，用逗号分隔，可多次指定")
+            .long("features")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1))
+        .arg(Arg::with_name("no-default-features")
+            .help("不启用 default 特性")
+            .long("no-default-features"))
+        .arg(Arg::with_name("cfg")
+            .help("视为成立的 cfg 选项，例如 unix 或 target_os=\"linux\"，可多次指定，未给出的条件按未知处理，test 和 doc 按不成立处理")
+            .long("cfg")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1))
+        .arg(Arg::with_name("feature-matrix")
+            .help("列出每个 Cargo 特性新增的条目")
+            .long("feature-matrix"))
+        .arg(Arg::with_name("call-graph")
+            .help("输出 crate 内部的调用图，写入日志文件旁的 call_graph.json / .dot / .mmd")
+            .long("call-graph")
+            .takes_value(true)
+            .possible_values(&["json", "dot", "mermaid"]))
+        .arg(Arg::with_name("trait-matrix")
+            .help("输出每个类型实现的 trait 和每个 trait 的实现者，写入日志文件旁的 trait_matrix.json / .md / .html")
+            .long("trait-matrix")
+            .takes_value(true)
+            .possible_values(&["json", "markdown", "html"]))
+        .arg(Arg::with_name("metrics")
+            .help("输出每个函数的圈复杂度、认知复杂度、嵌套深度、参数个数、行数和语句数，以及按文件和模块的汇总")
+            .long("metrics"))
+        .arg(Arg::with_name("sort-by")
+            .help("按某项度量列出最复杂的函数")
+            .long("sort-by")
+            .takes_value(true)
+            .possible_values(MetricKey::KEYWORDS))
+        .arg(Arg::with_name("top")
+            .help("--sort-by 列出的函数个数，默认为 10")
+            .long("top")
+            .takes_value(true))
+        .arg(Arg::with_name("audit")
+            .help("审计模式，代替条目清单写入日志：unsafe 列出所有 unsafe 代码及其 SAFETY 注释，\
+                   panic 按函数列出非测试代码中的 unwrap、expect、panic! 等和下标表达式")
+            .long("audit")
+            .takes_value(true)
+            .possible_values(&["unsafe", "panic"]))
+        .arg(Arg::with_name("module")
+            .help("panic 审计只报告该模块（含子模块）中的函数，例如 crate::net")
+            .long("module")
+            .takes_value(true)
+            .requires("audit"))
+        .arg(Arg::with_name("baseline")
+            .help("unsafe 审计的基线文件，有新增的 unsafe 代码时以非零状态退出")
+            .long("baseline")
+            .takes_value(true)
+            .requires("audit"))
+        .arg(Arg::with_name("update-baseline")
+            .help("把本次审计结果写入 --baseline 指定的文件，而不是与之比较")
+            .long("update-baseline")
+            .requires("baseline"))
+        .arg(Arg::with_name("format")
+            .help("输出格式，目前只支持 text")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["text"]))
+        .arg(Arg::with_name("max-depth")
+            .help("最大目录递归深度")
+            .long("max-depth")
+            .takes_value(true))
+        .arg(Arg::with_name("ignore-dir")
+            .help("要忽略的目录名，可多次指定")
+            .long("ignore-dir")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1))
+        .arg(Arg::with_name("only")
+            .help("只保留具有指定限定符的函数，用逗号分隔，满足任一即可 (const/async/unsafe/extern/static/constructor)")
+            .long("only")
+            .takes_value(true))
+        .get_matches();
+
+    let public_only = matches.is_present("public-only");
+    let strict = matches.is_present("strict");
+    let doc_coverage = matches.is_present("doc-coverage");
+    let features: Vec<String> = matches
+        .values_of("features")
+        .into_iter()
+        .flatten()
+        .flat_map(|list| list.split([',', ' ']))
+        .filter(|feature| !feature.is_empty())
+        .map(str::to_string)
+        .collect();
+    let default_features = !matches.is_present("no-default-features");
+    let cfgs: Vec<String> = matches.values_of("cfg").into_iter().flatten().map(str::to_string).collect();
+    let feature_matrix_mode = matches.is_present("feature-matrix");
+    let call_graph_format = matches.value_of("call-graph");
+    let trait_matrix_format = matches.value_of("trait-matrix");
+    let metrics_mode = matches.is_present("metrics");
+    // 只给出 --top 时按圈复杂度排序
+    let sort_key = match (matches.value_of("sort-by"), matches.is_present("top")) {
+        (Some(key), _) => MetricKey::from_keyword(key),
+        (None, true) => Some(MetricKey::Complexity),
+        (None, false) => None,
+    };
+    let top = match matches.value_of("top") {
+        Some(top) => top.parse().map_err(|_| anyhow!("无效的函数个数: {}", top))?,
+        None => 10,
+    };
+    let audit_unsafe = matches.value_of("audit") == Some("unsafe");
+    let audit_panic = matches.value_of("audit") == Some("panic");
+    // 模块路径可以省略开头的 crate::
+    let audit_module = matches.value_of("module").map(|module| {
+        if module == "crate" || module.starts_with("crate::") {
+            module.to_string()
+        } else {
+            format!("crate::{}", module)
+        }
+    });
+    let baseline_path = matches.value_of("baseline").map(Path::new);
+    let update_baseline = matches.is_present("update-baseline");
+    let ignore_dirs: Vec<String> = matches.values_of("ignore-dir").into_iter().flatten().map(str::to_string).collect();
+    let max_depth = match matches.value_of("max-depth") {
+        Some(depth) => Some(depth.parse().map_err(|_| anyhow!("无效的最大深度: {}", depth))?),
+        None => None,
+    };
+    let qualifiers: Vec<FnQualifier> = match matches.value_of("only") {
+        Some(list) => parse_qualifiers(list).map_err(|e| anyhow!(e))?,
+        None => Vec::new(),
+    };
 
     // 获取目录路径
-    let dir = if args.len() > 1 {
+    let dir = match matches.value_of("directory") {
         // 使用命令行传入的第一个参数作为目录路径
-        args[1].clone()
-    } else {
+        Some(dir) => dir.to_string(),
         // 如果没有提供命令行参数，尝试从toRead.txt读取
-        match fs::read_to_string("src/toRead.txt") {
+        None => match fs::read_to_string("src/toRead.txt") {
             Ok(content) => content.trim().to_string(),
             Err(_) => {
                 // 如果文件不存在，使用当前目录
                 println!("未提供目录参数且无法读取toRead.txt，将使用当前目录");
                 ".".to_string()
             }
-        }
+        },
     };
 
     println!("读取到目录路径: {}", dir);
@@ -84,32 +499,199 @@ fn main() -> Result<()> {
     }
 
     // 构建日志文件路径
-    let log_path = if args.len() > 2 {
+    let log_path = match matches.value_of("log") {
         // 如果提供了第二个参数，将其作为输出文件路径
-        args[2].clone()
-    } else {
-        // 否则在目标目录下创建fns_log文件
-        dir_path.join("fns_log").to_string_lossy().to_string()
+        Some(log) => log.to_string(),
+        // 否则与 code-parser 一样写入输出目录，不写入分析的目录
+        None => {
+            let output = log::configured_output_dir();
+            fs::create_dir_all(&output)?;
+            output.join("fns_log").to_string_lossy().to_string()
+        }
     };
 
     println!("日志文件将写入: {}", log_path);
     // 创建或清空日志文件
     let mut log_file = FsFile::create(&log_path)?;
 
-    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
-        if entry.path().extension().map_or(false, |ext| ext == "rs") {
-            let functions = process_file(entry.path())?;
-            if !functions.is_empty() {
-                // 写入文件路径
-                writeln!(log_file, "文件: {}", entry.path().display())?;
-                // 写入函数名称
-                for func in functions {
-                    writeln!(log_file, "  - {}", func)?;
-                }
+    let options = Options {
+        ignore_dirs,
+        max_depth,
+        features,
+        no_default_features: !default_features,
+        cfgs,
+        public_only,
+    };
+    let mut analysis = ProjectAnalysis::analyze(dir_path, &options);
+    if let Some(workspace) = &analysis.workspace {
+        println!("发现 {} 个包，{} 个编译目标", workspace.packages.len(), workspace.targets().count());
+    }
+    for c in &analysis.crates {
+        if let Some(enabled) = &c.enabled_features {
+            println!("启用的特性 ({}): {}", c.root.display(), enabled.join(", "));
+        }
+    }
+    for warning in &analysis.warnings {
+        println!("警告: {}", warning);
+    }
+    let diagnostics = &analysis.diagnostics;
+    // 按规范化路径计数不同的文件，同一文件可以属于多个编译目标
+    let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let parsed_files: BTreeSet<PathBuf> =
+        analysis.crates.iter().flat_map(|c| &c.files).map(|items| canonical(&items.file)).collect();
+    let failed_files: BTreeSet<PathBuf> = diagnostics.iter().map(|diagnostic| canonical(&diagnostic.file)).collect();
+    let crates = &mut analysis.crates;
+
+    // 调用图和 trait 实现矩阵写在日志文件旁边，在按 --public-only 等条件筛选之前计算
+    let log_dir = Path::new(&log_path).parent().unwrap_or(Path::new("."));
+    if let Some(format) = call_graph_format {
+        println!("{}", log::write_call_graph(log_dir, crates, format)?);
+    }
+    if let Some(format) = trait_matrix_format {
+        println!("{}", log::write_trait_matrix(log_dir, crates, format)?);
+    }
+
+    // 文档覆盖率按 crate 统计，在按 --public-only 等条件筛选之前计算
+    let coverages: Vec<(PathBuf, DocCoverage)> = if doc_coverage {
+        crates
+            .iter()
+            .map(|c| (c.root.clone(), DocCoverage::compute(&c.files)))
+            .filter(|(_, coverage)| coverage.total() > 0)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // 审计模式下日志中只有审计结果
+    let mut baseline_diff = None;
+    if audit_unsafe {
+        for c in crates.iter() {
+            let totals = UnsafeTotals::compute(&c.files);
+            if totals.total() == 0 {
+                continue;
+            }
+            write_unsafe_audit(&mut log_file, &c.root, &c.files, &totals)?;
+            println!(
+                "unsafe 审计 {}: {} 处，缺少 SAFETY 注释 {} 处",
+                c.root.display(),
+                totals.total(),
+                totals.undocumented
+            );
+        }
+        if let Some(path) = baseline_path {
+            let current = Baseline::from_crates(
+                dir_path,
+                crates.iter().map(|c| (c.root.as_path(), c.files.as_slice())),
+            );
+            if update_baseline {
+                current.save(path).map_err(|e| anyhow!(e))?;
+                println!("unsafe 基线已写入到 {}（{} 处）", path.display(), current.entries.len());
+            } else {
+                let diff = Baseline::load(path).map_err(|e| anyhow!(e))?.diff(&current);
+                write_baseline_diff(&mut log_file, path, &diff)?;
+                println!("与 unsafe 基线相比: 新增 {} 处，消失 {} 处", diff.added.len(), diff.removed.len());
+                baseline_diff = Some(diff);
+            }
+        }
+    }
+
+    if audit_panic {
+        for c in crates.iter() {
+            // 测试和基准测试目标整体属于测试代码
+            if c.target.as_ref().is_some_and(|t| matches!(t.kind, TargetKind::Test | TargetKind::Bench)) {
+                continue;
+            }
+            let total = write_panic_audit(&mut log_file, &c.root, &c.files, audit_module.as_deref())?;
+            if total > 0 {
+                println!("panic 审计 {}: {} 处", c.root.display(), total);
             }
         }
     }
 
+    for c in crates.iter_mut().filter(|_| !audit_unsafe && !audit_panic) {
+        if let Some(target) = &c.target {
+            writeln!(
+                log_file,
+                "目标 {} {} (包 {}): {}",
+                target.kind.keyword(),
+                target.name,
+                target.package,
+                target.root.display()
+            )?;
+        }
+        for items in c.files.iter_mut() {
+            if public_only {
+                items.retain_exported();
+            }
+            if !qualifiers.is_empty() {
+                items.retain_fns(|f| qualifiers.iter().any(|q| q.matches(f)));
+            }
+            if items.is_empty() {
+                continue;
+            }
+            // 写入文件路径及其模块路径
+            if items.orphaned {
+                writeln!(log_file, "文件: {} (孤立文件，未被任何 crate 根引用)", items.file.display())?;
+            } else {
+                writeln!(log_file, "文件: {} (模块 {})", items.file.display(), items.module)?;
+            }
+            write_file_items(&mut log_file, items)?;
+        }
+    }
+
+    // 度量按 --public-only、--only 筛选后的函数计算
+    if metrics_mode && !audit_unsafe && !audit_panic {
+        for c in crates.iter() {
+            if c.files.iter().all(|items| metrics::measured_fns(items).next().is_none()) {
+                continue;
+            }
+            write_metrics(&mut log_file, &c.root, &c.files)?;
+        }
+    }
+    if let Some(key) = sort_key.filter(|_| !audit_unsafe && !audit_panic) {
+        write_ranking(&mut log_file, crates, key, top)?;
+        println!("复杂度排名已写入日志（按{}，前 {} 个）", key.label(), top);
+    }
+
+    if feature_matrix_mode {
+        for c in crates.iter() {
+            let Some(table) = &c.manifest else {
+                continue;
+            };
+            let matrix = feature_matrix(&c.root, table, &options.cfgs);
+            write_feature_matrix(&mut log_file, &c.root, &matrix)?;
+            println!("特性矩阵 {}: {} 个特性", c.root.display(), matrix.len());
+        }
+    }
+
+    for (root, coverage) in &coverages {
+        write_coverage(&mut log_file, root, coverage)?;
+        println!(
+            "文档覆盖率 {}: {}/{} ({:.1}%)",
+            root.display(),
+            coverage.documented(),
+            coverage.total(),
+            coverage.percent()
+        );
+    }
+
+    if !diagnostics.is_empty() {
+        writeln!(log_file, "诊断信息:")?;
+        for diagnostic in diagnostics {
+            writeln!(log_file, "  {}", diagnostic)?;
+        }
+    }
+
     println!("函数列表已写入到 {}", log_path);
+    println!("共分析 {} 个文件，{} 个文件无法解析", parsed_files.len(), failed_files.len());
+    for diagnostic in diagnostics {
+        println!("  {}", diagnostic);
+    }
+    if strict && !diagnostics.is_empty() {
+        return Err(anyhow!("{} 个文件无法解析（--strict）", failed_files.len()));
+    }
+    if let Some(diff) = baseline_diff.filter(|diff| !diff.added.is_empty()) {
+        return Err(anyhow!("与基线相比新增了 {} 处 unsafe 代码", diff.added.len()));
+    }
     Ok(())
-}
\ No newline at end of file
+}
@@ -2,6 +2,11 @@ use std::fs;
 use std::io::Write;
 use serde_json::Value;
 use std::env;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// 同一进程中的测试并行运行，每个 TestDir 使用各自的目录
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
 pub struct TestDir {
     pub path: std::path::PathBuf,
@@ -9,7 +14,8 @@ pub struct TestDir {
 
 impl TestDir {
     pub fn new() -> Self {
-        let temp_dir = env::temp_dir().join("code_parser_test");
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        let temp_dir = env::temp_dir().join(format!("code_parser_test_{}_{}", process::id(), id));
         if temp_dir.exists() {
             fs::remove_dir_all(&temp_dir).unwrap_or_default();
         }
@@ -31,6 +37,12 @@ impl TestDir {
     }
 }
 
+impl Default for TestDir {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Drop for TestDir {
     fn drop(&mut self) {
         if self.path.exists() {
@@ -0,0 +1,192 @@
+use code_parser::test_utils::{TestDir, assert_json_eq};
+use code_parser::{Analyzer, FileReport, Options, ProjectReport, Registry};
+use std::path::Path;
+
+// 工具链总是不可用的分析器
+struct UnavailableAnalyzer;
+
+impl Analyzer for UnavailableAnalyzer {
+    fn name(&self) -> &str {
+        "kotlin"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["kt"]
+    }
+
+    fn check_available(&self) -> anyhow::Result<()> {
+        anyhow::bail!("未找到 kotlinc")
+    }
+
+    fn analyze(&self, _directory: &Path, _options: &Options) -> anyhow::Result<ProjectReport> {
+        unreachable!()
+    }
+}
+
+// 把目录中每个 .txt 文件记为一个没有函数的文件
+struct TextAnalyzer;
+
+impl Analyzer for TextAnalyzer {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["txt"]
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["txt"]
+    }
+
+    fn check_available(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn analyze(&self, directory: &Path, _options: &Options) -> anyhow::Result<ProjectReport> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(directory)? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            if name.ends_with(".txt") {
+                files.push(FileReport { path: name, language: None, functions: Vec::new(), classes: Vec::new() });
+            }
+        }
+        Ok(ProjectReport::new(files))
+    }
+}
+
+#[test]
+fn test_builtin_registry() {
+    let registry = Registry::builtin();
+    assert_eq!(
+        registry.language_names(),
+        ["rust", "python", "javascript", "js", "typescript", "ts", "java", "c", "cpp", "c++"]
+    );
+    assert_eq!(registry.get("js").unwrap().name(), "javascript");
+    assert_eq!(registry.get("c++").unwrap().name(), "cpp");
+    assert_eq!(registry.get("rust").unwrap().extensions(), ["rs"]);
+    assert!(registry.get("ruby").is_none());
+    assert!(registry.get("rust").unwrap().check_available().is_ok());
+}
+
+#[test]
+fn test_register_analyzer() {
+    let mut registry = Registry::builtin();
+    registry.register(Box::new(TextAnalyzer)).unwrap();
+    assert!(registry.language_names().ends_with(&["text", "txt"]));
+
+    let test_dir = TestDir::new();
+    test_dir.create_file("notes.txt", "");
+    let report = registry.get("txt").unwrap().analyze(&test_dir.path, &Options::default()).unwrap();
+    let expected = r#"{
+  "schema_version": "1.3",
+  "files": [
+    {"path": "notes.txt", "functions": [], "classes": []}
+  ]
+}"#;
+    assert_json_eq(&report.to_json().unwrap(), expected);
+
+    // 名称或别名已被占用时不能注册
+    let error = registry.register(Box::new(TextAnalyzer)).unwrap_err();
+    assert!(error.to_string().contains("text"));
+}
+
+#[test]
+fn test_detect_languages() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_dir("tools");
+    test_dir.create_dir("bin");
+    test_dir.create_dir("web");
+    test_dir.create_dir("target");
+    test_dir.create_file("src/lib.rs", "");
+    test_dir.create_file("tools/gen.py", "");
+    test_dir.create_file("bin/run", "#!/usr/bin/env python3\nprint('run')\n");
+    test_dir.create_file("bin/build.sh", "#!/bin/sh\n");
+    test_dir.create_file("web/app.ts", "");
+    test_dir.create_file("README.md", "");
+    test_dir.create_file("target/generated.rs", "");
+
+    let options = Options {
+        ignore_dirs: vec!["target".to_string()],
+        ..Options::default()
+    };
+    let detected = Registry::builtin().detect(&test_dir.path, &options);
+    let detected: Vec<(&str, Vec<&str>)> = detected
+        .iter()
+        .map(|(language, paths)| (language.as_str(), paths.iter().map(String::as_str).collect()))
+        .collect();
+    assert_eq!(
+        detected,
+        [
+            ("javascript", vec!["web/app.ts"]),
+            ("python", vec!["bin/run", "tools/gen.py"]),
+            ("rust", vec!["src/lib.rs"]),
+        ]
+    );
+}
+
+#[test]
+fn test_analyze_mixed_languages() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_dir("bin");
+    test_dir.create_file("src/lib.rs", "pub fn run() {}\n");
+    test_dir.create_file("bin/tool", "#!/usr/bin/env python3\ndef main():\n    pass\n");
+    test_dir.create_file("App.kt", "fun main() {}\n");
+
+    let mut registry = Registry::builtin();
+    registry.register(Box::new(UnavailableAnalyzer)).unwrap();
+    let mut report = registry.analyze_all(&test_dir.path, &Options::default(), &[]).unwrap();
+    // Rust 的详细报告合并在 details 中
+    let details = report.details.rust.take().unwrap();
+    assert_eq!(details.files().next().unwrap().functions[0].signature, "fn run()");
+
+    // 工具链不可用的语言记为 skipped，其他语言的结果照常合并
+    let expected = r#"{
+  "schema_version": "1.3",
+  "files": [
+    {
+      "path": "src/lib.rs",
+      "language": "rust",
+      "functions": [
+        {
+          "name": "run",
+          "type": "function",
+          "async": false,
+          "location": {
+            "start_line": 1, "start_column": 1, "end_line": 1, "end_column": 16, "byte_start": 0, "byte_end": 15
+          }
+        }
+      ],
+      "classes": []
+    },
+    {
+      "path": "bin/tool",
+      "language": "python",
+      "functions": [{"name": "main", "type": "function", "async": false}],
+      "classes": []
+    }
+  ],
+  "languages": [
+    {"language": "rust", "status": "analyzed", "detected_files": 1, "files": 1, "functions": 1, "classes": 0},
+    {"language": "python", "status": "analyzed", "detected_files": 1, "files": 1, "functions": 1, "classes": 0},
+    {
+      "language": "kotlin",
+      "status": "skipped",
+      "detected_files": 1,
+      "files": 0,
+      "functions": 0,
+      "classes": 0,
+      "reason": "未找到 kotlinc"
+    }
+  ]
+}"#;
+    assert_json_eq(&report.to_json().unwrap(), expected);
+
+    // 只分析指定的语言
+    assert!(registry.analyze_all(&test_dir.path, &Options::default(), &["py"]).is_err());
+    let report = registry.analyze_all(&test_dir.path, &Options::default(), &["python"]).unwrap();
+    assert_eq!(report.languages.len(), 1);
+    assert_eq!(report.files[0].path, "bin/tool");
+}
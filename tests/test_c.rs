@@ -121,7 +121,7 @@ int subtract(int a, int b) {
         .arg("-c")
         .arg("-Wall")
         .arg("-Wextra")
-        .arg(&test_dir.path.join("test.c"))
+        .arg(test_dir.path.join("test.c"))
         .output()
         .unwrap();
 
@@ -201,7 +201,7 @@ void process_array(int* arr, size_t size, Callback callback) {
         .arg("-c")
         .arg("-Wall")
         .arg("-Wextra")
-        .arg(&test_dir.path.join("pointer_test.c"))
+        .arg(test_dir.path.join("pointer_test.c"))
         .output()
         .unwrap();
 
@@ -272,7 +272,7 @@ int main(void) {
         .arg("-c")
         .arg("-Wall")
         .arg("-Wextra")
-        .arg(&test_dir.path.join("macro_test.c"))
+        .arg(test_dir.path.join("macro_test.c"))
         .output()
         .unwrap();
 
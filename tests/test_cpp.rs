@@ -143,7 +143,7 @@ int main() {
         .arg("-c")
         .arg("-Wall")
         .arg("-Wextra")
-        .arg(&test_dir.path.join("test.cpp"))
+        .arg(test_dir.path.join("test.cpp"))
         .output()
         .unwrap();
 
@@ -220,7 +220,7 @@ int main() {
         .arg("-c")
         .arg("-Wall")
         .arg("-Wextra")
-        .arg(&test_dir.path.join("template_test.cpp"))
+        .arg(test_dir.path.join("template_test.cpp"))
         .output()
         .unwrap();
 
@@ -297,7 +297,7 @@ int main() {
         .arg("-c")
         .arg("-Wall")
         .arg("-Wextra")
-        .arg(&test_dir.path.join("inheritance_test.cpp"))
+        .arg(test_dir.path.join("inheritance_test.cpp"))
         .output()
         .unwrap();
 
@@ -1,5 +1,4 @@
 use code_parser::test_utils::{TestDir, assert_json_eq};
-use std::process::Command;
 
 fn mock_parser_output(functions: Vec<(&str, bool)>, classes: Vec<&str>) -> String {
     let functions_json = functions
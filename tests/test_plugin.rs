@@ -0,0 +1,96 @@
+use code_parser::test_utils::{TestDir, assert_json_eq};
+use code_parser::{Analyzer, Options, PluginAnalyzer, PluginConfig};
+
+// 用 Python 写的 Kotlin 插件：每个 .kt 文件中的 fun 记为函数，无法读取的文件报告诊断
+const KOTLIN_PLUGIN: &str = r#"
+import json, os, re, sys
+
+def send(message):
+    print(json.dumps(message), flush=True)
+
+for line in sys.stdin:
+    request = json.loads(line)
+    if request["type"] == "handshake":
+        send({"type": "handshake", "protocol_version": PROTOCOL_VERSION, "capabilities": []})
+    elif request["type"] == "analyze":
+        directory = request["directory"]
+        for root, dirs, names in os.walk(directory):
+            dirs.sort()
+            for name in sorted(names):
+                path = os.path.join(root, name)
+                if name.endswith(".kt"):
+                    functions = [{"name": f, "type": "function", "async": False}
+                                 for f in re.findall(r"fun (\w+)", open(path).read())]
+                    send({"type": "file", "file": {"path": path, "functions": functions, "classes": []}})
+                elif name.endswith(".kts"):
+                    send({"type": "diagnostic", "diagnostic": {"file": path, "message": "不支持脚本"}})
+        send({"type": "done"})
+"#;
+
+fn kotlin_plugin(test_dir: &TestDir, protocol_version: u32) -> PluginAnalyzer {
+    let script = KOTLIN_PLUGIN.replace("PROTOCOL_VERSION", &protocol_version.to_string());
+    test_dir.create_file("plugin.py", &script);
+    PluginAnalyzer::new(PluginConfig {
+        name: "kotlin".to_string(),
+        aliases: vec!["kt".to_string()],
+        extensions: vec!["kt".to_string()],
+        interpreters: vec!["kotlin".to_string()],
+        command: "python3".to_string(),
+        args: vec![test_dir.path.join("plugin.py").to_string_lossy().into_owned()],
+    })
+}
+
+#[test]
+fn test_plugin_analyze() {
+    let test_dir = TestDir::new();
+    let plugin = kotlin_plugin(&test_dir, 1);
+    test_dir.create_dir("src");
+    test_dir.create_dir("build");
+    test_dir.create_file("src/Main.kt", "fun main() {}\nfun helper(x: Int) = x\n");
+    test_dir.create_file("src/build.kts", "");
+    test_dir.create_file("build/Generated.kt", "fun generated() {}\n");
+
+    assert!(plugin.check_available().is_ok());
+    let options = Options {
+        ignore_dirs: vec!["build".to_string()],
+        ..Options::default()
+    };
+    let report = plugin.analyze(&test_dir.path, &options).unwrap();
+
+    // 插件没有声明 ignore_dirs 能力，build 目录中的文件由本工具过滤
+    let expected = r#"{
+  "schema_version": "1.3",
+  "files": [
+    {
+      "path": "src/Main.kt",
+      "functions": [
+        {"name": "main", "type": "function", "async": false},
+        {"name": "helper", "type": "function", "async": false}
+      ],
+      "classes": []
+    }
+  ],
+  "diagnostics": [
+    {"file": "src/build.kts", "line": null, "column": null, "message": "不支持脚本"}
+  ]
+}"#;
+    assert_json_eq(&report.to_json().unwrap(), expected);
+}
+
+#[test]
+fn test_plugin_protocol_errors() {
+    let test_dir = TestDir::new();
+    let plugin = kotlin_plugin(&test_dir, 2);
+    let error = plugin.check_available().unwrap_err().to_string();
+    assert!(error.contains("协议版本 2"), "{}", error);
+
+    let missing = PluginAnalyzer::new(PluginConfig {
+        name: "missing".to_string(),
+        aliases: Vec::new(),
+        extensions: Vec::new(),
+        interpreters: Vec::new(),
+        command: "code-parser-missing-plugin".to_string(),
+        args: Vec::new(),
+    });
+    assert!(missing.check_available().is_err());
+}
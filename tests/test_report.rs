@@ -0,0 +1,391 @@
+use code_parser::rust::log;
+use code_parser::rust::metrics::{self, MetricKey};
+use code_parser::test_utils::{TestDir, assert_json_eq};
+use code_parser::{json_schema, Options, ProjectReport};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_schema_file_is_up_to_date() {
+    // schema/report.schema.json 由 `cargo run -- schema` 生成
+    let committed = fs::read_to_string("schema/report.schema.json").unwrap();
+    let generated = serde_json::to_string_pretty(&json_schema()).unwrap();
+    assert_json_eq(&committed, &generated);
+}
+
+#[test]
+fn test_normalize_files_shape() {
+    // C/C++ 分析器的输出：函数没有 type 字段，带有额外字段，路径为绝对路径
+    let output = r#"{
+  "files": [
+    {
+      "path": "/project/src/main.cpp",
+      "functions": [
+        {"name": "main", "return_type": "int", "parameters": ""}
+      ],
+      "classes": [
+        {
+          "name": "Shape",
+          "is_template": false,
+          "base_classes": [],
+          "methods": [
+            {"name": "area", "return_type": "double", "is_virtual": true, "is_const": true}
+          ]
+        }
+      ]
+    }
+  ]
+}"#;
+    let value = serde_json::from_str(output).unwrap();
+    let report = ProjectReport::normalize(&value, Path::new("/project")).unwrap();
+
+    let expected = r#"{
+  "schema_version": "1.3",
+  "files": [
+    {
+      "path": "src/main.cpp",
+      "functions": [
+        {"name": "main", "type": "function", "async": false}
+      ],
+      "classes": [
+        {
+          "name": "Shape",
+          "methods": [
+            {"name": "area", "type": "method", "static": false, "async": false}
+          ]
+        }
+      ]
+    }
+  ]
+}"#;
+    assert_json_eq(&report.to_json().unwrap(), expected);
+}
+
+#[test]
+fn test_normalize_java_classes_shape() {
+    let output = r#"{
+  "classes": [
+    {"name": "Main", "path": "/project/Main.java", "methods": [{"name": "main", "returnType": "void"}], "fields": []},
+    {"name": "Helper", "path": "/project/Main.java", "methods": [], "fields": []},
+    {"name": "Util", "path": "/project/util/Util.java", "methods": [{"name": "run", "returnType": "int"}], "fields": []}
+  ]
+}"#;
+    let value = serde_json::from_str(output).unwrap();
+    let report = ProjectReport::normalize(&value, Path::new("/project")).unwrap();
+
+    let expected = r#"{
+  "schema_version": "1.3",
+  "files": [
+    {
+      "path": "Main.java",
+      "functions": [],
+      "classes": [
+        {"name": "Main", "methods": [{"name": "main", "type": "method", "static": false, "async": false}]},
+        {"name": "Helper", "methods": []}
+      ]
+    },
+    {
+      "path": "util/Util.java",
+      "functions": [],
+      "classes": [
+        {"name": "Util", "methods": [{"name": "run", "type": "method", "static": false, "async": false}]}
+      ]
+    }
+  ]
+}"#;
+    assert_json_eq(&report.to_json().unwrap(), expected);
+}
+
+#[test]
+fn test_normalize_rejects_invalid_output() {
+    let cases = [
+        (r#"{"schema_version": "2.0", "files": []}"#, "不支持的报告版本"),
+        (r#"{"functions": []}"#, "既没有 files 也没有 classes"),
+        (r#"{"files": [{"path": "a.js", "functions": [{"type": "function"}]}]}"#, "files[0].functions[0] 缺少 name"),
+        (r#"{"files": [{"path": "a.js", "functions": [{"name": "f", "type": "lambda"}]}]}"#, "不支持 lambda"),
+        (r#"{"files": [{"path": "a.js", "classes": {}}]}"#, "files[0].classes 应为数组"),
+    ];
+    for (output, message) in cases {
+        let value = serde_json::from_str(output).unwrap();
+        let error = ProjectReport::normalize(&value, Path::new(".")).unwrap_err().to_string();
+        assert!(error.contains(message), "{} 的错误信息为 {}", output, error);
+    }
+}
+
+#[test]
+fn test_analyze_library_api() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file("src/lib.rs", r#"
+mod inner;
+
+pub use inner::Config;
+
+pub fn load() -> Config {
+    Config::default()
+}
+
+fn helper() {}
+"#);
+    test_dir.create_file("src/inner.rs", r#"
+#[derive(Default)]
+pub struct Config {
+    pub verbose: bool,
+}
+
+impl Config {
+    pub async fn refresh(&mut self) {}
+}
+"#);
+
+    let options = Options {
+        public_only: true,
+        ..Options::default()
+    };
+    let mut report = code_parser::analyze(&test_dir.path, &options).unwrap();
+    let details = report.details.rust.take().unwrap();
+    assert_eq!(details.files().count(), 2);
+
+    // 位置的行号、列号和字节偏移，load 从第 6 行开头的第 37 个字节开始
+    let expected = r#"{
+  "schema_version": "1.3",
+  "files": [
+    {
+      "path": "src/lib.rs",
+      "functions": [
+        {
+          "name": "load",
+          "type": "function",
+          "async": false,
+          "location": {
+            "start_line": 6, "start_column": 1, "end_line": 8, "end_column": 2, "byte_start": 37, "byte_end": 86
+          }
+        }
+      ],
+      "classes": []
+    },
+    {
+      "path": "src/inner.rs",
+      "functions": [],
+      "classes": [
+        {
+          "name": "Config",
+          "methods": [
+            {
+              "name": "refresh",
+              "type": "method",
+              "static": false,
+              "async": true,
+              "location": {
+                "start_line": 8, "start_column": 5, "end_line": 8, "end_column": 39, "byte_start": 84, "byte_end": 118
+              }
+            }
+          ]
+        }
+      ]
+    }
+  ]
+}"#;
+    assert_json_eq(&report.to_json().unwrap(), expected);
+
+    let detailed = code_parser::analyze_rust(&test_dir.path, &options).unwrap();
+    let load = &detailed.files().next().unwrap().functions[0];
+    assert_eq!(load.signature, "fn load() -> Config");
+    // 详细报告中的位置与通用格式相同
+    assert_eq!(Some(load.location), report.files[0].functions[0].location);
+    let refresh = &detailed.files().nth(1).unwrap().types[0].methods[0];
+    assert_eq!(Some(refresh.location), report.files[1].classes[0].methods[0].location);
+    assert_eq!(refresh.location.render(), "8:5-8:39 (84..118)");
+    assert!(code_parser::analyze(test_dir.path.join("missing"), &options).is_err());
+}
+
+#[test]
+fn test_rank_functions() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file("src/lib.rs", r#"
+pub fn simple() {}
+
+pub fn branchy(x: u8) -> u8 {
+    if x > 1 {
+        if x > 2 { 3 } else { 2 }
+    } else {
+        1
+    }
+}
+
+pub fn single(x: bool) -> u8 {
+    if x { 1 } else { 0 }
+}
+
+mod parse;
+"#);
+    test_dir.create_file("src/parse.rs", r#"
+pub fn scan(input: &str, limit: usize, strict: bool) -> usize {
+    let mut count = 0;
+    for c in input.chars() {
+        while count < limit {
+            if strict && c == ' ' {
+                count += 1;
+            }
+            break;
+        }
+    }
+    count
+}
+"#);
+
+    let details = code_parser::analyze_rust(&test_dir.path, &Options::default()).unwrap();
+    let mut report = ProjectReport::new(Vec::new());
+    report.ranking = Some(details.ranking(MetricKey::Complexity, 2));
+    let expected = r#"{
+  "schema_version": "1.3",
+  "files": [],
+  "ranking": {
+    "metric": "complexity",
+    "functions": [
+      {"path": "crate::parse::scan", "file": "src/parse.rs", "line": 2, "value": 5},
+      {"path": "crate::branchy", "file": "src/lib.rs", "line": 4, "value": 3}
+    ]
+  }
+}"#;
+    assert_json_eq(&report.to_json().unwrap(), expected);
+    assert!(report.to_text().contains("  1. crate::parse::scan = 5 (src/parse.rs:2)\n"));
+
+    // 其他度量的排名，相同时按路径排列
+    let ranked = |key: MetricKey| -> Vec<(String, usize)> {
+        let ranking = details.ranking(key, 10);
+        assert_eq!(ranking.metric, key.keyword());
+        ranking.functions.into_iter().map(|function| (function.path, function.value)).collect()
+    };
+    let expect = |values: [(&str, usize); 4]| -> Vec<(String, usize)> {
+        values.iter().map(|(path, value)| (path.to_string(), *value)).collect()
+    };
+    assert_eq!(
+        ranked(MetricKey::Cognitive),
+        expect([("crate::parse::scan", 7), ("crate::branchy", 5), ("crate::single", 2), ("crate::simple", 0)])
+    );
+    assert_eq!(
+        ranked(MetricKey::Nesting),
+        expect([("crate::parse::scan", 3), ("crate::branchy", 2), ("crate::single", 1), ("crate::simple", 0)])
+    );
+    assert_eq!(
+        ranked(MetricKey::Params),
+        expect([("crate::parse::scan", 3), ("crate::branchy", 1), ("crate::single", 1), ("crate::simple", 0)])
+    );
+    assert_eq!(
+        ranked(MetricKey::Loc),
+        expect([("crate::parse::scan", 12), ("crate::branchy", 7), ("crate::single", 3), ("crate::simple", 1)])
+    );
+    assert_eq!(
+        ranked(MetricKey::Statements),
+        expect([("crate::parse::scan", 7), ("crate::branchy", 5), ("crate::single", 3), ("crate::simple", 0)])
+    );
+
+    // 按文件和模块的汇总
+    let analysis = code_parser::analyze_project(&test_dir.path, &Options::default()).unwrap();
+    let files = &analysis.crates[0].files;
+    let lib = files.iter().find(|items| items.file.ends_with("src/lib.rs")).unwrap();
+    let summary = metrics::summarize_file(lib);
+    assert_eq!((summary.functions, summary.cyclomatic, summary.max_cyclomatic), (3, 6, 3));
+    assert_eq!((summary.cognitive, summary.max_cognitive, summary.max_nesting), (7, 5, 2));
+    assert_eq!((summary.loc, summary.statements), (11, 8));
+    assert_eq!(
+        log::format_summary(&summary),
+        "3 个函数，圈复杂度 合计 6 平均 2.0 最大 3，认知复杂度 合计 7 最大 5，最大嵌套 2，行数 11，语句数 8"
+    );
+    let modules = metrics::summarize_modules(files);
+    assert_eq!(modules.keys().copied().collect::<Vec<_>>(), ["crate", "crate::parse"]);
+    assert_eq!(log::format_summary(&modules["crate"]), log::format_summary(&summary));
+    assert_eq!(
+        log::format_summary(&modules["crate::parse"]),
+        "1 个函数，圈复杂度 合计 5 平均 5.0 最大 5，认知复杂度 合计 7 最大 7，最大嵌套 3，行数 12，语句数 7"
+    );
+}
+
+#[test]
+fn test_rust_report_details() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file(
+        "Cargo.toml",
+        "[package]\nname = \"geometry\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[features]\ndefault = [\"circle\"]\ncircle = []\n",
+    );
+    test_dir.create_file("src/lib.rs", r#"
+mod shapes;
+
+pub use shapes::Circle as Round;
+
+pub type Result<T> = std::result::Result<T, String>;
+
+pub const LIMIT: usize = 8;
+
+macro_rules! flag {
+    ($name:ident) => { pub static $name: bool = true; };
+}
+flag!(VERBOSE);
+
+pub trait Shape: std::fmt::Debug {
+    type Unit;
+    const SIDES: usize = 0;
+
+    fn area(&self) -> f64;
+
+    fn describe(&self) -> String {
+        format!("{:?}", self.area())
+    }
+}
+"#);
+    test_dir.create_file("src/shapes.rs", r#"
+#[derive(Debug)]
+#[deprecated(note = "use Ellipse")]
+#[cfg(feature = "circle")]
+pub struct Circle(pub f64);
+
+impl crate::Shape for Circle {
+    type Unit = f64;
+
+    fn area(&self) -> f64 {
+        square(self.0) * 3.14
+    }
+}
+
+fn square(x: f64) -> f64 {
+    x * x
+}
+"#);
+
+    let report = code_parser::analyze_rust(&test_dir.path, &Options::default()).unwrap();
+    let files: Vec<_> = report.files().collect();
+    let (lib, shapes) = (files[0], files[1]);
+
+    let alias = &lib.aliases[0];
+    assert_eq!(alias.declaration, "type Result<T> = std::result::Result<T, String>");
+    // 公开路径与 rustdoc 一致，以 crate 名称开头
+    assert_eq!(alias.public_paths, ["geometry::Result"]);
+    let values: Vec<(&str, &str, Option<&str>)> = lib
+        .values
+        .iter()
+        .map(|value| (value.path.as_str(), value.kind.as_str(), value.expanded_from.as_deref()))
+        .collect();
+    assert_eq!(values, [("crate::LIMIT", "const", None), ("crate::VERBOSE", "static", Some("flag"))]);
+
+    let shape = &lib.traits[0];
+    assert_eq!(shape.supertraits, ["std::fmt::Debug"]);
+    assert_eq!(shape.assoc_types[0].declaration, "type Unit");
+    assert_eq!(shape.assoc_consts[0].declaration, "const SIDES: usize = 0");
+    let provided: Vec<(&str, bool)> = shape.methods.iter().map(|m| (m.name.as_str(), m.provided)).collect();
+    assert_eq!(provided, [("area", false), ("describe", true)]);
+
+    let circle = &shapes.types[0];
+    assert_eq!(circle.public_paths, ["geometry::Round"]);
+    assert_eq!(circle.canonical_path.as_deref(), Some("geometry::Round"));
+    assert!(circle.deprecated);
+    assert_eq!(circle.cfg, ["feature = \"circle\""]);
+    assert!(circle.attrs.contains(&"#[deprecated(note = \"use Ellipse\")]".to_string()));
+
+    let imp = &shapes.trait_impls[0];
+    assert_eq!((imp.resolved_trait.as_str(), imp.type_path.as_str()), ("crate::Shape", "crate::shapes::Circle"));
+    let calls: Vec<(&str, &str)> = imp.methods[0].calls.iter().map(|c| (c.callee.as_str(), c.kind.as_str())).collect();
+    assert_eq!(calls, [("square", "path")]);
+}
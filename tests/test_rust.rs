@@ -1,6 +1,27 @@
 use code_parser::test_utils::{TestDir, assert_json_eq};
+use serde_json::Value;
 use std::process::Command;
 
+// 把输出拆成通用格式的报告和 details 中的 Rust 详细报告。
+// 通用格式中的位置由 test_rust_locations 检查，这里去掉
+fn split_details(stdout: &[u8]) -> (String, Value) {
+    let mut report: Value = serde_json::from_slice(stdout).unwrap();
+    let details = report.as_object_mut().unwrap().remove("details").unwrap();
+    for file in report["files"].as_array_mut().unwrap() {
+        strip_locations(&mut file["functions"]);
+        for class in file["classes"].as_array_mut().unwrap() {
+            strip_locations(&mut class["methods"]);
+        }
+    }
+    (report.to_string(), details["rust"].clone())
+}
+
+fn strip_locations(symbols: &mut Value) {
+    for symbol in symbols.as_array_mut().unwrap() {
+        symbol.as_object_mut().unwrap().remove("location");
+    }
+}
+
 #[test]
 fn test_rust_parser() {
     let test_dir = TestDir::new();
@@ -51,8 +72,9 @@ pub const LAMBDA: fn(i32) -> i32 = |x| x * 2;
     assert!(output.status.success(), "解析器执行失败");
 
     // 验证输出
-    let output_str = String::from_utf8_lossy(&output.stdout);
+    let (output_str, details) = split_details(&output.stdout);
     let expected = r#"{
+  "schema_version": "1.3",
   "files": [
     {
       "path": "test.rs",
@@ -103,6 +125,12 @@ pub const LAMBDA: fn(i32) -> i32 = |x| x * 2;
 }"#;
 
     assert_json_eq(&output_str, expected);
+
+    // 签名、可见性和位置只在详细报告中
+    let function = &details["crates"][0]["files"][0]["functions"][1];
+    assert_eq!(function["signature"], "async fn async_function() -> String");
+    assert_eq!(function["visibility"], "pub");
+    assert_eq!(function["location"]["start_line"], 24);
 }
 
 #[test]
@@ -152,8 +180,9 @@ impl<T: Display> Printable for Data<T> {
     assert!(output.status.success(), "解析器执行失败");
 
     // 验证输出
-    let output_str = String::from_utf8_lossy(&output.stdout);
+    let (output_str, details) = split_details(&output.stdout);
     let expected = r#"{
+  "schema_version": "1.3",
   "files": [
     {
       "path": "test_traits.rs",
@@ -182,6 +211,10 @@ impl<T: Display> Printable for Data<T> {
 }"#;
 
     assert_json_eq(&output_str, expected);
+
+    let file = &details["crates"][0]["files"][0];
+    assert_eq!(file["traits"][0]["implementors"], serde_json::json!(["Data<T>"]));
+    assert_eq!(file["trait_impls"][0]["header"], "impl<T: Display> Printable for Data<T>");
 }
 
 #[test]
@@ -230,8 +263,9 @@ impl MacroStruct {
     assert!(output.status.success(), "解析器执行失败");
 
     // 验证输出
-    let output_str = String::from_utf8_lossy(&output.stdout);
+    let (output_str, details) = split_details(&output.stdout);
     let expected = r#"{
+  "schema_version": "1.3",
   "files": [
     {
       "path": "test_macros.rs",
@@ -266,4 +300,90 @@ impl MacroStruct {
 }"#;
 
     assert_json_eq(&output_str, expected);
-} 
\ No newline at end of file
+
+    let function = &details["crates"][0]["files"][0]["functions"][0];
+    assert_eq!(function["expanded_from"], "create_function");
+    assert_eq!(function["visibility"], "private");
+}
+
+#[test]
+fn test_rust_locations() {
+    let test_dir = TestDir::new();
+    test_dir.create_file("lib.rs", r#"
+pub fn item() {}
+
+pub struct S;
+
+impl S {
+    pub fn method(&self) {}
+}
+"#);
+
+    // item 从第 2 行开头的第 1 个字节开始，method 从第 7 行第 5 列的第 47 个字节开始
+    let item = serde_json::json!({
+        "start_line": 2, "start_column": 1, "end_line": 2, "end_column": 17, "byte_start": 1, "byte_end": 17
+    });
+    let method = serde_json::json!({
+        "start_line": 7, "start_column": 5, "end_line": 7, "end_column": 28, "byte_start": 47, "byte_end": 70
+    });
+
+    // 通用格式的报告和 details 中的详细报告
+    let output = Command::new(env!("CARGO_BIN_EXE_code-parser"))
+        .arg("analyze")
+        .arg("--lang")
+        .arg("rust")
+        .arg(&test_dir.path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "解析器执行失败");
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["files"][0]["functions"][0]["location"], item);
+    assert_eq!(report["files"][0]["classes"][0]["methods"][0]["location"], method);
+    let file = &report["details"]["rust"]["crates"][0]["files"][0];
+    assert_eq!(file["functions"][0]["location"], item);
+    assert_eq!(file["types"][0]["methods"][0]["location"], method);
+
+    // 文本格式的报告
+    let output = Command::new(env!("CARGO_BIN_EXE_code-parser"))
+        .arg("analyze")
+        .arg("--lang")
+        .arg("rust")
+        .arg("--format")
+        .arg("text")
+        .arg(&test_dir.path)
+        .output()
+        .unwrap();
+    let text = String::from_utf8_lossy(&output.stdout);
+    assert!(text.contains("  - item (function) @2:1-2:17 (1..17)\n"));
+    assert!(text.contains("    - method (method) @7:5-7:28 (47..70)\n"));
+
+    // Rust 解析器的日志
+    let log_path = test_dir.path.join("fns_log");
+    let output = Command::new(env!("CARGO_BIN_EXE_rustAPI"))
+        .arg(&test_dir.path)
+        .arg(&log_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "Rust 解析器执行失败");
+    let log = std::fs::read_to_string(&log_path).unwrap();
+    assert!(log.contains("  - crate::item: pub fn item() @2:1-2:17 (1..17)\n"));
+    assert!(log.contains("    - pub fn method(&self) @7:5-7:28 (47..70)\n"));
+}
+
+#[test]
+fn test_rust_parser_default_log() {
+    let test_dir = TestDir::new();
+    test_dir.create_file("lib.rs", "pub fn item() {}\n");
+
+    // 没有指定日志文件时写入主目录下的输出目录，不写入分析的目录
+    let home = TestDir::new();
+    let output = Command::new(env!("CARGO_BIN_EXE_rustAPI"))
+        .arg(&test_dir.path)
+        .env("HOME", &home.path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "Rust 解析器执行失败");
+    let log = std::fs::read_to_string(home.path.join(".parser/output/fns_log")).unwrap();
+    assert!(log.contains("  - crate::item: pub fn item() @1:1-1:17 (0..16)\n"));
+    assert!(!test_dir.path.join("fns_log").exists());
+}
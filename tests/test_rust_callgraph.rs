@@ -0,0 +1,223 @@
+use code_parser::rust::callgraph::{self, CallGraph};
+use code_parser::rust::project::ProjectAnalysis;
+use code_parser::test_utils::{TestDir, assert_json_eq};
+use code_parser::Options;
+use std::fs;
+use std::process::Command;
+
+// 路径调用（包括经过 use 别名的调用）、方法调用、宏调用，以及同名方法导致无法解析的方法调用
+fn create_crate(test_dir: &TestDir) {
+    test_dir.create_dir("src");
+    test_dir.create_file("src/lib.rs", r#"
+mod net;
+
+use net::connect as dial;
+use crate::net::Client;
+
+macro_rules! trace {
+    () => {};
+}
+
+pub fn run() {
+    let client = dial("localhost");
+    client.send();
+    net::helper();
+    Client::new();
+    client.close();
+    trace!();
+    println!("done");
+}
+"#);
+    test_dir.create_file("src/net.rs", r#"
+pub struct Client;
+pub struct Server;
+
+impl Client {
+    pub fn new() -> Client {
+        Client
+    }
+
+    pub fn send(&self) {
+        self.close();
+    }
+
+    pub fn close(&self) {}
+}
+
+impl Server {
+    pub fn close(&self) {}
+}
+
+pub fn connect(addr: &str) -> Client {
+    Client::new()
+}
+
+pub fn helper() {}
+"#);
+}
+
+fn build_graph(test_dir: &TestDir) -> CallGraph {
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    let analysis = &analysis.crates[0];
+    CallGraph::build(&analysis.root, &analysis.files)
+}
+
+// 每条边的调用方、被调用方、调用方式和是否解析成功
+fn edges(graph: &CallGraph) -> Vec<(&str, &str, &str, bool)> {
+    graph
+        .edges
+        .iter()
+        .map(|edge| (edge.caller.as_str(), edge.callee.as_str(), edge.kind, edge.resolved))
+        .collect()
+}
+
+#[test]
+fn test_call_resolution() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+    let graph = build_graph(&test_dir);
+
+    assert_eq!(
+        graph.nodes,
+        [
+            "crate::net::Client::close",
+            "crate::net::Client::new",
+            "crate::net::Client::send",
+            "crate::net::Server::close",
+            "crate::net::connect",
+            "crate::net::helper",
+            "crate::run",
+        ]
+    );
+    assert_eq!(
+        edges(&graph),
+        [
+            // use 别名 dial 解析到 net::connect，use 导入的 Client 的关联函数解析到定义处
+            ("crate::run", "crate::net::connect", "path", true),
+            // crate 中只有一个 send 方法
+            ("crate::run", "crate::net::Client::send", "method", true),
+            ("crate::run", "crate::net::helper", "path", true),
+            ("crate::run", "crate::net::Client::new", "path", true),
+            // Client 和 Server 都有 close，接收者不是 self 时无法确定
+            ("crate::run", "close", "method", false),
+            // crate 自己定义的宏才算解析成功
+            ("crate::run", "trace!", "macro", true),
+            ("crate::run", "println!", "macro", false),
+            // 同一文件中先是函数，再是方法；接收者为 self 时先找所属类型的方法
+            ("crate::net::connect", "crate::net::Client::new", "path", true),
+            ("crate::net::Client::send", "crate::net::Client::close", "method", true),
+        ]
+    );
+    assert_eq!(graph.entry_points, ["crate::net::Server::close", "crate::run"]);
+}
+
+#[test]
+fn test_call_graph_formats() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+    let graphs = [build_graph(&test_dir)];
+    let root = test_dir.path.join("src/lib.rs");
+    let lib = root.display();
+
+    let json: serde_json::Value = serde_json::from_str(&callgraph::to_json(&graphs).unwrap()).unwrap();
+    assert_eq!(json[0]["crate"], lib.to_string());
+    assert_json_eq(
+        &json[0]["edges"][0].to_string(),
+        &format!(
+            r#"{{"caller": "crate::run", "callee": "crate::net::connect", "kind": "path", "resolved": true, "file": "{}", "line": 12, "column": 18}}"#,
+            lib
+        ),
+    );
+
+    let expected_dot = format!(
+        r#"digraph calls {{
+    rankdir=LR;
+    node [shape=box];
+    subgraph cluster_0 {{
+        label="{lib}";
+        "0:crate::net::Client::close" [label="crate::net::Client::close"];
+        "0:crate::net::Client::new" [label="crate::net::Client::new"];
+        "0:crate::net::Client::send" [label="crate::net::Client::send"];
+        "0:crate::net::Server::close" [label="crate::net::Server::close"];
+        "0:crate::net::connect" [label="crate::net::connect"];
+        "0:crate::net::helper" [label="crate::net::helper"];
+        "0:crate::run" [label="crate::run"];
+        "0:close" [label="close", style=dashed];
+        "0:println!" [label="println!", style=dashed];
+        "0:trace!" [label="trace!", style=dashed];
+        "0:crate::run" -> "0:crate::net::connect";
+        "0:crate::run" -> "0:crate::net::Client::send";
+        "0:crate::run" -> "0:crate::net::helper";
+        "0:crate::run" -> "0:crate::net::Client::new";
+        "0:crate::run" -> "0:close" [style=dashed];
+        "0:crate::run" -> "0:trace!";
+        "0:crate::run" -> "0:println!" [style=dashed];
+        "0:crate::net::connect" -> "0:crate::net::Client::new";
+        "0:crate::net::Client::send" -> "0:crate::net::Client::close";
+    }}
+}}
+"#
+    );
+    assert_eq!(callgraph::to_dot(&graphs), expected_dot);
+
+    let expected_mermaid = format!(
+        r#"graph LR
+    subgraph c0["{lib}"]
+        c0n0["crate::net::Client::close"]
+        c0n1["crate::net::Client::new"]
+        c0n2["crate::net::Client::send"]
+        c0n3["crate::net::Server::close"]
+        c0n4["crate::net::connect"]
+        c0n5["crate::net::helper"]
+        c0n6["crate::run"]
+        c0n7(["close"])
+        c0n8(["println!"])
+        c0n9(["trace!"])
+    end
+    c0n6 --> c0n4
+    c0n6 --> c0n2
+    c0n6 --> c0n5
+    c0n6 --> c0n1
+    c0n6 -.-> c0n7
+    c0n6 --> c0n9
+    c0n6 -.-> c0n8
+    c0n4 --> c0n1
+    c0n2 --> c0n0
+"#
+    );
+    assert_eq!(callgraph::to_mermaid(&graphs), expected_mermaid);
+}
+
+#[test]
+fn test_call_graph_option() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+
+    // 调用图写入主目录下的输出目录，文件扩展名取决于格式
+    let home = TestDir::new();
+    for (format, file) in [("json", "call_graph.json"), ("dot", "call_graph.dot"), ("mermaid", "call_graph.mmd")] {
+        let output = Command::new(env!("CARGO_BIN_EXE_code-parser"))
+            .arg("analyze")
+            .arg("--lang")
+            .arg("rust")
+            .arg("--call-graph")
+            .arg(format)
+            .arg(&test_dir.path)
+            .env("HOME", &home.path)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "解析器执行失败");
+        let path = home.path.join(".parser/output").join(file);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(&format!("调用图已写入到 {}（1 个 crate，9 条调用）", path.display())));
+        let content = fs::read_to_string(&path).unwrap();
+        match format {
+            "json" => {
+                let graphs: serde_json::Value = serde_json::from_str(&content).unwrap();
+                assert_eq!(graphs[0]["entry_points"], serde_json::json!(["crate::net::Server::close", "crate::run"]));
+            }
+            "dot" => assert!(content.contains("        \"0:crate::run\" -> \"0:crate::net::connect\";\n")),
+            _ => assert!(content.contains("    c0n6 --> c0n4\n")),
+        }
+    }
+}
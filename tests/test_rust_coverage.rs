@@ -0,0 +1,136 @@
+use code_parser::rust::coverage::DocCoverage;
+use code_parser::rust::log;
+use code_parser::rust::project::ProjectAnalysis;
+use code_parser::test_utils::TestDir;
+use code_parser::Options;
+use std::path::Path;
+
+// 每个模块各有一个有文档和一个没有文档的公开条目，另有缺少 Errors、Panics、Safety 小节的函数
+fn create_crate(test_dir: &TestDir) {
+    test_dir.create_dir("src");
+    test_dir.create_file("src/lib.rs", r#"
+pub mod io;
+
+// 普通注释不算文档
+pub fn undocumented() {}
+
+/// 有文档的函数
+pub fn documented() {}
+
+fn private_helper() {}
+
+#[doc(hidden)]
+pub fn hidden() {}
+"#);
+    test_dir.create_file("src/io.rs", r#"
+/// 读取配置
+pub fn read(path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(path)
+}
+
+/// 解析端口
+pub fn port(text: &str) -> u16 {
+    match text.parse() {
+        Ok(port) => port,
+        Err(_) => panic!("端口无效"),
+    }
+}
+
+/// 读取原始指针
+pub unsafe fn load(ptr: *const u8) -> u8 {
+    *ptr
+}
+
+/// 写入文件
+///
+/// # Errors
+///
+/// 无法写入时返回错误
+pub fn write(path: &str, text: &str) -> std::io::Result<()> {
+    std::fs::write(path, text)
+}
+
+pub struct Buffer;
+"#);
+}
+
+fn compute(dir: &Path) -> DocCoverage {
+    let analysis = ProjectAnalysis::analyze(dir, &Options::default());
+    DocCoverage::compute(analysis.crates.iter().flat_map(|c| c.files.iter()))
+}
+
+#[test]
+fn test_doc_coverage_modules() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+    let coverage = compute(&test_dir.path);
+
+    // 私有条目和 #[doc(hidden)] 的条目不计入
+    let modules: Vec<(&str, usize, usize, f64)> = coverage
+        .modules
+        .iter()
+        .map(|m| (m.module.as_str(), m.documented, m.total, m.percent()))
+        .collect();
+    assert_eq!(modules, [("crate", 1, 2, 50.0), ("crate::io", 4, 5, 80.0)]);
+    assert_eq!((coverage.documented(), coverage.total()), (5, 7));
+
+    let undocumented: Vec<(&str, usize)> = coverage
+        .undocumented
+        .iter()
+        .map(|issue| (issue.path.as_str(), issue.location.start_line))
+        .collect();
+    assert_eq!(undocumented, [("crate::undocumented", 5), ("crate::io::Buffer", 29)]);
+    assert!(coverage.undocumented.iter().all(|issue| issue.missing_section.is_none()));
+}
+
+#[test]
+fn test_doc_coverage_missing_sections() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+    let coverage = compute(&test_dir.path);
+
+    // write 已经说明了 Errors，不会被记录
+    let missing: Vec<(&str, Option<&str>)> = coverage
+        .missing_sections
+        .iter()
+        .map(|issue| (issue.path.as_str(), issue.missing_section))
+        .collect();
+    assert_eq!(
+        missing,
+        [
+            ("crate::io::read", Some("Errors")),
+            ("crate::io::port", Some("Panics")),
+            ("crate::io::load", Some("Safety")),
+        ]
+    );
+}
+
+#[test]
+fn test_write_coverage() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+    let coverage = compute(&test_dir.path);
+
+    let mut output = Vec::new();
+    log::write_coverage(&mut output, &test_dir.path, &coverage).unwrap();
+    let lib = test_dir.path.join("src/lib.rs");
+    let io = test_dir.path.join("src/io.rs");
+    let expected = format!(
+        "文档覆盖率 ({root}):
+  crate: 1/2 (50.0%)
+  crate::io: 4/5 (80.0%)
+  总计: 5/7 (71.4%)
+  缺少文档的公开条目:
+    crate::undocumented ({lib}:5)
+    crate::io::Buffer ({io}:29)
+  缺少的文档小节:
+    crate::io::read: # Errors ({io}:2)
+    crate::io::port: # Panics ({io}:7)
+    crate::io::load: # Safety ({io}:15)
+",
+        root = test_dir.path.display(),
+        lib = lib.display(),
+        io = io.display()
+    );
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+}
@@ -0,0 +1,105 @@
+use code_parser::rust::diagnostics::parse_source;
+use code_parser::rust::project::ProjectAnalysis;
+use code_parser::test_utils::TestDir;
+use code_parser::Options;
+use serde_json::Value;
+use std::fs;
+use std::process::Command;
+
+// broken.rs 第 3 行的表达式不完整，其余文件可以正常解析
+fn create_crate(test_dir: &TestDir) {
+    test_dir.create_dir("src");
+    test_dir.create_file("src/lib.rs", r#"
+pub mod broken;
+pub mod fine;
+"#);
+    test_dir.create_file("src/broken.rs", r#"
+pub fn broken() {
+    let x = 1 + ;
+}
+"#);
+    test_dir.create_file("src/fine.rs", r#"
+pub fn fine() {}
+"#);
+}
+
+#[test]
+fn test_syntax_error() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    assert_eq!(analysis.diagnostics.len(), 1);
+    let diagnostic = &analysis.diagnostics[0];
+    assert!(diagnostic.file.ends_with("src/broken.rs"));
+    assert_eq!((diagnostic.line, diagnostic.column), (Some(3), Some(17)));
+
+    // 其余文件照常分析
+    let functions: Vec<&str> = analysis.crates[0]
+        .files
+        .iter()
+        .flat_map(|file| file.functions.iter().map(|f| f.path.as_str()))
+        .collect();
+    assert_eq!(functions, ["crate::fine::fine"]);
+}
+
+#[test]
+fn test_invalid_utf8() {
+    let test_dir = TestDir::new();
+    let path = test_dir.path.join("latin1.rs");
+    // 第 2 行 "caf" 之后是 Latin-1 编码的 é
+    fs::write(&path, b"// \xe6\xb3\xa8\xe9\x87\x8a\nconst NAME: &str = \"caf\xe9\";\n").unwrap();
+
+    let diagnostic = parse_source(&path).unwrap_err();
+    assert_eq!(diagnostic.file, path);
+    assert_eq!((diagnostic.line, diagnostic.column), (Some(2), Some(24)));
+    assert_eq!(diagnostic.message, "文件不是有效的 UTF-8 编码");
+    assert_eq!(diagnostic.to_string(), format!("{}:2:24: 文件不是有效的 UTF-8 编码", path.display()));
+}
+
+#[test]
+fn test_strict() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+    let home = TestDir::new();
+
+    // 默认只在报告中记录诊断信息
+    let output = Command::new(env!("CARGO_BIN_EXE_code-parser"))
+        .arg("analyze")
+        .arg("--lang")
+        .arg("rust")
+        .arg(&test_dir.path)
+        .env("HOME", &home.path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "解析器执行失败");
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let diagnostics = report["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0]["file"].as_str().unwrap().ends_with("src/broken.rs"));
+    assert_eq!((&diagnostics[0]["line"], &diagnostics[0]["column"]), (&Value::from(3), &Value::from(17)));
+
+    // --strict 时有文件无法解析就以非零状态退出
+    let output = Command::new(env!("CARGO_BIN_EXE_code-parser"))
+        .arg("analyze")
+        .arg("--lang")
+        .arg("rust")
+        .arg("--strict")
+        .arg(&test_dir.path)
+        .env("HOME", &home.path)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 个文件无法解析（--strict）"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rustAPI"))
+        .arg("--strict")
+        .arg(&test_dir.path)
+        .env("HOME", &home.path)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1 个文件无法解析\n"));
+    assert!(stdout.contains(&format!("  {}:3:17: ", test_dir.path.join("src/broken.rs").display())));
+}
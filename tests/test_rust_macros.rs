@@ -0,0 +1,144 @@
+use code_parser::rust::macros::MacroTable;
+use code_parser::rust::modules::ModuleScope;
+use code_parser::rust::signature::render_tokens;
+use code_parser::rust::visitor::FunctionVisitor;
+use syn::visit::Visit;
+use syn::Item;
+
+// 收集 source 中的宏定义，展开最后一个条目位置的宏调用，每个生成的条目渲染为一行
+fn expand(source: &str) -> Result<Vec<String>, String> {
+    let file = syn::parse_file(source).unwrap();
+    let table = MacroTable::collect([&file]);
+    let Some(Item::Macro(invocation)) = file.items.last() else {
+        panic!("最后一个条目不是宏调用");
+    };
+    let items = table.expand(&invocation.mac)?;
+    Ok(items.iter().map(render_tokens).collect())
+}
+
+#[test]
+fn test_repetitions() {
+    let source = r#"
+macro_rules! getters {
+    ($name:ident { $($field:ident: $ty:ty),* $(,)? }) => {
+        impl $name {
+            $(pub fn $field(&self) -> &$ty { &self.$field })*
+        }
+    };
+}
+getters!(Point { x: i32, y: i32, });
+"#;
+    assert_eq!(
+        expand(source).unwrap(),
+        ["impl Point { pub fn x(&self) -> &i32 { &self.x } pub fn y(&self) -> &i32 { &self.y } }"]
+    );
+}
+
+#[test]
+fn test_nested_repetitions() {
+    let source = r#"
+macro_rules! modules {
+    ($($module:ident => [$($item:ident)+]);+ $(;)?) => {
+        $(pub mod $module { $(pub fn $item() {})+ })+
+    };
+}
+modules! {
+    net => [connect close];
+    io => [read];
+}
+"#;
+    assert_eq!(
+        expand(source).unwrap(),
+        [
+            "pub mod net { pub fn connect() {} pub fn close() {} }",
+            "pub mod io { pub fn read() {} }",
+        ]
+    );
+
+    // `+` 至少需要一次重复
+    let source = source.replace("io => [read];", "io => [];");
+    assert!(expand(&source).is_err());
+}
+
+#[test]
+fn test_optional_and_multi_token_separators() {
+    let source = r#"
+macro_rules! routes {
+    ($($path:literal => $handler:ident)=>* $(; $fallback:ident)?) => {
+        $(pub fn $handler() -> &'static str { $path })*
+        $(pub fn $fallback() {})?
+    };
+}
+routes!("/" => index => "/about" => about; missing);
+"#;
+    assert_eq!(
+        expand(source).unwrap(),
+        [
+            "pub fn index() -> &'static str { \"/\" }",
+            "pub fn about() -> &'static str { \"/about\" }",
+            "pub fn missing() {}",
+        ]
+    );
+}
+
+#[test]
+fn test_recursive_macro_and_crate_path() {
+    // 展开结果中的宏调用原样保留，由访问者再次展开
+    let source = r#"
+macro_rules! consts {
+    () => {};
+    ($name:ident = $value:expr $(, $rest:ident = $rest_value:expr)*) => {
+        pub const $name: $crate::Value = $crate::Value($value);
+        consts!($($rest = $rest_value),*);
+    };
+}
+consts!(A = 1, B = 2 + 3);
+"#;
+    assert_eq!(
+        expand(source).unwrap(),
+        ["pub const A: crate::Value = crate::Value(1);", "consts!(B = 2 + 3);"]
+    );
+}
+
+#[test]
+fn test_no_matching_rule() {
+    let source = r#"
+macro_rules! only_idents {
+    ($($name:ident),*) => { $(pub struct $name;)* };
+}
+only_idents!(1, 2);
+"#;
+    assert_eq!(expand(source).unwrap_err(), "only_idents! 的调用没有匹配的规则");
+}
+
+#[test]
+fn test_visitor_expands_recursively() {
+    let source = r#"
+pub struct Value(i32);
+macro_rules! consts {
+    () => {};
+    ($name:ident = $value:expr $(, $rest:ident = $rest_value:expr)*) => {
+        pub const $name: $crate::Value = $crate::Value($value);
+        consts!($($rest = $rest_value),*);
+    };
+}
+consts!(A = 1, B = 2, C = 3);
+consts!(1);
+"#;
+    let file = syn::parse_file(source).unwrap();
+    let mut visitor = FunctionVisitor::new(vec!["crate".to_string()], ModuleScope::root())
+        .with_macros(MacroTable::collect([&file]));
+    visitor.visit_file(&file);
+    let values: Vec<(&str, Option<&str>)> = visitor
+        .items
+        .values
+        .iter()
+        .map(|value| (value.path.as_str(), value.expanded_from.as_deref()))
+        .collect();
+    assert_eq!(
+        values,
+        [("crate::A", Some("consts")), ("crate::B", Some("consts")), ("crate::C", Some("consts"))]
+    );
+    // 没有匹配规则的调用不展开，只记录警告
+    assert_eq!(visitor.items.warnings, ["consts! 的调用没有匹配的规则"]);
+}
@@ -0,0 +1,161 @@
+use code_parser::rust::model::{PanicKind, PanicSite};
+use code_parser::rust::panic_audit::{in_module, PanicVisitor};
+use code_parser::test_utils::TestDir;
+use std::fs;
+use std::process::Command;
+
+fn collect(source: &str) -> Vec<PanicSite> {
+    let syntax = syn::parse_file(source).unwrap();
+    PanicVisitor::collect(vec!["crate".to_string()], &syntax)
+}
+
+// 每处可能 panic 的代码的种类、所在函数、行号和源码写法
+fn summary(sites: &[PanicSite]) -> Vec<(PanicKind, &str, usize, &str)> {
+    sites
+        .iter()
+        .map(|site| (site.kind, site.enclosing.as_str(), site.location.start_line, site.code.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_panic_sites() {
+    let source = r#"
+pub fn load_config() -> Config {
+    let home = dirs::home_dir().unwrap();
+    let text = std::fs::read_to_string(home.join("config.toml")).expect("无法读取配置");
+    toml::from_str(&text).unwrap()
+}
+
+pub fn check(values: &[u32], index: usize) -> u32 {
+    assert!(index < values.len());
+    if values.is_empty() {
+        panic!("没有数据");
+    }
+    match index {
+        0 => todo!(),
+        1 => unimplemented!("尚未支持"),
+        2 => values[index] + values[1..][0],
+        _ => unreachable!(),
+    }
+}
+
+impl Config {
+    pub fn port(&self) -> u16 {
+        println!("端口: {}", self.port.unwrap());
+        assert_eq!(self.name, "server", "名称错误");
+        self.port.unwrap_or(80)
+    }
+}
+"#;
+    assert_eq!(
+        summary(&collect(source)),
+        [
+            (PanicKind::Unwrap, "crate::load_config", 3, "dirs::home_dir().unwrap()"),
+            (
+                PanicKind::Expect,
+                "crate::load_config",
+                4,
+                "std::fs::read_to_string(home.join(\"config.toml\")).expect(\"无法..."
+            ),
+            (PanicKind::Unwrap, "crate::load_config", 5, "toml::from_str(&text).unwrap()"),
+            (PanicKind::Assert, "crate::check", 9, "assert!"),
+            (PanicKind::Panic, "crate::check", 11, "panic!"),
+            (PanicKind::Todo, "crate::check", 14, "todo!"),
+            (PanicKind::Unimplemented, "crate::check", 15, "unimplemented!"),
+            (PanicKind::Index, "crate::check", 16, "values[index]"),
+            (PanicKind::Index, "crate::check", 16, "values[1..][0]"),
+            (PanicKind::Index, "crate::check", 16, "values[1..]"),
+            (PanicKind::Unreachable, "crate::check", 17, "unreachable!"),
+            // 宏参数中的 unwrap 也会记录，unwrap_or 不会
+            (PanicKind::Unwrap, "crate::Config::port", 23, "self.port.unwrap()"),
+            (PanicKind::Assert, "crate::Config::port", 24, "assert_eq!"),
+        ]
+    );
+}
+
+#[test]
+fn test_skip_test_code_and_constants() {
+    let source = r#"
+const LIMIT: usize = [1, 2, 3][0];
+static NAME: &str = match option_env!("NAME") { Some(name) => name, None => panic!() };
+
+pub fn run(args: &[String]) -> &str {
+    &args[0]
+}
+
+#[test]
+fn test_run() {
+    run(&[]).parse::<u32>().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    fn helper() {
+        None::<u32>.unwrap();
+    }
+}
+
+#[cfg(all(test, unix))]
+fn unix_helper() {
+    todo!()
+}
+"#;
+    // 只有 run 中的下标会在运行时 panic
+    assert_eq!(summary(&collect(source)), [(PanicKind::Index, "crate::run", 6, "args[0]")]);
+}
+
+#[test]
+fn test_in_module() {
+    assert!(in_module("crate::net", "crate::net"));
+    assert!(in_module("crate::net::tcp::connect", "crate::net"));
+    assert!(!in_module("crate::network::connect", "crate::net"));
+    assert!(!in_module("crate::connect", "crate::net"));
+}
+
+#[test]
+fn test_audit_panic_module() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file("src/lib.rs", r#"
+pub mod net;
+pub mod network;
+
+pub fn load_config() -> String {
+    std::fs::read_to_string(dirs::home_dir().unwrap()).unwrap()
+}
+"#);
+    test_dir.create_file("src/net.rs", r#"
+pub fn connect(addrs: &[&str]) -> std::net::TcpStream {
+    std::net::TcpStream::connect(addrs[0]).expect("连接失败")
+}
+"#);
+    test_dir.create_file("src/network.rs", r#"
+pub fn listen() {
+    todo!()
+}
+"#);
+
+    // 日志写入主目录下的输出目录，换一个主目录以免读到用户的配置
+    let home = TestDir::new();
+    let output = Command::new(env!("CARGO_BIN_EXE_code-parser"))
+        .arg("audit")
+        .arg("panic")
+        .arg("--module")
+        .arg("crate::net")
+        .arg(&test_dir.path)
+        .env("HOME", &home.path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "审计执行失败");
+
+    let log = fs::read_to_string(home.path.join(".parser/output/fns_log")).unwrap();
+    let net = test_dir.path.join("src/net.rs");
+    assert!(log.contains(&format!("  crate::net::connect ({}):\n", net.display())));
+    assert!(log.contains("    expect @3:5-3:58 (61..122) std::net::TcpStream::connect(addrs[0]).expect(\"连接失败\")\n"));
+    assert!(log.contains("    index @3:34-3:42 (90..98) addrs[0]\n"));
+    assert!(log.contains("  合计: 2 处，涉及 1 个函数 (expect 1, index 1)\n"));
+    // crate::network 和 crate 根模块中的函数不在 crate::net 中
+    assert!(!log.contains("crate::network::listen"));
+    assert!(!log.contains("crate::load_config"));
+}
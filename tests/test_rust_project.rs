@@ -0,0 +1,418 @@
+use code_parser::rust::project::ProjectAnalysis;
+use code_parser::rust::workspace::TargetKind;
+use code_parser::test_utils::TestDir;
+use code_parser::Options;
+use std::fs;
+use std::process::Command;
+
+const MANIFEST: &str = "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n";
+
+// 项目中所有函数的路径
+fn function_paths(analysis: &ProjectAnalysis) -> Vec<String> {
+    let mut paths: Vec<String> = analysis
+        .crates
+        .iter()
+        .flat_map(|analysis| &analysis.files)
+        .flat_map(|items| items.all_fns())
+        .map(|function| function.path.clone())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+#[test]
+fn test_targets_respect_ignore_dirs() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_dir("src/generated");
+    test_dir.create_dir("tests");
+    test_dir.create_file("Cargo.toml", MANIFEST);
+    test_dir.create_file("src/lib.rs", "mod shared;\nmod generated;\npub fn run() {}\n");
+    test_dir.create_file("src/main.rs", "mod shared;\nfn main() {}\n");
+    test_dir.create_file("src/shared.rs", "fn broken( {}\n");
+    test_dir.create_file("src/generated/mod.rs", "pub fn generated() {}\n");
+    test_dir.create_file("tests/it.rs", "fn it_works() {}\n");
+
+    let options = Options {
+        ignore_dirs: vec!["tests".to_string(), "generated".to_string()],
+        ..Options::default()
+    };
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &options);
+
+    // tests/ 中的测试目标和 generated/ 中的模块文件都被忽略
+    assert_eq!(function_paths(&analysis), ["crate::main", "crate::run"]);
+    assert_eq!(analysis.crates.len(), 2);
+    // lib 和 main 共用的文件无法解析，只报告一次
+    assert_eq!(analysis.diagnostics.len(), 1);
+    assert!(analysis.diagnostics[0].file.ends_with("src/shared.rs"));
+
+    let options = Options {
+        max_depth: Some(1),
+        ..Options::default()
+    };
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &options);
+    assert!(analysis.crates.is_empty());
+}
+
+#[test]
+fn test_items_in_fn_bodies_are_local() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file(
+        "src/lib.rs",
+        r#"
+pub struct Outer;
+
+pub fn outer() {
+    fn inner() {}
+    struct Local;
+    impl Local {
+        fn method(&self) {}
+    }
+    const LIMIT: usize = 3;
+}
+
+impl Outer {
+    pub fn run(&self) {
+        enum State { Idle }
+        fn helper() {}
+    }
+}
+"#,
+    );
+
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    assert_eq!(function_paths(&analysis), ["crate::Outer::run", "crate::outer"]);
+    let items = &analysis.crates[0].files[0];
+    let types: Vec<&str> = items.types.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(types, ["crate::Outer"]);
+    assert!(items.values.is_empty());
+    assert!(items.defs.iter().all(|def| def.name == "Outer" || def.name == "outer"));
+}
+
+#[test]
+fn test_impl_self_type_paths() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_dir("src/net");
+    test_dir.create_file(
+        "src/lib.rs",
+        r#"
+pub mod net;
+pub mod model;
+pub struct Foo;
+pub struct Bar;
+pub struct Baz;
+
+impl self::Baz {
+    pub fn baz(&self) {}
+}
+"#,
+    );
+    test_dir.create_file("src/model.rs", "pub struct Data;\n");
+    test_dir.create_file("src/net/mod.rs", "pub mod tcp;\n");
+    test_dir.create_file(
+        "src/net/tcp.rs",
+        r#"
+use super::super::Bar;
+use crate::model as m;
+
+impl super::super::Foo {
+    pub fn foo(&self) {}
+}
+
+impl Bar {
+    pub fn bar(&self) {}
+}
+
+impl m::Data {
+    pub fn data(&self) {}
+}
+
+impl crate::model::Data {
+    pub fn more(&self) {}
+}
+
+impl Clone for Bar {
+    fn clone(&self) -> Self { Bar }
+}
+
+pub struct Conn;
+
+impl Conn {
+    pub fn open() -> Self { Conn }
+}
+"#,
+    );
+
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    assert_eq!(
+        function_paths(&analysis),
+        [
+            "crate::Bar::bar",
+            "crate::Bar::clone",
+            "crate::Baz::baz",
+            "crate::Foo::foo",
+            "crate::model::Data::data",
+            "crate::model::Data::more",
+            "crate::net::tcp::Conn::open",
+        ]
+    );
+    let tcp = analysis.crates[0].files.iter().find(|items| items.module == "crate::net::tcp").unwrap();
+    let types: Vec<&str> = tcp.types.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(types, ["crate::Foo", "crate::Bar", "crate::model::Data", "crate::net::tcp::Conn"]);
+    assert_eq!(tcp.trait_impls[0].type_path, "crate::Bar");
+}
+
+#[test]
+fn test_exports_of_types_with_same_name() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file("Cargo.toml", MANIFEST);
+    test_dir.create_file(
+        "src/lib.rs",
+        r#"
+pub mod net;
+mod io;
+
+impl io::Error {
+    pub fn io_kind(&self) {}
+}
+
+impl net::Error {
+    pub fn net_kind(&self) {}
+}
+
+impl std::fmt::Display for io::Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
+}
+
+impl std::fmt::Display for net::Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
+}
+"#,
+    );
+    test_dir.create_file("src/io.rs", "pub struct Error;\n");
+    test_dir.create_file("src/net.rs", "pub struct Error;\n");
+
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    let lib = analysis.crates[0].files.iter().find(|items| items.module == "crate").unwrap();
+    let exported: Vec<(&str, bool)> = lib.all_fns().map(|function| (function.path.as_str(), function.exported)).collect();
+    assert_eq!(
+        exported,
+        [
+            ("crate::io::Error::io_kind", false),
+            ("crate::net::Error::net_kind", true),
+            ("crate::io::Error::fmt", false),
+            ("crate::net::Error::fmt", true),
+        ]
+    );
+    let net_error = lib.types.iter().find(|item| item.path == "crate::net::Error").unwrap();
+    assert_eq!(net_error.canonical_path.as_deref(), Some("demo::net::Error"));
+}
+
+#[test]
+fn test_implementors_by_trait_path() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file("Cargo.toml", MANIFEST);
+    test_dir.create_file(
+        "src/lib.rs",
+        r#"
+pub mod text;
+pub trait Render {}
+pub struct Page;
+impl Render for Page {}
+impl text::Render for Page {}
+"#,
+    );
+    test_dir.create_file("src/text.rs", "pub trait Render {}\npub struct Line;\nimpl Render for Line {}\n");
+    test_dir.create_file(
+        "src/main.rs",
+        r#"
+use demo::text;
+trait Render {}
+struct Cli;
+impl Render for Cli {}
+impl demo::Render for Cli {}
+impl text::Render for Cli {}
+fn main() {}
+"#,
+    );
+
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    let implementors = |kind: TargetKind| -> Vec<(String, String)> {
+        let analysis = analysis.crates.iter().find(|c| c.target.as_ref().unwrap().kind == kind).unwrap();
+        let traits = analysis.files.iter().flat_map(|items| &items.traits);
+        traits.map(|def| (def.path.clone(), def.implementors.join(", "))).collect()
+    };
+    let lib = implementors(TargetKind::Lib);
+    assert_eq!(lib[0], ("crate::Render".to_string(), "Page, Cli".to_string()));
+    assert_eq!(lib[1], ("crate::text::Render".to_string(), "Page, Line, Cli".to_string()));
+    // main 中的 Render 与 lib 中的同名 trait 互不相关
+    let bin = implementors(TargetKind::Bin);
+    assert_eq!(bin, [("crate::Render".to_string(), "Cli".to_string())]);
+}
+
+#[test]
+fn test_cfg_uses_default_features() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file("Cargo.toml", &format!("{}\n[features]\ndefault = [\"std\"]\nstd = []\nextra = []\n", MANIFEST));
+    test_dir.create_file(
+        "src/lib.rs",
+        r#"
+#[cfg(feature = "std")]
+pub fn with_std() {}
+#[cfg(feature = "extra")]
+pub fn with_extra() {}
+#[cfg(unix)]
+pub fn on_unix() {}
+#[cfg(not(unix))]
+pub fn not_unix() {}
+#[cfg(debug_assertions)]
+pub fn debug() {}
+#[cfg(all(unix, feature = "extra"))]
+pub fn unix_extra() {}
+"#,
+    );
+
+    // 没有给出任何选项时同样启用 default 特性，其他 cfg 条件未知，条目保留并标出条件
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    assert_eq!(
+        function_paths(&analysis),
+        ["crate::debug", "crate::not_unix", "crate::on_unix", "crate::with_std"]
+    );
+    let on_unix = analysis.crates[0].files[0].functions.iter().find(|f| f.path == "crate::on_unix").unwrap();
+    assert_eq!(on_unix.attrs.cfg, ["unix"]);
+    assert_eq!(analysis.crates[0].enabled_features.as_deref(), Some(&["default".to_string(), "std".to_string()][..]));
+
+    let options = Options {
+        features: vec!["extra".to_string()],
+        ..Options::default()
+    };
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &options);
+    assert_eq!(
+        function_paths(&analysis),
+        [
+            "crate::debug",
+            "crate::not_unix",
+            "crate::on_unix",
+            "crate::unix_extra",
+            "crate::with_extra",
+            "crate::with_std",
+        ]
+    );
+
+    // 只有通过 --cfg 给出的条件成立，not(unix) 的条目被去掉，debug_assertions 仍然未知
+    let options = Options {
+        no_default_features: true,
+        cfgs: vec!["unix".to_string()],
+        ..Options::default()
+    };
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &options);
+    assert_eq!(function_paths(&analysis), ["crate::debug", "crate::on_unix"]);
+}
+
+#[test]
+fn test_cfg_test_off_by_default() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file("Cargo.toml", MANIFEST);
+    test_dir.create_file(
+        "src/lib.rs",
+        r#"
+pub fn run() {}
+#[cfg(not(test))]
+pub fn not_test() {}
+#[cfg(any(test, unix))]
+pub fn test_or_unix() {}
+#[cfg(all(test, unix))]
+pub fn test_and_unix() {}
+#[cfg(doc)]
+pub fn doc_only() {}
+
+#[cfg(test)]
+mod tests {
+    pub fn helper() {}
+}
+
+#[cfg(test)]
+mod integration;
+"#,
+    );
+    test_dir.create_file("src/integration.rs", "pub fn setup() {}\n");
+
+    // test 和 doc 没有给出时一定不成立，测试模块不会作为 API 出现，也不会读取其文件
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    assert_eq!(function_paths(&analysis), ["crate::not_test", "crate::run", "crate::test_or_unix"]);
+    assert_eq!(analysis.crates[0].files.len(), 1);
+    let test_or_unix = analysis.crates[0].files[0].functions.iter().find(|f| f.path == "crate::test_or_unix").unwrap();
+    assert_eq!(test_or_unix.attrs.cfg, ["any(test, unix)"]);
+
+    let options = Options {
+        cfgs: vec!["test".to_string()],
+        ..Options::default()
+    };
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &options);
+    assert_eq!(
+        function_paths(&analysis),
+        [
+            "crate::integration::setup",
+            "crate::run",
+            "crate::test_and_unix",
+            "crate::test_or_unix",
+            "crate::tests::helper",
+        ]
+    );
+}
+
+#[test]
+fn test_public_paths_use_crate_name() {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file("Cargo.toml", MANIFEST);
+    test_dir.create_file(
+        "src/lib.rs",
+        r#"
+pub mod shapes {
+    pub struct Circle;
+}
+pub use shapes::Circle;
+
+mod util {
+    pub fn helper() {}
+}
+pub use util::helper as assist;
+
+pub fn run() {}
+"#,
+    );
+
+    // 公开路径与 rustdoc 显示的一致，以 Cargo.toml 中的 crate 名称开头
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    let lib = &analysis.crates[0].files[0];
+    let circle = lib.types.iter().find(|item| item.path == "crate::shapes::Circle").unwrap();
+    assert_eq!(circle.public_paths, ["demo::Circle", "demo::shapes::Circle"]);
+    assert_eq!(circle.canonical_path.as_deref(), Some("demo::Circle"));
+    let helper = lib.functions.iter().find(|f| f.path == "crate::util::helper").unwrap();
+    assert_eq!(helper.public_paths, ["demo::assist"]);
+    let run = lib.functions.iter().find(|f| f.path == "crate::run").unwrap();
+    assert_eq!(run.public_paths, ["demo::run"]);
+
+    // rustAPI 只为能以定义处以外的路径访问的条目列出公开路径
+    let home = TestDir::new();
+    let log_path = home.path.join("fns_log");
+    let output = Command::new(env!("CARGO_BIN_EXE_rustAPI"))
+        .arg(&test_dir.path)
+        .arg(&log_path)
+        .env("HOME", &home.path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "rustAPI 执行失败");
+    let log = fs::read_to_string(&log_path).unwrap();
+    assert!(log.contains("公开路径: demo::Circle (规范), demo::shapes::Circle\n"));
+    assert!(log.contains("公开路径: demo::assist (规范)\n"));
+    assert_eq!(log.matches("公开路径").count(), 2);
+}
@@ -0,0 +1,89 @@
+use code_parser::rust::signature::{render_tokens, FnSignature, ReceiverKind};
+
+fn signature(source: &str) -> FnSignature {
+    let method: syn::ImplItemFn = syn::parse_str(source).unwrap();
+    FnSignature::from_syn(&method.sig)
+}
+
+// 解析后重新渲染的签名
+fn render(source: &str) -> String {
+    signature(source).render()
+}
+
+#[test]
+fn test_receivers() {
+    let cases = [
+        ("fn get(&self) {}", ReceiverKind::Ref, "&self"),
+        ("fn set<'a>(&'a mut self) {}", ReceiverKind::RefMut, "&'a mut self"),
+        ("fn take(mut self) {}", ReceiverKind::Value, "mut self"),
+        ("fn boxed(self: Box<Self>) {}", ReceiverKind::Boxed, "self: Box<Self>"),
+        ("fn pinned(self: Pin<&mut Self>) {}", ReceiverKind::Other, "self: Pin<&mut Self>"),
+        ("fn explicit(self: &mut Self) {}", ReceiverKind::RefMut, "self: &mut Self"),
+    ];
+    for (source, kind, text) in cases {
+        let receiver = signature(source).receiver.unwrap();
+        assert_eq!((receiver.kind, receiver.text.as_str()), (kind, text), "{}", source);
+    }
+    assert!(signature("fn new() -> Self { Self }").receiver.is_none());
+}
+
+#[test]
+fn test_generics_and_bounds() {
+    let sig = signature("fn get<'a, 'b: 'a, T: Clone + ?Sized, const N: usize>(&'a self, key: &'b str) -> Option<&'a T> {}");
+    assert_eq!(sig.lifetimes, ["'a", "'b: 'a"]);
+    assert_eq!(sig.generics, ["T: Clone + ?Sized", "const N: usize"]);
+    assert_eq!(
+        sig.render(),
+        "fn get<'a, 'b: 'a, T: Clone + ?Sized, const N: usize>(&'a self, key: &'b str) -> Option<&'a T>"
+    );
+
+    assert_eq!(
+        render("fn merge<K, V>(maps: Vec<HashMap<K, V>>) -> HashMap<K, V> where K: Eq + Hash, for<'x> V: Fn(&'x str) {}"),
+        "fn merge<K, V>(maps: Vec<HashMap<K, V>>) -> HashMap<K, V> where K: Eq + Hash, for<'x> V: Fn(&'x str)"
+    );
+}
+
+#[test]
+fn test_impl_and_dyn_trait() {
+    assert_eq!(
+        render("fn iter(&self) -> impl Iterator<Item = &u8> + '_ {}"),
+        "fn iter(&self) -> impl Iterator<Item = &u8> + '_"
+    );
+    assert_eq!(
+        render("fn run(task: Box<dyn FnOnce() -> Result<(), Box<dyn Error + Send>> + Send + 'static>) {}"),
+        "fn run(task: Box<dyn FnOnce() -> Result<(), Box<dyn Error + Send>> + Send + 'static>)"
+    );
+    assert_eq!(
+        render("fn apply(f: &mut dyn FnMut(&[u8]) -> usize, path: impl AsRef<::std::path::Path>) {}"),
+        "fn apply(f: &mut dyn FnMut(&[u8]) -> usize, path: impl AsRef<::std::path::Path>)"
+    );
+    assert_eq!(render("fn raw(p: *const u8, q: *mut [u8; 4]) {}"), "fn raw(p: *const u8, q: *mut [u8; 4])");
+}
+
+#[test]
+fn test_qualifiers_and_abi() {
+    let sig = signature(r#"unsafe extern "C" fn callback(data: *mut c_void, len: usize) -> i32 {}"#);
+    assert!(sig.is_unsafe);
+    assert_eq!(sig.abi.as_deref(), Some("C"));
+    assert_eq!(sig.render(), r#"unsafe extern "C" fn callback(data: *mut c_void, len: usize) -> i32"#);
+
+    // 省略 ABI 时为 "C"
+    assert_eq!(render("extern fn plain() {}"), r#"extern "C" fn plain()"#);
+    assert_eq!(render("pub const async fn ready() -> u8 {}"), "const async fn ready() -> u8");
+    assert_eq!(
+        render(r#"unsafe extern "system" fn proc(hwnd: HWND, (a, b): (u32, u32)) {}"#),
+        r#"unsafe extern "system" fn proc(hwnd: HWND, (a, b): (u32, u32))"#
+    );
+}
+
+#[test]
+fn test_const_generic_closures() {
+    assert_eq!(
+        render("fn table<const N: usize>(f: impl Fn(usize) -> u8) -> [u8; { N * 2 }] {}"),
+        "fn table<const N: usize>(f: impl Fn(usize) -> u8) -> [u8; { N * 2 }]"
+    );
+    let ty: syn::Type = syn::parse_str("Checked<{ |x: u8| x + 1 }, 3>").unwrap();
+    assert_eq!(render_tokens(&ty), "Checked<{ |x: u8| x + 1 }, 3>");
+    let ty: syn::Type = syn::parse_str("Table<{ (|| N)() }>").unwrap();
+    assert_eq!(render_tokens(&ty), "Table<{ (|| N)() }>");
+}
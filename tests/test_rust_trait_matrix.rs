@@ -0,0 +1,229 @@
+use code_parser::rust::project::ProjectAnalysis;
+use code_parser::rust::trait_matrix::TraitMatrix;
+use code_parser::test_utils::{TestDir, assert_json_eq};
+use code_parser::Options;
+
+const MANIFEST: &str = "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n";
+
+// 显式 impl、derive、互相依赖的 blanket impl、否定实现，以及与标准库 trait 同名的本地 trait
+fn build_matrix() -> TraitMatrix {
+    let test_dir = TestDir::new();
+    test_dir.create_dir("src");
+    test_dir.create_file("Cargo.toml", MANIFEST);
+    test_dir.create_file("src/lib.rs", r#"
+use std::fmt;
+
+pub mod shapes;
+
+pub trait Named {
+    fn name(&self) -> String;
+}
+
+pub trait Describe {}
+
+impl<T: Named + fmt::Debug> Describe for T {}
+
+pub trait Report {}
+
+impl<T> Report for T where T: Describe + ?Sized {}
+"#);
+    test_dir.create_file("src/shapes.rs", r#"
+use std::fmt::{self, Debug};
+
+use crate::Named;
+
+pub trait Display {}
+
+#[derive(Debug, Clone)]
+pub struct Circle;
+
+impl Named for Circle {
+    fn name(&self) -> String {
+        "circle".to_string()
+    }
+}
+
+impl Display for Circle {}
+
+impl fmt::Display for Circle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "circle")
+    }
+}
+
+#[derive(Clone)]
+pub struct Square;
+
+impl Named for Square {
+    fn name(&self) -> String {
+        "square".to_string()
+    }
+}
+
+pub struct Handle(*mut u8);
+
+impl !Send for Handle {}
+
+impl Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("handle")
+    }
+}
+
+impl Named for Handle {
+    fn name(&self) -> String {
+        "handle".to_string()
+    }
+}
+"#);
+
+    let analysis = ProjectAnalysis::analyze(&test_dir.path, &Options::default());
+    TraitMatrix::build(
+        analysis
+            .crates
+            .iter()
+            .map(|c| (c.target.as_ref().map_or("crate", |target| target.name.as_str()), c.files.as_slice())),
+    )
+}
+
+#[test]
+fn test_trait_matrix_json() {
+    let matrix = build_matrix();
+    // derive 的 Debug 和 `impl Debug`（use std::fmt::Debug）是同一个 trait，
+    // 本地的 Display 和 `impl fmt::Display` 是两个 trait
+    let expected = r#"{
+  "types": {
+    "demo::shapes::Circle": {
+      "demo::Describe": "blanket",
+      "demo::Named": "impl",
+      "demo::Report": "blanket",
+      "demo::shapes::Display": "impl",
+      "std::clone::Clone": "derive",
+      "std::fmt::Debug": "derive",
+      "std::fmt::Display": "impl"
+    },
+    "demo::shapes::Handle": {
+      "demo::Describe": "blanket",
+      "demo::Named": "impl",
+      "demo::Report": "blanket",
+      "std::fmt::Debug": "impl",
+      "std::marker::Send": "negative"
+    },
+    "demo::shapes::Square": {
+      "demo::Named": "impl",
+      "std::clone::Clone": "derive"
+    }
+  },
+  "traits": {
+    "demo::Describe": {
+      "name": "Describe",
+      "local": true,
+      "implementors": [
+        {"type": "demo::shapes::Circle", "via": "blanket"},
+        {"type": "demo::shapes::Handle", "via": "blanket"}
+      ],
+      "blanket": ["impl<T: Named + fmt::Debug> Describe for T"]
+    },
+    "demo::Named": {
+      "name": "Named",
+      "local": true,
+      "implementors": [
+        {"type": "demo::shapes::Circle", "via": "impl"},
+        {"type": "demo::shapes::Handle", "via": "impl"},
+        {"type": "demo::shapes::Square", "via": "impl"}
+      ],
+      "blanket": []
+    },
+    "demo::Report": {
+      "name": "Report",
+      "local": true,
+      "implementors": [
+        {"type": "demo::shapes::Circle", "via": "blanket"},
+        {"type": "demo::shapes::Handle", "via": "blanket"}
+      ],
+      "blanket": ["impl<T> Report for T where T: Describe + ?Sized"]
+    },
+    "demo::shapes::Display": {
+      "name": "Display",
+      "local": true,
+      "implementors": [
+        {"type": "demo::shapes::Circle", "via": "impl"}
+      ],
+      "blanket": []
+    },
+    "std::clone::Clone": {
+      "name": "Clone",
+      "local": false,
+      "implementors": [
+        {"type": "demo::shapes::Circle", "via": "derive"},
+        {"type": "demo::shapes::Square", "via": "derive"}
+      ],
+      "blanket": []
+    },
+    "std::fmt::Debug": {
+      "name": "Debug",
+      "local": false,
+      "implementors": [
+        {"type": "demo::shapes::Circle", "via": "derive"},
+        {"type": "demo::shapes::Handle", "via": "impl"}
+      ],
+      "blanket": []
+    },
+    "std::fmt::Display": {
+      "name": "Display",
+      "local": false,
+      "implementors": [
+        {"type": "demo::shapes::Circle", "via": "impl"}
+      ],
+      "blanket": []
+    },
+    "std::marker::Send": {
+      "name": "Send",
+      "local": false,
+      "implementors": [
+        {"type": "demo::shapes::Handle", "via": "negative"}
+      ],
+      "blanket": []
+    }
+  }
+}"#;
+    assert_json_eq(&matrix.to_json().unwrap(), expected);
+}
+
+#[test]
+fn test_trait_matrix_markdown() {
+    let matrix = build_matrix();
+    // 列名只用 trait 名称，同名的 trait 用完整路径区分
+    let expected = r#"# trait 实现矩阵
+
+| 类型 | Describe | Named | Report | demo::shapes::Display | Clone | Debug | std::fmt::Display | Send |
+|---|---|---|---|---|---|---|---|---|
+| demo::shapes::Circle | blanket | impl | blanket | impl | derive | derive | impl |  |
+| demo::shapes::Handle | blanket | impl | blanket |  |  | impl |  | !impl |
+| demo::shapes::Square |  | impl |  |  | derive |  |  |  |
+
+## trait 实现者
+
+- **Describe** (`demo::Describe`): `demo::shapes::Circle` (blanket), `demo::shapes::Handle` (blanket)
+  - `impl<T: Named + fmt::Debug> Describe for T`
+- **Named** (`demo::Named`): `demo::shapes::Circle` (impl), `demo::shapes::Handle` (impl), `demo::shapes::Square` (impl)
+- **Report** (`demo::Report`): `demo::shapes::Circle` (blanket), `demo::shapes::Handle` (blanket)
+  - `impl<T> Report for T where T: Describe + ?Sized`
+- **Display** (`demo::shapes::Display`): `demo::shapes::Circle` (impl)
+- **Clone** (`std::clone::Clone`): `demo::shapes::Circle` (derive), `demo::shapes::Square` (derive)
+- **Debug** (`std::fmt::Debug`): `demo::shapes::Circle` (derive), `demo::shapes::Handle` (impl)
+- **Display** (`std::fmt::Display`): `demo::shapes::Circle` (impl)
+- **Send** (`std::marker::Send`): `demo::shapes::Handle` (!impl)
+"#;
+    assert_eq!(matrix.to_markdown(), expected);
+}
+
+#[test]
+fn test_trait_matrix_html() {
+    let html = build_matrix().to_html();
+    assert!(html.contains("<tr><th>类型</th><th>Describe</th><th>Named</th><th>Report</th><th>demo::shapes::Display</th><th>Clone</th><th>Debug</th><th>std::fmt::Display</th><th>Send</th></tr>\n"));
+    assert!(html.contains("<tr><td>demo::shapes::Handle</td><td>blanket</td><td>impl</td><td>blanket</td><td></td><td></td><td>impl</td><td></td><td>!impl</td></tr>\n"));
+    // blanket impl 头部经过转义
+    assert!(html.contains("<li><b>Describe</b> (<code>demo::Describe</code>): demo::shapes::Circle (blanket), demo::shapes::Handle (blanket)<ul><li><code>impl&lt;T: Named + fmt::Debug&gt; Describe for T</code></li></ul></li>\n"));
+    assert!(html.contains("<li><b>Send</b> (<code>std::marker::Send</code>): demo::shapes::Handle (!impl)</li>\n"));
+}
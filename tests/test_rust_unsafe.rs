@@ -0,0 +1,126 @@
+use code_parser::rust::model::{UnsafeKind, UnsafeSite};
+use code_parser::rust::unsafe_audit::{Baseline, BaselineEntry, UnsafeVisitor};
+
+fn collect(source: &str) -> Vec<UnsafeSite> {
+    let syntax = syn::parse_file(source).unwrap();
+    UnsafeVisitor::collect(source, vec!["crate".to_string()], &syntax)
+}
+
+// 每处 unsafe 代码的种类、所在函数、行号以及是否有 SAFETY 注释
+fn summary(sites: &[UnsafeSite]) -> Vec<(UnsafeKind, &str, usize, bool)> {
+    sites
+        .iter()
+        .map(|site| (site.kind, site.enclosing.as_str(), site.location.start_line, site.has_safety_comment))
+        .collect()
+}
+
+#[test]
+fn test_safety_comments() {
+    let source = r#"
+// SAFETY: 调用方保证 p 有效
+#[inline]
+#[allow(clippy::missing_safety_doc)]
+unsafe fn documented(p: *const u8) -> u8 {
+    *p
+}
+
+/// 读取一个字节
+///
+/// # Safety
+///
+/// p 必须有效
+pub unsafe fn doc_section(p: *const u8) -> u8 {
+    *p
+}
+
+fn blocks(p: *const u8) -> u8 {
+    // SAFETY: p 来自 Box::into_raw
+    let a = unsafe { *p };
+    // 这里没有说明
+    let b = unsafe { *p };
+    a + b
+}
+
+// SAFETY: 只有一个线程访问
+#[cfg(unix)]
+
+unsafe impl Send for Handle {}
+"#;
+    assert_eq!(
+        summary(&collect(source)),
+        [
+            (UnsafeKind::Fn, "crate::documented", 5, true),
+            (UnsafeKind::RawDeref, "crate::documented", 6, true),
+            (UnsafeKind::Fn, "crate::doc_section", 14, true),
+            (UnsafeKind::RawDeref, "crate::doc_section", 15, true),
+            (UnsafeKind::Block, "crate::blocks", 20, true),
+            (UnsafeKind::RawDeref, "crate::blocks", 20, true),
+            (UnsafeKind::Block, "crate::blocks", 22, false),
+            (UnsafeKind::RawDeref, "crate::blocks", 22, false),
+            // 空行打断了注释与 unsafe impl 之间的联系
+            (UnsafeKind::Impl, "crate", 29, false),
+        ]
+    );
+}
+
+#[test]
+fn test_pointer_methods_need_pointer_receiver() {
+    let source = r#"
+fn arithmetic(p: *const u32, n: Wrapper, v: &[u32]) -> u32 {
+    unsafe {
+        let a = *p.add(1);
+        let b = *p.cast::<u8>().add(2) as u32;
+        let c = *n.add(1);
+        let d = *v.as_ptr().add(3);
+        a + b + c + d
+    }
+}
+"#;
+    let lines: Vec<usize> = collect(source)
+        .iter()
+        .filter(|site| site.kind == UnsafeKind::RawDeref)
+        .map(|site| site.location.start_line)
+        .collect();
+    // n 不是裸指针，`*n.add(1)` 是普通的解引用
+    assert_eq!(lines, [4, 5, 7]);
+}
+
+fn entry(enclosing: &str, line: usize) -> BaselineEntry {
+    BaselineEntry {
+        root: "src/lib.rs".to_string(),
+        file: "src/lib.rs".to_string(),
+        kind: "unsafe block".to_string(),
+        enclosing: enclosing.to_string(),
+        line,
+    }
+}
+
+#[test]
+fn test_baseline_diff() {
+    let baseline = Baseline {
+        entries: vec![entry("crate::read", 10), entry("crate::read", 12), entry("crate::write", 20)],
+    };
+
+    // 行号变化不算新增
+    let moved = Baseline {
+        entries: vec![entry("crate::read", 30), entry("crate::read", 32), entry("crate::write", 40)],
+    };
+    let diff = baseline.diff(&moved);
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+
+    // 同一位置多出来的记录算新增，报告靠后的一条
+    let current = Baseline {
+        entries: vec![entry("crate::read", 10), entry("crate::read", 12), entry("crate::read", 14)],
+    };
+    let diff = baseline.diff(&current);
+    assert_eq!(diff.added, [entry("crate::read", 14)]);
+    assert_eq!(diff.removed, [entry("crate::write", 20)]);
+
+    // 同一位置少了的记录算消失
+    let current = Baseline {
+        entries: vec![entry("crate::read", 12), entry("crate::write", 20), entry("crate::write", 22)],
+    };
+    let diff = baseline.diff(&current);
+    assert_eq!(diff.added, [entry("crate::write", 22)]);
+    assert_eq!(diff.removed, [entry("crate::read", 12)]);
+}
@@ -0,0 +1,173 @@
+use code_parser::rust::report::RustReport;
+use code_parser::rust::visibility::Visibility;
+use code_parser::test_utils::TestDir;
+use code_parser::Options;
+use serde_json::Value;
+use std::fs;
+use std::process::Command;
+
+const MANIFEST: &str = "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n";
+
+// 各种受限可见性，以及私有模块中通过 pub use 重导出的条目
+fn create_crate(test_dir: &TestDir) {
+    test_dir.create_dir("src");
+    test_dir.create_dir("src/net");
+    test_dir.create_file("Cargo.toml", MANIFEST);
+    test_dir.create_file("src/lib.rs", r#"
+pub mod net;
+mod inner;
+
+pub use inner::{reachable, Config};
+
+pub fn api() {}
+pub(crate) fn internal() {}
+fn private() {}
+"#);
+    test_dir.create_file("src/net.rs", r#"
+pub mod tcp;
+
+pub fn connect() {}
+pub(super) fn to_parent() {}
+pub(in crate::net) fn in_net() {}
+pub(self) fn own() {}
+"#);
+    test_dir.create_file("src/net/tcp.rs", r#"
+pub fn open() {}
+pub(in crate::net) fn restricted() {}
+"#);
+    test_dir.create_file("src/inner.rs", r#"
+pub struct Config {
+    pub name: String,
+    pub(crate) secret: String,
+}
+
+pub fn reachable() {}
+pub fn unreachable() {}
+"#);
+}
+
+// 各文件中函数的路径、可见性和是否属于公开 API
+fn functions(report: &RustReport) -> Vec<(&str, &str, bool)> {
+    report
+        .files()
+        .flat_map(|file| &file.functions)
+        .map(|function| (function.path.as_str(), function.visibility.as_str(), function.exported))
+        .collect()
+}
+
+#[test]
+fn test_visibility_from_syn() {
+    let cases = [
+        ("pub", Visibility::Public, "pub"),
+        ("pub(crate)", Visibility::Crate, "pub(crate)"),
+        ("pub(super)", Visibility::Super, "pub(super)"),
+        ("pub(in crate::net)", Visibility::Restricted("crate::net".to_string()), "pub(in crate::net)"),
+        ("pub(self)", Visibility::Private, ""),
+        ("", Visibility::Private, ""),
+    ];
+    for (source, expected, rendered) in cases {
+        let vis = syn::parse_str::<syn::Visibility>(source).unwrap();
+        let vis = Visibility::from_syn(&vis);
+        assert_eq!(vis, expected, "{}", source);
+        assert_eq!(vis.as_str(), rendered);
+        assert_eq!(vis.is_public(), source == "pub");
+    }
+}
+
+#[test]
+fn test_visibility_and_exports() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+
+    // 只有 pub 且从 crate 根可以到达的条目属于公开 API，受限可见性的条目都不是
+    let report = code_parser::analyze_rust(&test_dir.path, &Options::default()).unwrap();
+    assert_eq!(
+        functions(&report),
+        [
+            ("crate::api", "pub", true),
+            ("crate::internal", "pub(crate)", false),
+            ("crate::private", "private", false),
+            ("crate::net::connect", "pub", true),
+            ("crate::net::to_parent", "pub(super)", false),
+            ("crate::net::in_net", "pub(in crate::net)", false),
+            ("crate::net::own", "private", false),
+            ("crate::net::tcp::open", "pub", true),
+            ("crate::net::tcp::restricted", "pub(in crate::net)", false),
+            ("crate::inner::reachable", "pub", true),
+            ("crate::inner::unreachable", "pub", false),
+        ]
+    );
+
+    // 私有模块中的条目通过 pub use 到达，公开路径是重导出的路径
+    let inner = report.files().find(|file| file.module == "crate::inner").unwrap();
+    assert_eq!(inner.functions[0].public_paths, ["demo::reachable"]);
+    let config = &inner.types[0];
+    assert_eq!((config.visibility.as_str(), config.exported), ("pub", true));
+    assert_eq!(config.public_paths, ["demo::Config"]);
+    let fields: Vec<(&str, &str)> = config.fields.iter().map(|f| (f.name.as_str(), f.visibility.as_str())).collect();
+    assert_eq!(fields, [("name", "pub"), ("secret", "pub(crate)")]);
+}
+
+#[test]
+fn test_public_only() {
+    let test_dir = TestDir::new();
+    create_crate(&test_dir);
+
+    let options = Options {
+        public_only: true,
+        ..Options::default()
+    };
+    let report = code_parser::analyze_rust(&test_dir.path, &options).unwrap();
+    assert_eq!(
+        functions(&report),
+        [
+            ("crate::api", "pub", true),
+            ("crate::net::connect", "pub", true),
+            ("crate::net::tcp::open", "pub", true),
+            ("crate::inner::reachable", "pub", true),
+        ]
+    );
+    // 非 pub 字段也被去掉
+    let inner = report.files().find(|file| file.module == "crate::inner").unwrap();
+    let fields: Vec<&str> = inner.types[0].fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(fields, ["name"]);
+
+    // 命令行的 --public-only 同样只输出公开 API
+    let home = TestDir::new();
+    let output = Command::new(env!("CARGO_BIN_EXE_code-parser"))
+        .arg("analyze")
+        .arg("--lang")
+        .arg("rust")
+        .arg("--public-only")
+        .arg(&test_dir.path)
+        .env("HOME", &home.path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "解析器执行失败");
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut names: Vec<&str> = report["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|file| file["functions"].as_array().unwrap())
+        .map(|function| function["name"].as_str().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["api", "connect", "open", "reachable"]);
+
+    let log_path = home.path.join("fns_log");
+    let output = Command::new(env!("CARGO_BIN_EXE_rustAPI"))
+        .arg(&test_dir.path)
+        .arg(&log_path)
+        .arg("--public-only")
+        .env("HOME", &home.path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "rustAPI 执行失败");
+    let log = fs::read_to_string(&log_path).unwrap();
+    assert!(log.contains("  - crate::inner::reachable: pub fn reachable()"));
+    for hidden in ["internal", "private", "unreachable", "to_parent", "in_net", "own", "restricted"] {
+        assert!(!log.contains(&format!("fn {}(", hidden)), "{}", hidden);
+    }
+    assert!(!log.contains("secret"));
+}
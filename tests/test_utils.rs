@@ -30,6 +30,12 @@ impl TestDir {
     }
 }
 
+impl Default for TestDir {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Drop for TestDir {
     fn drop(&mut self) {
         fs::remove_dir_all(&self.path).unwrap();